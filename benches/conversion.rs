@@ -0,0 +1,176 @@
+// Throughput of the hex and base64 conversions on 1 MiB of input. The crate's functions now wrap
+// the table-driven code, so each is measured against a baseline copy of the older conversion,
+// which builds up Vecs of digits, chars or 6-bit values a character at a time.
+
+use std::hint::black_box;
+use std::io::{BufReader, Read, Write};
+use std::time::{Duration, Instant};
+
+use cryptopals::conversion::base64::{
+    base64_to_bytes, base_64_character_to_byte, bytes_to_base64, Alphabet, Base64Config,
+    Base64Writer,
+};
+use cryptopals::conversion::hex::{
+    byte_to_ascii_hex_pair, bytes_to_hex_string, decode_hex_into, encode_hex_into,
+    hex_string_to_bytes, try_hex_to_bytes, OddLength,
+};
+use cryptopals::random::Rng;
+
+const INPUT_SIZE: usize = 1 << 20;
+const MINIMUM_TIME: Duration = Duration::from_millis(500);
+
+// Runs the function until enough time has passed to trust the average, and reports MiB/s of
+// input processed.
+fn measure<F: FnMut()>(name: &str, input_size: usize, mut function: F) {
+    function();
+
+    let start = Instant::now();
+    let mut iterations = 0;
+    while start.elapsed() < MINIMUM_TIME {
+        function();
+        iterations += 1;
+    }
+
+    let seconds = start.elapsed().as_secs_f64() / iterations as f64;
+    let mebibytes = input_size as f64 / (1 << 20) as f64;
+    println!(
+        "{:<40} {:>10.3} ms {:>10.1} MiB/s",
+        name,
+        1000.0 * seconds,
+        mebibytes / seconds
+    );
+}
+
+// What hex_string_to_bytes did before it had a fast path.
+fn baseline_hex_to_bytes(hex_string: &str) -> Vec<u8> {
+    try_hex_to_bytes(hex_string, OddLength::PadLeft).unwrap_or_else(|error| panic!("{}", error))
+}
+
+fn baseline_bytes_to_hex(bytes: &[u8]) -> String {
+    let result: Vec<[char; 2]> = bytes.iter().map(|b| byte_to_ascii_hex_pair(*b)).collect();
+
+    result.iter().flatten().collect()
+}
+
+#[rustfmt::skip]
+fn baseline_bytes_to_base64(bytes: &[u8]) -> String {
+    let padding_count = [0, 2, 1][bytes.len() % 3];
+    let padded_bytes = [bytes, &[0, 0][..padding_count]].concat();
+    let mut reader = BufReader::new(padded_bytes.as_slice());
+
+    let mut working_buffer = [0; 3];
+    let mut result: Vec<u8> = Vec::new();
+    while let Ok(()) = reader.read_exact(&mut working_buffer) {
+        result.push((working_buffer[0] & 0b1111_1100) >> 2);
+        result.push((working_buffer[0] & 0b0000_0011) << 4 | (working_buffer[1] & 0b1111_0000) >> 4);
+        result.push((working_buffer[1] & 0b0000_1111) << 2 | (working_buffer[2] & 0b1100_0000) >> 6);
+        result.push(working_buffer[2] & 0b0011_1111);
+    }
+
+    let end = result.len();
+    result
+        .into_iter()
+        .enumerate()
+        .map(|(index, c)| {
+            if index >= end - padding_count {
+                '='
+            } else {
+                char::from(Alphabet::STANDARD.symbol(c))
+            }
+        })
+        .collect()
+}
+
+#[rustfmt::skip]
+fn baseline_base64_to_bytes(base64: &str) -> Vec<u8> {
+    let bytes: Vec<u8> = base64
+        .bytes()
+        .filter(|c| *c != b'\n' && *c != b'=')
+        .map(base_64_character_to_byte)
+        .collect();
+
+    let padding_count = match bytes.len() % 4 {
+        0 => 0,
+        2 => 2,
+        3 => 1,
+        _ => panic!("Invalid base64"),
+    };
+    let padded_bytes = [bytes, vec![0; padding_count]].concat();
+    let mut reader = BufReader::new(padded_bytes.as_slice());
+
+    let mut working_buffer = [0; 4];
+    let mut result = Vec::new();
+    while let Ok(()) = reader.read_exact(&mut working_buffer) {
+        result.push(working_buffer[0] << 2 | (working_buffer[1] & 0b0011_0000) >> 4);
+        result.push((working_buffer[1] & 0b0000_1111) << 4 | (working_buffer[2] & 0b0011_1100) >> 2);
+        result.push((working_buffer[2] & 0b0000_0011) << 6 | working_buffer[3]);
+    }
+
+    result.truncate(result.len() - padding_count);
+    result
+}
+
+fn main() {
+    let bytes = Rng::new(48).bytes(INPUT_SIZE);
+    let hex = bytes_to_hex_string(&bytes);
+    let config = Base64Config::STANDARD;
+    let base64 = config.encode(&bytes);
+
+    let mut hex_buffer = vec![0; 2 * INPUT_SIZE];
+    let mut byte_buffer = vec![0; INPUT_SIZE + 2];
+    let mut base64_buffer = vec![0; config.encoded_length(INPUT_SIZE)];
+
+    assert_eq!(baseline_hex_to_bytes(&hex), bytes);
+    assert_eq!(baseline_bytes_to_hex(&bytes), hex);
+    assert_eq!(baseline_base64_to_bytes(&base64), bytes);
+    assert_eq!(baseline_bytes_to_base64(&bytes), base64);
+
+    println!("hex, {} bytes", INPUT_SIZE);
+    measure("hex_string_to_bytes (baseline)", hex.len(), || {
+        black_box(baseline_hex_to_bytes(black_box(&hex)));
+    });
+    measure("hex_string_to_bytes", hex.len(), || {
+        black_box(hex_string_to_bytes(black_box(&hex)));
+    });
+    measure("decode_hex_into", hex.len(), || {
+        black_box(decode_hex_into(black_box(hex.as_bytes()), &mut byte_buffer).unwrap());
+    });
+    measure("bytes_to_hex_string (baseline)", INPUT_SIZE, || {
+        black_box(baseline_bytes_to_hex(black_box(&bytes)));
+    });
+    measure("bytes_to_hex_string", INPUT_SIZE, || {
+        black_box(bytes_to_hex_string(black_box(&bytes)));
+    });
+    measure("encode_hex_into", INPUT_SIZE, || {
+        black_box(encode_hex_into(black_box(&bytes), &mut hex_buffer));
+    });
+
+    println!("base64, {} bytes", INPUT_SIZE);
+    measure("base64_to_bytes (baseline)", base64.len(), || {
+        black_box(baseline_base64_to_bytes(black_box(&base64)));
+    });
+    measure("base64_to_bytes (lenient)", base64.len(), || {
+        black_box(base64_to_bytes(black_box(&base64)));
+    });
+    measure("Base64Config::decode_into", base64.len(), || {
+        black_box(
+            config
+                .decode_into(black_box(base64.as_bytes()), &mut byte_buffer)
+                .unwrap(),
+        );
+    });
+    measure("bytes_to_base64 (baseline)", INPUT_SIZE, || {
+        black_box(baseline_bytes_to_base64(black_box(&bytes)));
+    });
+    measure("bytes_to_base64", INPUT_SIZE, || {
+        black_box(bytes_to_base64(black_box(&bytes)));
+    });
+    measure("Base64Writer", INPUT_SIZE, || {
+        let mut writer = Base64Writer::new(Vec::with_capacity(base64.len()), config);
+        writer.write_all(black_box(&bytes)).unwrap();
+        black_box(writer.finish().unwrap());
+    });
+    measure("Base64Config::encode_into", INPUT_SIZE, || {
+        black_box(config.encode_into(black_box(&bytes), &mut base64_buffer));
+    });
+}
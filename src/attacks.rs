@@ -0,0 +1,19 @@
+pub mod bleichenbacher;
+pub mod cbc_mac;
+pub mod compression;
+pub mod dh_mitm;
+pub mod dh_subgroup;
+pub mod dsa;
+pub mod ec_invalid_curve;
+pub mod ec_twist;
+pub mod ecdsa_biased_nonce;
+pub mod gcm;
+pub mod iterated_hash;
+pub mod key_selection;
+pub mod md4_collision;
+pub mod pkcs1_forgery;
+pub mod rc4_bias;
+pub mod rsa_broadcast;
+pub mod rsa_parity;
+pub mod rsa_unpadded;
+pub mod srp;
@@ -0,0 +1,147 @@
+use crate::math::bigint::BigUint;
+use crate::oracles::rsa::RsaPaddingOracle;
+
+pub struct PaddingOracleResult {
+    pub plaintext: BigUint,
+    pub oracle_calls: usize,
+}
+
+fn ceil_div(numerator: &BigUint, denominator: &BigUint) -> BigUint {
+    let (quotient, remainder) = numerator.divrem(denominator);
+    if remainder.is_zero() {
+        quotient
+    } else {
+        &quotient + &BigUint::one()
+    }
+}
+
+struct CountingOracle<'a, O> {
+    oracle: &'a mut O,
+    ciphertext: BigUint,
+    calls: usize,
+}
+
+impl<'a, O: RsaPaddingOracle> CountingOracle<'a, O> {
+    // Whether c * s^e decrypts to a conforming plaintext.
+    fn is_conforming(&mut self, s: &BigUint) -> bool {
+        let public_key = self.oracle.public_key().clone();
+        let ciphertext = &(&self.ciphertext * &public_key.encrypt(s)) % &public_key.n;
+        self.calls += 1;
+        self.oracle.is_padding_conforming(&ciphertext)
+    }
+
+    fn search_from(&mut self, mut s: BigUint) -> BigUint {
+        while !self.is_conforming(&s) {
+            s = &s + &BigUint::one();
+        }
+        s
+    }
+}
+
+// Merges the intervals into a sorted list of disjoint ones.
+fn union(mut intervals: Vec<(BigUint, BigUint)>) -> Vec<(BigUint, BigUint)> {
+    intervals.sort();
+
+    let mut merged: Vec<(BigUint, BigUint)> = Vec::new();
+    for (a, b) in intervals {
+        match merged.last_mut() {
+            Some((_, last_b)) if a <= *last_b => {
+                if b > *last_b {
+                    *last_b = b;
+                }
+            }
+            _ => merged.push((a, b)),
+        }
+    }
+    merged
+}
+
+// Bleichenbacher's 1998 attack ("Chosen Ciphertext Attacks Against Protocols Based on the
+// RSA Encryption Standard PKCS #1"), for a ciphertext that is already PKCS conforming so
+// that blinding (step 1) can be skipped with s0 = 1. Step numbers follow the paper.
+pub fn bleichenbacher_attack<O: RsaPaddingOracle>(
+    oracle: &mut O,
+    ciphertext: &BigUint,
+) -> PaddingOracleResult {
+    let public_key = oracle.public_key().clone();
+    let n = &public_key.n;
+    let one = BigUint::one();
+
+    let k = public_key.size();
+    let big_b = BigUint::one() << (8 * (k - 2));
+    let two_b = &big_b * &BigUint::from(2u32);
+    let three_b = &big_b * &BigUint::from(3u32);
+    let three_b_minus_one = &three_b - &one;
+
+    let mut oracle = CountingOracle {
+        oracle,
+        ciphertext: ciphertext.clone(),
+        calls: 0,
+    };
+
+    let mut intervals = vec![(two_b.clone(), three_b_minus_one.clone())];
+    let mut s = BigUint::zero();
+
+    for i in 1.. {
+        s = if i == 1 {
+            // Step 2.a: start the search at n / 3B
+            oracle.search_from(ceil_div(n, &three_b))
+        } else if intervals.len() > 1 {
+            // Step 2.b: several intervals left, keep searching linearly
+            oracle.search_from(&s + &one)
+        } else {
+            // Step 2.c: one interval left, search r and s together to roughly halve it
+            let (a, b) = &intervals[0];
+            let mut r = ceil_div(&(&(&(b * &s) - &two_b) * &BigUint::from(2u32)), n);
+
+            'search: loop {
+                let r_n = &r * n;
+                let low = ceil_div(&(&two_b + &r_n), b);
+                let high = &(&three_b_minus_one + &r_n) / a;
+
+                let mut candidate = low;
+                while candidate <= high {
+                    if oracle.is_conforming(&candidate) {
+                        break 'search candidate;
+                    }
+                    candidate = &candidate + &one;
+                }
+
+                r = &r + &one;
+            }
+        };
+
+        // Step 3: narrow every interval given that m * s is conforming
+        let mut next_intervals = Vec::new();
+        for (a, b) in &intervals {
+            let r_low = ceil_div(
+                &(&(a * &s) + &one).checked_sub(&three_b).unwrap_or_default(),
+                n,
+            );
+            let r_high = &(&(b * &s) - &two_b) / n;
+
+            let mut r = r_low;
+            while r <= r_high {
+                let r_n = &r * n;
+                let low = ceil_div(&(&two_b + &r_n), &s).max(a.clone());
+                let high = (&(&three_b_minus_one + &r_n) / &s).min(b.clone());
+
+                if low <= high {
+                    next_intervals.push((low, high));
+                }
+                r = &r + &one;
+            }
+        }
+        intervals = union(next_intervals);
+
+        // Step 4: done once a single value is left
+        if intervals.len() == 1 && intervals[0].0 == intervals[0].1 {
+            break;
+        }
+    }
+
+    PaddingOracleResult {
+        plaintext: intervals[0].0.clone(),
+        oracle_calls: oracle.calls,
+    }
+}
@@ -0,0 +1,85 @@
+use crate::byte_operations::bytes_xor;
+use crate::ciphers::aes::BLOCK_SIZE;
+use crate::mac::cbc_mac::cbc_mac_blocks;
+use crate::padding::pkcs7_pad;
+
+// Challenge 49, attacker-controlled IV: the first plaintext block is XORed with the IV before
+// anything else happens, so any change to it can be cancelled by flipping the same bits in the
+// IV. Rewrites "from=#{attacker}" as "from=#{victim}" in one of our own signed transfers; the two
+// account numbers need the same number of digits.
+pub fn forge_transfer_source(request: &[u8], victim: u32) -> Option<Vec<u8>> {
+    if request.len() < 2 * BLOCK_SIZE {
+        return None;
+    }
+
+    let (message, rest) = request.split_at(request.len() - 2 * BLOCK_SIZE);
+    let (iv, mac) = rest.split_at(BLOCK_SIZE);
+
+    let source_length = message.iter().position(|&byte| byte == b'&')?;
+    let victim_field = format!("from={}", victim).into_bytes();
+    if victim_field.len() != source_length || source_length > BLOCK_SIZE {
+        return None;
+    }
+
+    let mut forged = message.to_vec();
+    forged[..source_length].copy_from_slice(&victim_field);
+
+    let difference = bytes_xor(&message[..source_length], &victim_field);
+    let mut forged_iv = iv.to_vec();
+    for (iv_byte, difference_byte) in forged_iv.iter_mut().zip(difference) {
+        *iv_byte ^= difference_byte;
+    }
+
+    Some([forged, forged_iv, mac.to_vec()].concat())
+}
+
+// Challenge 49, fixed IV: the chaining state after the victim's padded message is their MAC, so
+// appending our own message with its first block XORed by that MAC puts the chain back where our
+// message started from, and our MAC carries over. The first block of our message turns into
+// garbage, so it should carry nothing we need.
+pub fn extend_transactions(victim_request: &[u8], attacker_request: &[u8]) -> Option<Vec<u8>> {
+    if victim_request.len() < BLOCK_SIZE || attacker_request.len() < 2 * BLOCK_SIZE {
+        return None;
+    }
+
+    let (victim_message, victim_mac) = victim_request.split_at(victim_request.len() - BLOCK_SIZE);
+    let (attacker_message, attacker_mac) =
+        attacker_request.split_at(attacker_request.len() - BLOCK_SIZE);
+
+    Some(
+        [
+            pkcs7_pad(victim_message, BLOCK_SIZE),
+            bytes_xor(&attacker_message[..BLOCK_SIZE], victim_mac),
+            attacker_message[BLOCK_SIZE..].to_vec(),
+            attacker_mac.to_vec(),
+        ]
+        .concat(),
+    )
+}
+
+// Challenge 50: with a known key, CBC-MAC is no hash at all. Pads the prefix with spaces to a
+// block boundary, then adds a glue block that sends the chain to the same state the target's
+// first block does, so the rest of the target follows with its own MAC. The prefix is expected
+// to end in a line comment; the glue block is regenerated until it contains no line breaks, so
+// everything up to the target's own newline stays commented out.
+pub fn cbc_mac_collision(key: &[u8], iv: &[u8], target: &[u8], prefix: &[u8]) -> Vec<u8> {
+    if target.len() < BLOCK_SIZE {
+        panic!("Target must be at least one block long");
+    }
+
+    let mut forged = prefix.to_vec();
+    loop {
+        forged.push(b' ');
+        while !forged.len().is_multiple_of(BLOCK_SIZE) {
+            forged.push(b' ');
+        }
+
+        let state = cbc_mac_blocks(key, iv, &forged);
+        let glue = bytes_xor(&bytes_xor(&state, iv), &target[..BLOCK_SIZE]);
+        if !glue.iter().any(|&byte| byte == b'\n' || byte == b'\r') {
+            forged.extend(glue);
+            forged.extend_from_slice(&target[BLOCK_SIZE..]);
+            return forged;
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use crate::oracles::compression::CompressionLengthOracle;
+
+const MAX_SECRET_LENGTH: usize = 256;
+
+// Filler bytes that never repeat, so they always go out as literals. Under the fixed Huffman
+// code the first set costs 8 bits a byte and the second 9, so mixing them can move the end of
+// the compressed stream by any number of bits up to a full 128-bit block.
+const EIGHT_BIT_FILLER: [u8; 16] = [
+    0x80, 0x81, 0x82, 0x83, 0x84, 0x85, 0x86, 0x87, 0x88, 0x89, 0x8a, 0x8b, 0x8c, 0x8d, 0x8e, 0x8f,
+];
+const NINE_BIT_FILLER: [u8; 8] = [0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7];
+
+// Challenge 51: recovers whatever follows `known_prefix` in the request, one byte at a time
+// up to `terminator`. The right guess extends an LZ77 match against the real secret, so it
+// compresses to fewer bits than any wrong guess. Lengths only show whole bytes (CTR) or whole
+// blocks (CBC), so each guess is tried behind every amount of filler until exactly one
+// candidate comes out shorter than the rest.
+pub fn recover_secret<O: CompressionLengthOracle>(
+    oracle: &mut O,
+    known_prefix: &[u8],
+    alphabet: &[u8],
+    terminator: u8,
+) -> Option<Vec<u8>> {
+    let fillers = fillers();
+    let mut recovered = known_prefix.to_vec();
+    let mut start = 0;
+
+    while recovered.len() < known_prefix.len() + MAX_SECRET_LENGTH {
+        let (filler_index, byte) = (0..fillers.len())
+            .map(|offset| (start + offset) % fillers.len())
+            .find_map(|index| {
+                let lengths: Vec<usize> = alphabet
+                    .iter()
+                    .map(|&candidate| {
+                        let guess = [&fillers[index], &recovered[..], &[candidate]].concat();
+                        oracle.encrypted_length(&guess)
+                    })
+                    .collect();
+
+                let shortest = *lengths.iter().min()?;
+                let mut winners = alphabet
+                    .iter()
+                    .zip(&lengths)
+                    .filter(|(_, &length)| length == shortest);
+                match (winners.next(), winners.next()) {
+                    (Some((&byte, _)), None) => Some((index, byte)),
+                    _ => None,
+                }
+            })?;
+
+        // The alignment that separated this byte usually separates the next one too
+        start = filler_index;
+        if byte == terminator {
+            return Some(recovered[known_prefix.len()..].to_vec());
+        }
+        recovered.push(byte);
+    }
+
+    None
+}
+
+// Every filler from nothing up to 127 bits, in order of length modulo a block.
+fn fillers() -> Vec<Vec<u8>> {
+    let mut fillers: Vec<(usize, Vec<u8>)> = (0..NINE_BIT_FILLER.len())
+        .flat_map(|nine_bit| {
+            (0..EIGHT_BIT_FILLER.len()).map(move |eight_bit| {
+                let bits = (8 * eight_bit + 9 * nine_bit) % 128;
+                let filler =
+                    [&EIGHT_BIT_FILLER[..eight_bit], &NINE_BIT_FILLER[..nine_bit]].concat();
+                (bits, filler)
+            })
+        })
+        .collect();
+
+    fillers.sort();
+    fillers.into_iter().map(|(_, filler)| filler).collect()
+}
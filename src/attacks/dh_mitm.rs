@@ -0,0 +1,119 @@
+use crate::math::bigint::BigUint;
+use crate::protocols::channel::Endpoint;
+use crate::protocols::dh::{decrypt_message, session_key, DhGroup, DhMessage};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MaliciousG {
+    One,
+    P,
+    PMinusOne,
+}
+
+impl MaliciousG {
+    pub fn value(self, p: &BigUint) -> BigUint {
+        match self {
+            MaliciousG::One => BigUint::one(),
+            MaliciousG::P => p.clone(),
+            MaliciousG::PMinusOne => p - &BigUint::one(),
+        }
+    }
+}
+
+fn expect_message(endpoint: &Endpoint<DhMessage>) -> DhMessage {
+    endpoint.receive().expect("Peer hung up mid-handshake")
+}
+
+// Forwards encrypted traffic both ways until Alice hangs up, recording every plaintext.
+fn relay_encrypted(
+    alice: &Endpoint<DhMessage>,
+    bob: &Endpoint<DhMessage>,
+    key: &[u8; 16],
+) -> Vec<Vec<u8>> {
+    let mut plaintexts = Vec::new();
+
+    let mut forward = |message: DhMessage, to: &Endpoint<DhMessage>| {
+        if let DhMessage::Encrypted(ciphertext) = &message {
+            plaintexts.extend(decrypt_message(key, ciphertext));
+        }
+        to.send(message);
+    };
+
+    while let Some(message) = alice.receive() {
+        forward(message, bob);
+
+        if let Some(reply) = bob.receive() {
+            forward(reply, alice);
+        }
+    }
+
+    plaintexts
+}
+
+// Challenge 34: replace both public keys with p, so each side computes s = p^x mod p = 0.
+pub fn key_fixing_relay(alice: &Endpoint<DhMessage>, bob: &Endpoint<DhMessage>) -> Vec<Vec<u8>> {
+    let group = match expect_message(alice) {
+        DhMessage::Negotiate { group, .. } => group,
+        message => panic!("Unexpected message from Alice: {:?}", message),
+    };
+    bob.send(DhMessage::Negotiate {
+        group: group.clone(),
+        public_key: group.p.clone(),
+    });
+
+    match expect_message(bob) {
+        DhMessage::PublicKey(_) => alice.send(DhMessage::PublicKey(group.p.clone())),
+        message => panic!("Unexpected message from Bob: {:?}", message),
+    }
+
+    relay_encrypted(alice, bob, &session_key(&BigUint::zero()))
+}
+
+// Challenge 35: swap g for a degenerate value during group negotiation. Both sides then
+// compute s = g'^(ab), which takes at most two values that can be told apart from the
+// public keys alone.
+pub fn malicious_g_relay(
+    alice: &Endpoint<DhMessage>,
+    bob: &Endpoint<DhMessage>,
+    malicious_g: MaliciousG,
+) -> Vec<Vec<u8>> {
+    let group = match expect_message(alice) {
+        DhMessage::Propose(group) => group,
+        message => panic!("Unexpected message from Alice: {:?}", message),
+    };
+    let p = group.p.clone();
+    bob.send(DhMessage::Propose(DhGroup::new(
+        p.clone(),
+        malicious_g.value(&p),
+    )));
+
+    let ack = expect_message(bob);
+    alice.send(ack);
+
+    let alice_public_key = match expect_message(alice) {
+        DhMessage::PublicKey(public_key) => public_key,
+        message => panic!("Unexpected message from Alice: {:?}", message),
+    };
+    bob.send(DhMessage::PublicKey(alice_public_key.clone()));
+
+    let bob_public_key = match expect_message(bob) {
+        DhMessage::PublicKey(public_key) => public_key,
+        message => panic!("Unexpected message from Bob: {:?}", message),
+    };
+    alice.send(DhMessage::PublicKey(bob_public_key.clone()));
+
+    let p_minus_one = &p - &BigUint::one();
+    let shared_secret = match malicious_g {
+        MaliciousG::One => BigUint::one(),
+        MaliciousG::P => BigUint::zero(),
+        // (p - 1)^(ab) is p - 1 only when both exponents are odd, i.e. both public keys are p - 1
+        MaliciousG::PMinusOne => {
+            if alice_public_key == p_minus_one && bob_public_key == p_minus_one {
+                p_minus_one
+            } else {
+                BigUint::one()
+            }
+        }
+    };
+
+    relay_encrypted(alice, bob, &session_key(&shared_secret))
+}
@@ -0,0 +1,192 @@
+use crate::math::bigint::BigUint;
+use crate::math::number_theory::{crt, trial_division};
+use crate::oracles::dh::{shared_secret_mac, DhMacServer};
+use crate::protocols::dh::DhGroup;
+use crate::random::Rng;
+
+// What's known about a private key: x = residue (mod modulus).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialKey {
+    pub residue: BigUint,
+    pub modulus: BigUint,
+}
+
+// A random element of order r, for a prime r dividing p - 1.
+pub fn element_of_order(p: &BigUint, r: u32, rng: &mut Rng) -> BigUint {
+    let exponent = &(p - &BigUint::one()) / &BigUint::from(r);
+
+    loop {
+        let h = BigUint::random_range(rng, &BigUint::from(2u32), p).modpow(&exponent, p);
+        if !h.is_one() {
+            return h;
+        }
+    }
+}
+
+// Challenge 57: feed Bob public keys of small prime order r, so the shared secret can only be
+// one of r values, and find which one keyed his MAC. That gives x mod r for every small factor
+// of j = (p - 1) / q, found by trial division up to `factor_bound`; once their product passes
+// the order q of g, CRT gives x itself.
+pub fn subgroup_confinement(
+    server: &DhMacServer,
+    group: &DhGroup,
+    order: &BigUint,
+    factor_bound: u32,
+    rng: &mut Rng,
+) -> PartialKey {
+    let cofactor = &(&group.p - &BigUint::one()) / order;
+    let (factors, _) = trial_division(&cofactor, factor_bound);
+
+    let mut residues = Vec::new();
+    let mut moduli = Vec::new();
+    let mut product = BigUint::one();
+    for (r, _) in factors {
+        // A factor shared with q would put the residues out of reach of CRT
+        if order.rem_u32(r) == 0 {
+            continue;
+        }
+
+        let h = element_of_order(&group.p, r, rng);
+        let (message, mac) = server.respond(&h);
+
+        let mut candidate = BigUint::one();
+        for exponent in 0..r {
+            if shared_secret_mac(&candidate, &message) == mac {
+                residues.push(BigUint::from(exponent));
+                moduli.push(BigUint::from(r));
+                product = &product * &BigUint::from(r);
+                break;
+            }
+            candidate = &(&candidate * &h) % &group.p;
+        }
+
+        if &product > order {
+            break;
+        }
+    }
+
+    PartialKey {
+        residue: crt(&residues, &moduli),
+        modulus: product,
+    }
+}
+
+// What Pollard's kangaroo needs from a group: powers of an element to set up the jumps and the
+// tame kangaroo's start, the group operation to jump, and a cheap function of an element to
+// pick which jump to take next.
+pub trait KangarooGroup {
+    type Element: Clone + PartialEq;
+
+    fn power(&self, base: &Self::Element, exponent: &BigUint) -> Self::Element;
+    fn operate(&self, a: &Self::Element, b: &Self::Element) -> Self::Element;
+    fn jump_index(&self, element: &Self::Element, k: u32) -> usize;
+}
+
+// The integers mod p under multiplication.
+struct MultiplicativeGroup<'a> {
+    p: &'a BigUint,
+}
+
+impl<'a> KangarooGroup for MultiplicativeGroup<'a> {
+    type Element = BigUint;
+
+    fn power(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        base.modpow(exponent, self.p)
+    }
+
+    fn operate(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        &(a * b) % self.p
+    }
+
+    fn jump_index(&self, element: &BigUint, k: u32) -> usize {
+        element.rem_u32(k) as usize
+    }
+}
+
+// Pollard's kangaroo (lambda) method: the x in [low, high] with y = g^x mod p, in about the
+// square root of the width of the interval. The width has to fit in a u64, which is well past
+// what's feasible anyway.
+pub fn kangaroo(
+    p: &BigUint,
+    g: &BigUint,
+    y: &BigUint,
+    low: &BigUint,
+    high: &BigUint,
+) -> Option<BigUint> {
+    kangaroo_in(&MultiplicativeGroup { p }, g, y, low, high)
+}
+
+// The same walk in any group: the x in [low, high] with y = g^x, written multiplicatively.
+pub fn kangaroo_in<G: KangarooGroup>(
+    group: &G,
+    g: &G::Element,
+    y: &G::Element,
+    low: &BigUint,
+    high: &BigUint,
+) -> Option<BigUint> {
+    let width = high.checked_sub(low)?.to_u64()?;
+
+    // Jumps are 2^f(y) for f(y) below k, with k picked so the mean jump is about half the
+    // square root of the width
+    let target = ((width as f64).sqrt() / 2.0).max(1.0);
+    let k = (1..63u32)
+        .find(|&k| ((1u64 << k) - 1) as f64 / k as f64 >= target)
+        .unwrap_or(63);
+    let jumps: Vec<u64> = (0..k).map(|index| 1 << index).collect();
+    let steps: Vec<G::Element> = jumps
+        .iter()
+        .map(|&jump| group.power(g, &BigUint::from(jump)))
+        .collect();
+    let mean = jumps.iter().sum::<u64>() / u64::from(k);
+
+    // The tame kangaroo starts at the top of the interval and sets a trap where it stops
+    let mut tame_distance = 0u64;
+    let mut tame = group.power(g, high);
+    for _ in 0..4 * mean {
+        let index = group.jump_index(&tame, k);
+        tame_distance += jumps[index];
+        tame = group.operate(&tame, &steps[index]);
+    }
+
+    // The wild kangaroo starts at y; if it ever lands on the same element, it follows the tame
+    // one into the trap
+    let mut wild_distance = 0u64;
+    let mut wild = y.clone();
+    while wild_distance <= width + tame_distance {
+        if wild == tame {
+            return Some(&(high + &BigUint::from(tame_distance)) - &BigUint::from(wild_distance));
+        }
+
+        let index = group.jump_index(&wild, k);
+        wild_distance += jumps[index];
+        wild = group.operate(&wild, &steps[index]);
+    }
+
+    None
+}
+
+// Challenge 58: when the small factors of j don't cover q, subgroup confinement leaves
+// x = n + m * r for known n and r. Then y * g^-n = (g^r)^m with m at most (q - 1) / r, which
+// is small enough to catch with a kangaroo.
+pub fn subgroup_confinement_with_kangaroo(
+    server: &DhMacServer,
+    group: &DhGroup,
+    order: &BigUint,
+    factor_bound: u32,
+    rng: &mut Rng,
+) -> Option<BigUint> {
+    let PartialKey { residue, modulus } =
+        subgroup_confinement(server, group, order, factor_bound, rng);
+    if &modulus > order {
+        return Some(residue);
+    }
+
+    let p = &group.p;
+    let g_inverse_n = group.g.modpow(&residue, p).modinv(p)?;
+    let y = &(server.public_key() * &g_inverse_n) % p;
+    let g = group.g.modpow(&modulus, p);
+    let high = &(order - &BigUint::one()) / &modulus;
+
+    let m = kangaroo(p, &g, &y, &BigUint::zero(), &high)?;
+    Some(&residue + &(&m * &modulus))
+}
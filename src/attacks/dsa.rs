@@ -0,0 +1,85 @@
+use crate::math::bigint::BigUint;
+use crate::pubkey::dsa::{DsaParameters, DsaSignature};
+
+pub struct SignedMessage {
+    pub message: Vec<u8>,
+    pub hash: BigUint,
+    pub signature: DsaSignature,
+}
+
+// (a - b) mod q for a, b already reduced mod q.
+fn sub_mod(a: &BigUint, b: &BigUint, q: &BigUint) -> BigUint {
+    &(&(a + q) - b) % q
+}
+
+// x = (s * k - H(m)) / r mod q
+pub fn private_key_from_nonce(
+    parameters: &DsaParameters,
+    hash: &BigUint,
+    signature: &DsaSignature,
+    k: &BigUint,
+) -> Option<BigUint> {
+    let q = &parameters.q;
+    let r_inverse = signature.r.modinv(q)?;
+    let s_k = &(&signature.s * k) % q;
+
+    Some(&(&sub_mod(&s_k, &(hash % q), q) * &r_inverse) % q)
+}
+
+// Challenge 43: try every nonce in 0..=max_nonce, keeping the candidate key whose public
+// key matches.
+pub fn brute_force_nonce(
+    parameters: &DsaParameters,
+    public_key: &BigUint,
+    hash: &BigUint,
+    signature: &DsaSignature,
+    max_nonce: u64,
+) -> Option<BigUint> {
+    (0..=max_nonce)
+        .filter_map(|k| private_key_from_nonce(parameters, hash, signature, &BigUint::from(k)))
+        .find(|x| &parameters.g.modpow(x, &parameters.p) == public_key)
+}
+
+// Challenge 44: two signatures sharing a nonce share r, and then
+// k = (m1 - m2) / (s1 - s2) mod q.
+pub fn repeated_nonce_key_recovery(
+    parameters: &DsaParameters,
+    public_key: &BigUint,
+    messages: &[SignedMessage],
+) -> Option<BigUint> {
+    let q = &parameters.q;
+
+    for (index, first) in messages.iter().enumerate() {
+        for second in &messages[index + 1..] {
+            if first.signature.r != second.signature.r || first.signature.s == second.signature.s {
+                continue;
+            }
+
+            let hash_difference = sub_mod(&(&first.hash % q), &(&second.hash % q), q);
+            let s_difference = sub_mod(&first.signature.s, &second.signature.s, q);
+            let k = &(&hash_difference * &s_difference.modinv(q)?) % q;
+
+            let candidate = private_key_from_nonce(parameters, &first.hash, &first.signature, &k)?;
+            if &parameters.g.modpow(&candidate, &parameters.p) == public_key {
+                return Some(candidate);
+            }
+        }
+    }
+
+    None
+}
+
+// Challenge 45: with g = p + 1 every power of g is 1 mod p, so for any z the pair
+// r = (y^z mod p) mod q, s = r / z mod q verifies against any message.
+pub fn magic_signature(
+    parameters: &DsaParameters,
+    public_key: &BigUint,
+    z: &BigUint,
+) -> DsaSignature {
+    let q = &parameters.q;
+    let r = &public_key.modpow(z, &parameters.p) % q;
+    let z_inverse = z.modinv(q).expect("z must be invertible mod q");
+    let s = &(&r * &z_inverse) % q;
+
+    DsaSignature { r, s }
+}
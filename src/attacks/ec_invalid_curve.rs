@@ -0,0 +1,128 @@
+use crate::attacks::dh_subgroup::PartialKey;
+use crate::ec::weierstrass::{Point, WeierstrassCurve, WeierstrassGroup};
+use crate::math::bigint::BigUint;
+use crate::math::number_theory::{crt, trial_division};
+use crate::oracles::ec::{shared_point_mac, EcdhMacServer};
+use crate::random::Rng;
+
+// A curve sharing p and a with the real one but with a different b, along with its number of
+// points. Counting points is out of scope here, so the orders are given.
+#[derive(Clone, Debug)]
+pub struct InvalidCurve {
+    pub b: BigUint,
+    pub order: BigUint,
+}
+
+impl InvalidCurve {
+    pub fn from_decimal(b: &str, order: &str) -> Self {
+        InvalidCurve {
+            b: BigUint::from_decimal(b),
+            order: BigUint::from_decimal(order),
+        }
+    }
+}
+
+// A random point of order r on a curve with `curve_order` points, for a prime r dividing it.
+// Multiplying by the order with every factor of r removed leaves a point of order r^j; the
+// group needn't be cyclic, so it's multiplied by r until one more step would reach infinity.
+pub fn point_of_order(
+    curve: &WeierstrassCurve,
+    curve_order: &BigUint,
+    r: u32,
+    rng: &mut Rng,
+) -> Point {
+    let r = BigUint::from(r);
+    let mut cofactor = curve_order.clone();
+    while (&cofactor % &r).is_zero() {
+        cofactor = &cofactor / &r;
+    }
+
+    loop {
+        let mut point = curve.scalar_multiply(&curve.random_point(rng), &cofactor);
+        if point == Point::Infinity {
+            continue;
+        }
+
+        loop {
+            let next = curve.scalar_multiply(&point, &r);
+            if next == Point::Infinity {
+                return point;
+            }
+            point = next;
+        }
+    }
+}
+
+// Challenge 59: Bob's scalar multiplication never uses b, so a point on an invalid curve gets
+// multiplied as if it were on his. Picking invalid curves whose orders have small factors r
+// and sending points of order r confines the shared point to r values; the one keying his MAC
+// gives his key mod r. Once the product of the factors passes the order of the base point, CRT
+// gives the key.
+pub fn invalid_curve_attack(
+    server: &EcdhMacServer,
+    group: &WeierstrassGroup,
+    invalid_curves: &[InvalidCurve],
+    factor_bound: u32,
+    rng: &mut Rng,
+) -> PartialKey {
+    let mut residues = Vec::new();
+    let mut moduli = Vec::new();
+    let mut product = BigUint::one();
+
+    'curves: for invalid_curve in invalid_curves {
+        let curve = group.curve.with_b(invalid_curve.b.clone());
+        let (factors, _) = trial_division(&invalid_curve.order, factor_bound);
+
+        for (r, _) in factors {
+            // The same factor on two curves would only repeat what's already known
+            let modulus = BigUint::from(r);
+            if moduli.contains(&modulus) || group.order.rem_u32(r) == 0 {
+                continue;
+            }
+
+            let h = point_of_order(&curve, &invalid_curve.order, r, rng);
+            let (message, mac) = server.respond(&h);
+
+            if let Some(k) = brute_force_multiple(group, &curve, &h, r, &message, &mac) {
+                residues.push(BigUint::from(k));
+                product = &product * &modulus;
+                moduli.push(modulus);
+            }
+
+            if product > group.order {
+                break 'curves;
+            }
+        }
+    }
+
+    PartialKey {
+        residue: crt(&residues, &moduli),
+        modulus: product,
+    }
+}
+
+// The k below r for which k * h keyed Bob's MAC. kh and (r - k)h are negations of each other,
+// so walking halfway and trying both covers every k.
+fn brute_force_multiple(
+    group: &WeierstrassGroup,
+    curve: &WeierstrassCurve,
+    h: &Point,
+    r: u32,
+    message: &[u8],
+    mac: &[u8; 32],
+) -> Option<u32> {
+    let mut multiple = Point::Infinity;
+
+    for k in 0..=r / 2 {
+        if &shared_point_mac(group, &multiple, message) == mac {
+            return Some(k);
+        }
+        if &shared_point_mac(group, &curve.negate(&multiple), message) == mac {
+            return Some(r - k);
+        }
+
+        multiple = curve.add(&multiple, h);
+    }
+
+    None
+}
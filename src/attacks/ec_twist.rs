@@ -0,0 +1,251 @@
+use crate::attacks::dh_subgroup::{kangaroo_in, KangarooGroup, PartialKey};
+use crate::ec::montgomery::{LadderPoint, MontgomeryCurve, MontgomeryGroup};
+use crate::ec::weierstrass::{Point, WeierstrassCurve};
+use crate::ec::{batch_invert, mul_mod};
+use crate::math::bigint::BigUint;
+use crate::math::number_theory::{crt, trial_division};
+use crate::oracles::dh::shared_secret_mac;
+use crate::oracles::ec::LadderMacServer;
+use crate::random::Rng;
+
+// Multiples brought back to affine u per inversion in the brute force
+const BATCH_SIZE: usize = 512;
+
+// The u coordinate of a random point on the twist whose order is the product of `factors`,
+// distinct odd primes dividing the twist order. Odd orders keep clear of u = 0, which the
+// ladder uses for both the identity and the point of order two.
+//
+// A factor can divide the twist order more than once, so the cofactor leaves out its whole
+// power, and the point's component of order r^j is then multiplied down to order r.
+pub fn twist_point_of_order(
+    curve: &MontgomeryCurve,
+    twist_order: &BigUint,
+    factors: &[u32],
+    rng: &mut Rng,
+) -> BigUint {
+    let prime_powers: Vec<BigUint> = factors
+        .iter()
+        .map(|&factor| {
+            let factor = BigUint::from(factor);
+            let mut power = BigUint::one();
+            while (twist_order % &(&power * &factor)).is_zero() {
+                power = &power * &factor;
+            }
+            power
+        })
+        .collect();
+    let full_order = prime_powers
+        .iter()
+        .fold(BigUint::one(), |order, power| &order * power);
+    let cofactor = twist_order / &full_order;
+
+    'search: loop {
+        let u = BigUint::random_below(rng, &curve.p);
+        if curve.is_on_curve(&u) {
+            continue;
+        }
+
+        let mut h = curve.ladder(&u, &cofactor);
+        for (&factor, power) in factors.iter().zip(&prime_powers) {
+            let factor = BigUint::from(factor);
+            let others = &full_order / power;
+            loop {
+                let component = curve.ladder(&h, &others);
+                if component.is_zero() {
+                    continue 'search;
+                }
+                if curve.ladder(&component, &factor).is_zero() {
+                    break;
+                }
+                h = curve.ladder(&h, &factor);
+            }
+        }
+        return h;
+    }
+}
+
+// Challenge 60, first half: the ladder takes any u, and every u not on the curve is on its
+// twist, whose order has plenty of small factors. Sending a twist point of order r gives
+// Bob's key mod r as with the invalid curves, except that the MAC only covers u, so each
+// residue is only known up to sign. One more query per factor, with a point of order r0 * r,
+// lines its sign up with that of the first residue r0. The result is a key d = +/- residue.
+pub fn twist_confinement(
+    server: &LadderMacServer,
+    group: &MontgomeryGroup,
+    factor_bound: u32,
+    rng: &mut Rng,
+) -> PartialKey {
+    let curve = &group.curve;
+    let twist_order = group.twist_order();
+    let (factors, _) = trial_division(&twist_order, factor_bound);
+
+    let mut found = Vec::new();
+    for (r, _) in factors {
+        if r == 2 || group.order.rem_u32(r) == 0 {
+            continue;
+        }
+
+        let h = twist_point_of_order(curve, &twist_order, &[r], rng);
+        let (message, mac) = server.respond(&h);
+        if let Some(k) = brute_force_ladder(curve, &h, r, &message, &mac) {
+            found.push((r, k));
+        }
+    }
+
+    // A zero residue has no sign, and can't anchor the others
+    let anchor = found.iter().copied().find(|&(_, k)| k != 0);
+
+    let mut residues = Vec::new();
+    let mut moduli = Vec::new();
+    for &(r, k) in &found {
+        let k = match anchor {
+            Some(anchor) if anchor.0 != r && k != 0 => {
+                align_sign(server, curve, &twist_order, anchor, (r, k), rng)
+            }
+            _ => k,
+        };
+        residues.push(BigUint::from(k));
+        moduli.push(BigUint::from(r));
+    }
+
+    PartialKey {
+        residue: crt(&residues, &moduli),
+        modulus: moduli
+            .iter()
+            .fold(BigUint::one(), |product, modulus| &product * modulus),
+    }
+}
+
+// k or r - k, whichever has the same sign as the anchor's residue. Of CRT(k0, k) and
+// CRT(k0, -k), only one is +/- Bob's key mod r0 * r.
+fn align_sign(
+    server: &LadderMacServer,
+    curve: &MontgomeryCurve,
+    twist_order: &BigUint,
+    (r0, k0): (u32, u32),
+    (r, k): (u32, u32),
+    rng: &mut Rng,
+) -> u32 {
+    let h = twist_point_of_order(curve, twist_order, &[r0, r], rng);
+    let (message, mac) = server.respond(&h);
+
+    let candidate = crt(
+        &[BigUint::from(k0), BigUint::from(k)],
+        &[BigUint::from(r0), BigUint::from(r)],
+    );
+    if shared_secret_mac(&curve.ladder(&h, &candidate), &message) == mac {
+        k
+    } else {
+        r - k
+    }
+}
+
+// The k in [0, r / 2] for which u(kh) keyed Bob's MAC. The multiples come from differential
+// additions, (k + 1)h = kh + h with difference (k - 1)h, and are brought back to affine u in
+// batches so they share inversions.
+fn brute_force_ladder(
+    curve: &MontgomeryCurve,
+    h: &BigUint,
+    r: u32,
+    message: &[u8],
+    mac: &[u8; 32],
+) -> Option<u32> {
+    if &shared_secret_mac(&BigUint::zero(), message) == mac {
+        return Some(0);
+    }
+
+    let base = LadderPoint::from_u(h);
+    let mut previous = base.clone();
+    let mut current = curve.double(&base);
+    let mut batch = vec![base.clone()];
+    let mut start = 1;
+
+    for _ in 2..=r / 2 {
+        if batch.len() == BATCH_SIZE {
+            if let Some(offset) = find_mac(curve, &batch, message, mac) {
+                return Some(start + offset);
+            }
+            start += batch.len() as u32;
+            batch.clear();
+        }
+
+        batch.push(current.clone());
+        let next = curve.differential_add(&current, &base, &previous);
+        previous = std::mem::replace(&mut current, next);
+    }
+
+    find_mac(curve, &batch, message, mac).map(|offset| start + offset)
+}
+
+fn find_mac(
+    curve: &MontgomeryCurve,
+    batch: &[LadderPoint],
+    message: &[u8],
+    mac: &[u8; 32],
+) -> Option<u32> {
+    let z: Vec<BigUint> = batch.iter().map(|point| point.z.clone()).collect();
+    let inverses = batch_invert(&z, &curve.p);
+
+    batch
+        .iter()
+        .zip(inverses.iter())
+        .position(|(point, inverse)| {
+            &shared_secret_mac(&mul_mod(&point.x, inverse, &curve.p), message) == mac
+        })
+        .map(|offset| offset as u32)
+}
+
+// Pollard's kangaroo on a curve, with the jumps chosen by the x coordinate.
+impl KangarooGroup for WeierstrassCurve {
+    type Element = Point;
+
+    fn power(&self, base: &Point, exponent: &BigUint) -> Point {
+        self.scalar_multiply(base, exponent)
+    }
+
+    fn operate(&self, a: &Point, b: &Point) -> Point {
+        self.add(a, b)
+    }
+
+    fn jump_index(&self, element: &Point, k: u32) -> usize {
+        element.x().map_or(0, |x| x.rem_u32(k) as usize)
+    }
+}
+
+// Challenge 60: the twist leaves d = c + m * r for c one of +/- the residue mod r, and
+// `key_bound` caps m. The kangaroo needs whole points, so it runs on the isomorphic
+// Weierstrass curve, where Bob's u lifts to either dG or -dG; that's four walks at worst.
+// The key found might be the group order minus Bob's, which gives the same u for every
+// shared secret.
+pub fn twist_attack(
+    server: &LadderMacServer,
+    group: &MontgomeryGroup,
+    factor_bound: u32,
+    key_bound: &BigUint,
+    rng: &mut Rng,
+) -> Option<BigUint> {
+    let PartialKey { residue, modulus } = twist_confinement(server, group, factor_bound, rng);
+
+    let curve = group.curve.to_weierstrass();
+    let g = curve.lift_x(&group.curve.u_to_x(&group.base))?;
+    let public_key = curve.lift_x(&group.curve.u_to_x(server.public_key()))?;
+    let step = curve.scalar_multiply(&g, &modulus);
+    let high = &(key_bound - &BigUint::one()) / &modulus;
+
+    let mut offsets = vec![residue.clone()];
+    if !residue.is_zero() {
+        offsets.push(&modulus - &residue);
+    }
+
+    for target in [curve.negate(&public_key), public_key] {
+        for offset in &offsets {
+            // target - cG = m * (rG)
+            let shifted = curve.add(&target, &curve.negate(&curve.scalar_multiply(&g, offset)));
+            if let Some(m) = kangaroo_in(&curve, &step, &shifted, &BigUint::zero(), &high) {
+                return Some(offset + &(&m * &modulus));
+            }
+        }
+    }
+
+    None
+}
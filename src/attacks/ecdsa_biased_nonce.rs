@@ -0,0 +1,32 @@
+use crate::ec::weierstrass::{Point, WeierstrassGroup};
+use crate::math::bigint::BigUint;
+use crate::math::lattice::hidden_number_problem;
+use crate::pubkey::ecdsa::{self, EcdsaSignature};
+
+// Challenge 62: with k = b 2^l for a b below n / 2^l, the signing equation s = (H(m) + dr) / k
+// rearranges to b = H(m) / (s 2^l) + d r / (s 2^l) mod n. Every signature is then a sample
+// (t, u) = (r / (s 2^l), H(m) / (s 2^l)) for the hidden number problem in d. The candidates
+// the lattice turns up are checked against the public key.
+pub fn biased_nonce_attack(
+    group: &WeierstrassGroup,
+    public_key: &Point,
+    signatures: &[(Vec<u8>, EcdsaSignature)],
+    zero_bits: usize,
+) -> Option<BigUint> {
+    let n = &group.order;
+    let scale = &(BigUint::one() << zero_bits) % n;
+
+    let samples: Vec<(BigUint, BigUint)> = signatures
+        .iter()
+        .filter_map(|(message, signature)| {
+            let inverse = (&signature.s * &scale).modinv(n)?;
+            let t = &(&signature.r * &inverse) % n;
+            let u = &(&ecdsa::message_hash(message, n) * &inverse) % n;
+            Some((t, u))
+        })
+        .collect();
+
+    hidden_number_problem(n, zero_bits, &samples)
+        .into_iter()
+        .find(|d| &group.curve.scalar_multiply(&group.generator, d) == public_key)
+}
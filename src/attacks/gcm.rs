@@ -0,0 +1,211 @@
+use crate::ciphers::aes::BLOCK_SIZE;
+use crate::ciphers::gcm::{ghash, ghash_blocks, TAG_SIZE};
+use crate::math::gf128::Gf128;
+use crate::math::gf128_polynomial::Polynomial;
+use crate::math::gf2::{bit, flip_bit, kernel, words_for};
+use crate::oracles::gcm::TruncatedGcmOracle;
+use crate::random::Rng;
+
+// A message as it went over the wire.
+#[derive(Clone, Debug)]
+pub struct GcmMessage {
+    pub associated_data: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+    pub tag: [u8; TAG_SIZE],
+}
+
+// The tag as a polynomial in h: GHASH's blocks as coefficients, with the tag itself as the
+// constant term. It evaluates to the tag mask s = E(K, J0) at the authentication key.
+fn tag_polynomial(message: &GcmMessage) -> Polynomial {
+    let blocks = ghash_blocks(&message.associated_data, &message.ciphertext);
+    Polynomial::new(
+        std::iter::once(Gf128::from_bytes(&message.tag))
+            .chain(blocks.into_iter().rev())
+            .collect(),
+    )
+}
+
+// Challenge 63: two messages under the same key and nonce share the tag mask s, so the
+// difference of their tag polynomials has h as a root.
+pub fn forbidden_attack(first: &GcmMessage, second: &GcmMessage, rng: &mut Rng) -> Vec<Gf128> {
+    (&tag_polynomial(first) - &tag_polynomial(second)).roots(rng)
+}
+
+// Narrows the candidates down by pairing the first message with each of the others in turn,
+// until only one is left. All the messages have to share a nonce.
+pub fn recover_authentication_key(messages: &[GcmMessage], rng: &mut Rng) -> Option<Gf128> {
+    let (first, rest) = messages.split_first()?;
+    let mut candidates: Option<Vec<Gf128>> = None;
+
+    for other in rest {
+        let roots = forbidden_attack(first, other, rng);
+        let narrowed: Vec<Gf128> = match candidates {
+            None => roots,
+            Some(previous) => previous.into_iter().filter(|h| roots.contains(h)).collect(),
+        };
+
+        if narrowed.len() == 1 {
+            return Some(narrowed[0]);
+        }
+        candidates = Some(narrowed);
+    }
+
+    None
+}
+
+// With h in hand, any message under the same nonce gives away s, and with it the tag for
+// whatever ciphertext we like.
+pub fn forge_tag(
+    h: Gf128,
+    known: &GcmMessage,
+    associated_data: &[u8],
+    ciphertext: &[u8],
+) -> [u8; TAG_SIZE] {
+    let mask = Gf128::from_bytes(&known.tag) + ghash(h, &known.associated_data, &known.ciphertext);
+    (ghash(h, associated_data, ciphertext) + mask).to_bytes()
+}
+
+// Challenge 64: with a truncated tag, forgeries are only a few bits of luck away, and each
+// one that lands gives away linear equations in the bits of h.
+//
+// Squaring is linear over GF(2), so changing only the ciphertext blocks that multiply h^(2^i)
+// by d_i changes the tag by sum d_i h^(2^i) = Ad * h for a 128x128 bit matrix Ad that depends
+// linearly on the d_i. Picking d_i so that the top rows of Ad vanish leaves only the last few
+// tag bits to chance. When a forgery is accepted, the rest of Ad's rows within the tag give
+// equations that h satisfies. Writing the possible h as X * h' for a basis X of the solutions
+// so far, the same trick on Ad * X has fewer columns per row, so more rows can be zeroed and
+// the next forgery is cheaper. Carries on until one candidate is left.
+//
+// The message is 2^doublings blocks long, which gives that many d_i to play with.
+pub fn truncated_mac_attack(
+    oracle: &TruncatedGcmOracle,
+    doublings: usize,
+    rng: &mut Rng,
+) -> Option<Gf128> {
+    let tag_bits = 8 * oracle.tag_length();
+    let block_count = 1 << doublings;
+    let variables = 128 * doublings;
+
+    let plaintext = rng.bytes(block_count * BLOCK_SIZE);
+    let (nonce, ciphertext, tag) = oracle.encrypt(&plaintext, rng);
+
+    // The block multiplied by h^(2^i), counting the length block as the last one
+    let block_offsets: Vec<usize> = (1..=doublings)
+        .map(|i| (block_count + 1 - (1 << i)) * BLOCK_SIZE)
+        .collect();
+
+    let monomials: Vec<Gf128> = (0..128).map(Gf128::monomial).collect();
+    let monomial_squares = repeated_squares(&monomials, doublings);
+    let mut equations: Vec<Vec<u64>> = Vec::new();
+    let mut basis = monomials;
+
+    while basis.len() > 1 {
+        let zeroed = std::cmp::min(tag_bits - 1, (variables - 1) / basis.len());
+        let squares = repeated_squares(&basis, doublings);
+        let solutions = kernel(&zeroing_constraints(&squares, zeroed), variables);
+
+        let differences = loop {
+            let differences = random_differences(&solutions, doublings, rng);
+            if differences.iter().all(|d| d.is_zero()) {
+                continue;
+            }
+
+            let mut forged = ciphertext.clone();
+            for (offset, difference) in block_offsets.iter().zip(&differences) {
+                for (byte, difference_byte) in
+                    forged[*offset..].iter_mut().zip(difference.to_bytes())
+                {
+                    *byte ^= difference_byte;
+                }
+            }
+
+            if oracle.is_valid(&nonce, &forged, &tag) {
+                break differences;
+            }
+        };
+
+        // Every row of Ad within the tag is now known to vanish at h
+        let columns = error_columns(&monomial_squares, &differences);
+        for row in 0..tag_bits {
+            let equation = columns
+                .iter()
+                .enumerate()
+                .filter(|(_, column)| column.bit(row))
+                .fold(0u128, |equation, (index, _)| equation | (1 << index));
+            equations.push(vec![equation as u64, (equation >> 64) as u64]);
+        }
+
+        basis = kernel(&equations, 128)
+            .into_iter()
+            .map(|vector| Gf128::from_bits(vector[0] as u128 | (vector[1] as u128) << 64))
+            .collect();
+    }
+
+    basis.first().copied()
+}
+
+// squares[i][m] = basis[m]^(2^(i + 1)), for i below `doublings`.
+fn repeated_squares(basis: &[Gf128], doublings: usize) -> Vec<Vec<Gf128>> {
+    let mut squares = Vec::new();
+    let mut current = basis.to_vec();
+    for _ in 0..doublings {
+        current = current.iter().map(|value| value.square()).collect();
+        squares.push(current.clone());
+    }
+    squares
+}
+
+// The columns of Ad * X: sum over i of d_i * x_m^(2^i) for each basis vector x_m.
+fn error_columns(squares: &[Vec<Gf128>], differences: &[Gf128]) -> Vec<Gf128> {
+    (0..squares[0].len())
+        .map(|m| {
+            squares
+                .iter()
+                .zip(differences)
+                .fold(Gf128::ZERO, |sum, (row, &d)| sum + d * row[m])
+        })
+        .collect()
+}
+
+// The linear map from the bits of the d_i to the top `zeroed` rows of Ad * X, one equation per
+// entry. Variable 128 i + b is bit b of d_(i + 1); setting only it makes column m of Ad * X
+// equal to x^b * x_m^(2^(i + 1)).
+fn zeroing_constraints(squares: &[Vec<Gf128>], zeroed: usize) -> Vec<Vec<u64>> {
+    let width = squares[0].len();
+    let variables = 128 * squares.len();
+    let mut constraints = vec![vec![0; words_for(variables)]; zeroed * width];
+
+    for (i, row) in squares.iter().enumerate() {
+        for (m, &square) in row.iter().enumerate() {
+            let mut column = square;
+            for b in 0..128 {
+                for r in (0..zeroed).filter(|&r| column.bit(r)) {
+                    flip_bit(&mut constraints[r * width + m], 128 * i + b);
+                }
+                column = column.mul_x();
+            }
+        }
+    }
+
+    constraints
+}
+
+// A random combination of the solution vectors, split back up into the d_i.
+fn random_differences(solutions: &[Vec<u64>], doublings: usize, rng: &mut Rng) -> Vec<Gf128> {
+    let mut combination = vec![0; words_for(128 * doublings)];
+    for solution in solutions {
+        if rng.next_u64() & 1 == 1 {
+            for (word, other) in combination.iter_mut().zip(solution) {
+                *word ^= other;
+            }
+        }
+    }
+
+    (0..doublings)
+        .map(|i| {
+            (0..128)
+                .filter(|&b| bit(&combination, 128 * i + b))
+                .fold(Gf128::ZERO, |d, b| d + Gf128::monomial(b))
+        })
+        .collect()
+}
@@ -0,0 +1,342 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::ciphers::aes::BLOCK_SIZE;
+use crate::hash::weak_md::{padding, WeakMdHash};
+use crate::random::Rng;
+
+type Block = [u8; BLOCK_SIZE];
+
+fn random_block(rng: &mut Rng) -> Block {
+    let mut block = [0; BLOCK_SIZE];
+    rng.fill_bytes(&mut block);
+    block
+}
+
+// Birthday search for two different blocks that take `state` to the same place.
+pub fn find_collision(hash: &WeakMdHash, state: u32, rng: &mut Rng) -> (Block, Block, u32) {
+    let mut seen: HashMap<u32, Block> = HashMap::new();
+
+    loop {
+        let block = random_block(rng);
+        let next = hash.compress(state, &block);
+        match seen.get(&next) {
+            Some(other) if *other != block => return (*other, block, next),
+            _ => {
+                seen.insert(next, block);
+            }
+        }
+    }
+}
+
+// Birthday search for a block from each of two states that land on the same state.
+fn find_cross_collision(
+    hash: &WeakMdHash,
+    first: u32,
+    second: u32,
+    rng: &mut Rng,
+) -> (Block, Block, u32) {
+    let mut first_seen: HashMap<u32, Block> = HashMap::new();
+    let mut second_seen: HashMap<u32, Block> = HashMap::new();
+
+    loop {
+        let first_block = random_block(rng);
+        let next = hash.compress(first, &first_block);
+        if let Some(second_block) = second_seen.get(&next) {
+            return (first_block, *second_block, next);
+        }
+        first_seen.insert(next, first_block);
+
+        let second_block = random_block(rng);
+        let next = hash.compress(second, &second_block);
+        if let Some(first_block) = first_seen.get(&next) {
+            return (*first_block, second_block, next);
+        }
+        second_seen.insert(next, second_block);
+    }
+}
+
+// Joux's multicollision: n single-block collisions chained from the initial state give 2^n
+// messages of n blocks that all reach the same state, for n times the cost of one collision.
+pub struct Multicollision {
+    pub pairs: Vec<(Block, Block)>,
+    pub state: u32,
+}
+
+impl Multicollision {
+    pub fn new(hash: &WeakMdHash, collisions: usize, rng: &mut Rng) -> Self {
+        let mut multicollision = Multicollision {
+            pairs: Vec::new(),
+            state: hash.initial_state(),
+        };
+        for _ in 0..collisions {
+            multicollision.extend(hash, rng);
+        }
+        multicollision
+    }
+
+    // Doubles the number of colliding messages.
+    pub fn extend(&mut self, hash: &WeakMdHash, rng: &mut Rng) {
+        let (first, second, state) = find_collision(hash, self.state, rng);
+        self.pairs.push((first, second));
+        self.state = state;
+    }
+
+    pub fn blocks(&self) -> usize {
+        self.pairs.len()
+    }
+
+    // Bit i of the index picks which block of pair i to use.
+    pub fn message(&self, index: usize) -> Vec<u8> {
+        self.pairs
+            .iter()
+            .enumerate()
+            .flat_map(|(bit, (first, second))| {
+                if index >> bit & 1 == 0 {
+                    *first
+                } else {
+                    *second
+                }
+            })
+            .collect()
+    }
+}
+
+// Challenge 52: a collision in f(m) || g(m), where f is cheap and g is the stronger hash. Build
+// a multicollision in f large enough that a birthday collision in g is likely among its
+// messages, and keep doubling it until one turns up.
+pub fn concatenated_collision(
+    cheap: &WeakMdHash,
+    expensive: &WeakMdHash,
+    rng: &mut Rng,
+) -> (Vec<u8>, Vec<u8>) {
+    let mut multicollision = Multicollision::new(cheap, expensive.state_bytes() * 4, rng);
+
+    loop {
+        let blocks = multicollision.blocks();
+        let tail = padding(&vec![0; blocks * BLOCK_SIZE]).split_off(blocks * BLOCK_SIZE);
+
+        // All the messages share prefixes, so walk them a level at a time rather than one by one
+        let mut states = vec![expensive.initial_state()];
+        for (first, second) in &multicollision.pairs {
+            let with_first = states.iter().map(|&state| expensive.compress(state, first));
+            let with_second: Vec<u32> = states
+                .iter()
+                .map(|&state| expensive.compress(state, second))
+                .collect();
+            states = with_first.chain(with_second).collect();
+        }
+
+        let mut seen: HashMap<u32, usize> = HashMap::new();
+        for (index, state) in states.into_iter().enumerate() {
+            let digest = expensive.compress_blocks(state, &tail);
+            if let Some(&other) = seen.get(&digest) {
+                return (multicollision.message(other), multicollision.message(index));
+            }
+            seen.insert(digest, index);
+        }
+
+        multicollision.extend(cheap, rng);
+    }
+}
+
+// Kelsey and Schneier's expandable message: k pieces, each a collision between a single block
+// and a message of 2^(k-1-i) + 1 blocks, so every length from k to k + 2^k - 1 blocks reaches
+// the same final state.
+pub struct ExpandableMessage {
+    pieces: Vec<(Vec<u8>, Vec<u8>)>,
+    pub state: u32,
+}
+
+impl ExpandableMessage {
+    pub fn new(hash: &WeakMdHash, k: usize, rng: &mut Rng) -> Self {
+        let mut state = hash.initial_state();
+        let mut pieces = Vec::with_capacity(k);
+
+        for index in 0..k {
+            let dummy_blocks = vec![0; (1 << (k - 1 - index)) * BLOCK_SIZE];
+            let dummy_state = hash.compress_blocks(state, &dummy_blocks);
+
+            let (short, long_last, next) = find_cross_collision(hash, state, dummy_state, rng);
+            pieces.push((short.to_vec(), [&dummy_blocks[..], &long_last].concat()));
+            state = next;
+        }
+
+        ExpandableMessage { pieces, state }
+    }
+
+    pub fn min_blocks(&self) -> usize {
+        self.pieces.len()
+    }
+
+    pub fn max_blocks(&self) -> usize {
+        self.pieces.len() + (1 << self.pieces.len()) - 1
+    }
+
+    pub fn message(&self, blocks: usize) -> Option<Vec<u8>> {
+        if blocks < self.min_blocks() || blocks > self.max_blocks() {
+            return None;
+        }
+
+        let extra = blocks - self.min_blocks();
+        let k = self.pieces.len();
+        let message = self
+            .pieces
+            .iter()
+            .enumerate()
+            .flat_map(|(index, (short, long))| {
+                if extra >> (k - 1 - index) & 1 == 0 {
+                    short.clone()
+                } else {
+                    long.clone()
+                }
+            })
+            .collect();
+
+        Some(message)
+    }
+}
+
+// Challenge 53: a second preimage for a long message. Find a bridge block from the end of an
+// expandable message to one of the message's intermediate states, then stretch the expandable
+// message so the forgery has the same length, and so the same padding, as the original.
+pub fn second_preimage(hash: &WeakMdHash, message: &[u8], rng: &mut Rng) -> Option<Vec<u8>> {
+    let blocks = message.len() / BLOCK_SIZE;
+    if blocks < 2 {
+        return None;
+    }
+
+    let k = (usize::BITS - 1 - blocks.leading_zeros()) as usize;
+    let expandable = ExpandableMessage::new(hash, k, rng);
+
+    // The state after j blocks, for every j the expandable message can line up with
+    let mut intermediate: HashMap<u32, usize> = HashMap::new();
+    let mut state = hash.initial_state();
+    for (index, block) in message.chunks_exact(BLOCK_SIZE).enumerate() {
+        state = hash.compress(state, block);
+        let consumed = index + 1;
+        if consumed > expandable.min_blocks() && consumed <= expandable.max_blocks() + 1 {
+            intermediate.entry(state).or_insert(consumed);
+        }
+    }
+
+    loop {
+        let bridge = random_block(rng);
+        if let Some(&consumed) = intermediate.get(&hash.compress(expandable.state, &bridge)) {
+            let prefix = expandable.message(consumed - 1)?;
+            return Some([&prefix[..], &bridge, &message[consumed * BLOCK_SIZE..]].concat());
+        }
+    }
+}
+
+// A binary tree of collisions: 2^k leaf states, each pair of which is joined to a single state
+// on the level above, down to one root. From any leaf there is a k-block path to the root.
+pub struct DiamondStructure {
+    states: Vec<Vec<u32>>,
+    links: Vec<Vec<Block>>,
+    leaves: HashMap<u32, usize>,
+}
+
+impl DiamondStructure {
+    pub fn new(hash: &WeakMdHash, k: usize, rng: &mut Rng) -> Self {
+        let mut leaf_states = HashSet::new();
+        while leaf_states.len() < 1 << k {
+            leaf_states.insert(hash.compress(hash.initial_state(), &random_block(rng)));
+        }
+
+        let mut level: Vec<u32> = leaf_states.into_iter().collect();
+        level.sort();
+        let leaves = level
+            .iter()
+            .enumerate()
+            .map(|(index, &state)| (state, index))
+            .collect();
+
+        let mut states = Vec::with_capacity(k + 1);
+        let mut links = Vec::with_capacity(k);
+        while level.len() > 1 {
+            let mut level_links = Vec::with_capacity(level.len());
+            let mut next_level = Vec::with_capacity(level.len() / 2);
+            for pair in level.chunks(2) {
+                let (first, second, next) = find_cross_collision(hash, pair[0], pair[1], rng);
+                level_links.extend([first, second]);
+                next_level.push(next);
+            }
+
+            states.push(level);
+            links.push(level_links);
+            level = next_level;
+        }
+        states.push(level);
+
+        DiamondStructure {
+            states,
+            links,
+            leaves,
+        }
+    }
+
+    pub fn root(&self) -> u32 {
+        self.states[self.states.len() - 1][0]
+    }
+
+    pub fn depth(&self) -> usize {
+        self.links.len()
+    }
+
+    pub fn leaf(&self, state: u32) -> Option<usize> {
+        self.leaves.get(&state).copied()
+    }
+
+    pub fn path(&self, leaf: usize) -> Vec<u8> {
+        let mut index = leaf;
+        let mut path = Vec::with_capacity(self.depth() * BLOCK_SIZE);
+        for level_links in &self.links {
+            path.extend_from_slice(&level_links[index]);
+            index /= 2;
+        }
+        path
+    }
+}
+
+// Challenge 54: commit to a hash now, and later produce a message with any prefix of up to
+// `prefix_blocks` blocks that hashes to it. The total length is fixed up front so the final
+// padding block can be included in the prediction.
+pub struct NostradamusPrediction {
+    diamond: DiamondStructure,
+    prefix_blocks: usize,
+    pub prediction: u32,
+}
+
+impl NostradamusPrediction {
+    pub fn new(hash: &WeakMdHash, k: usize, prefix_blocks: usize, rng: &mut Rng) -> Self {
+        let diamond = DiamondStructure::new(hash, k, rng);
+
+        let length = (prefix_blocks + 1 + diamond.depth()) * BLOCK_SIZE;
+        let tail = padding(&vec![0; length]).split_off(length);
+        let prediction = hash.compress_blocks(diamond.root(), &tail);
+
+        NostradamusPrediction {
+            diamond,
+            prefix_blocks,
+            prediction,
+        }
+    }
+
+    // Pads the prefix out with spaces, then searches for a glue block into one of the leaves.
+    pub fn forge(&self, hash: &WeakMdHash, prefix: &[u8], rng: &mut Rng) -> Option<Vec<u8>> {
+        if prefix.len() > self.prefix_blocks * BLOCK_SIZE {
+            return None;
+        }
+
+        let mut padded = prefix.to_vec();
+        padded.resize(self.prefix_blocks * BLOCK_SIZE, b' ');
+        let state = hash.compress_blocks(hash.initial_state(), &padded);
+
+        loop {
+            let glue = random_block(rng);
+            if let Some(leaf) = self.diamond.leaf(hash.compress(state, &glue)) {
+                return Some([&padded[..], &glue, &self.diamond.path(leaf)].concat());
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use crate::ec::weierstrass::{EcKeypair, Point, WeierstrassGroup};
+use crate::math::bigint::BigUint;
+use crate::math::number_theory::{crt, is_probable_prime, small_primes};
+use crate::pubkey::ecdsa::{self, EcdsaSignature};
+use crate::pubkey::pkcs1::encode_signature;
+use crate::pubkey::rsa::RsaPublicKey;
+use crate::random::Rng;
+
+// Largest prime factor of p - 1 for the RSA primes. Each one costs a brute-force discrete log
+// of up to this many steps.
+const SMOOTHNESS_BOUND: u32 = 1 << 12;
+
+// Tries at a q for each p before drawing a fresh p
+const Q_ATTEMPTS: usize = 32;
+
+// Challenge 61, ECDSA: verification takes the generator from the domain parameters, which
+// aren't bound to the key. With R = u1 G + u2 Q from the signature, any d' works once
+// G' = R / (u1 + u2 d'), since then u1 G' + u2 d' G' = R. Returns the doctored group and a key
+// pair on it that Eve actually holds.
+pub fn ecdsa_key_selection(
+    group: &WeierstrassGroup,
+    public_key: &Point,
+    message: &[u8],
+    signature: &EcdsaSignature,
+    rng: &mut Rng,
+) -> Option<(WeierstrassGroup, EcKeypair)> {
+    let n = &group.order;
+    let w = signature.s.modinv(n)?;
+    let u1 = &(&ecdsa::message_hash(message, n) * &w) % n;
+    let u2 = &(&signature.r * &w) % n;
+
+    let curve = &group.curve;
+    let point = curve.add(
+        &curve.scalar_multiply(&group.generator, &u1),
+        &curve.scalar_multiply(public_key, &u2),
+    );
+
+    loop {
+        let private_key = BigUint::random_range(rng, &BigUint::one(), n);
+        let t = &(&u1 + &(&u2 * &private_key)) % n;
+        let t_inverse = match t.modinv(n) {
+            Some(t_inverse) => t_inverse,
+            None => continue,
+        };
+
+        let forged_group = WeierstrassGroup {
+            generator: curve.scalar_multiply(&point, &t_inverse),
+            ..group.clone()
+        };
+        let keypair = forged_group.keypair_from_private(private_key);
+        return Some((forged_group, keypair));
+    }
+}
+
+// A prime p with exactly `bits` bits where p - 1 is twice a product of distinct primes from
+// `pool`, returned with those odd factors.
+pub fn smooth_prime(rng: &mut Rng, bits: usize, pool: &[u32]) -> (BigUint, Vec<u32>) {
+    loop {
+        let mut product = BigUint::from(2u32);
+        let mut factors = Vec::new();
+
+        while product.bits() < bits {
+            let factor = pool[rng.below(pool.len() as u64) as usize];
+            if !factors.contains(&factor) {
+                product = &product * &BigUint::from(factor);
+                factors.push(factor);
+            }
+        }
+
+        // p has the bit length of p - 1, which is even
+        if product.bits() != bits {
+            continue;
+        }
+
+        let p = &product + &BigUint::one();
+        if is_probable_prime(&p, rng) {
+            return (p, factors);
+        }
+    }
+}
+
+// Whether g generates all of (Z/pZ)*, given the odd prime factors of (p - 1) / 2.
+fn is_generator(g: &BigUint, p: &BigUint, factors: &[u32]) -> bool {
+    let p_minus_one = p - &BigUint::one();
+
+    std::iter::once(2)
+        .chain(factors.iter().copied())
+        .all(|factor| {
+            !g.modpow(&(&p_minus_one / &BigUint::from(factor)), p)
+                .is_one()
+        })
+}
+
+// Pohlig-Hellman for a squarefree p - 1 = 2 * factors: the log of h to the base g, a generator,
+// mod each prime factor by brute force in the subgroup of that order, then CRT.
+pub fn discrete_log(g: &BigUint, h: &BigUint, p: &BigUint, factors: &[u32]) -> Option<BigUint> {
+    let p_minus_one = p - &BigUint::one();
+
+    let mut residues = Vec::new();
+    let mut moduli = Vec::new();
+    for factor in std::iter::once(2).chain(factors.iter().copied()) {
+        let modulus = BigUint::from(factor);
+        let exponent = &p_minus_one / &modulus;
+        let g_factor = g.modpow(&exponent, p);
+        let h_factor = h.modpow(&exponent, p);
+
+        let mut candidate = BigUint::one();
+        let mut residue = None;
+        for x in 0..factor {
+            if candidate == h_factor {
+                residue = Some(x);
+                break;
+            }
+            candidate = &(&candidate * &g_factor) % p;
+        }
+
+        residues.push(BigUint::from(residue?));
+        moduli.push(modulus);
+    }
+
+    Some(crt(&residues, &moduli))
+}
+
+// Challenge 61, RSA: for a signature s on the padded message m, find e' and N' with
+// s^e' = m mod N'. Picking primes p and q with smooth p - 1 and q - 1 that s generates makes
+// the discrete logs mod p and q easy, and CRT puts them together into e'. The odd factors of
+// p - 1 and q - 1 are kept disjoint, so the two logs only have to agree mod 2. N' has the bit
+// length of N, so the padding fills the same number of bytes.
+pub fn rsa_key_selection(
+    public_key: &RsaPublicKey,
+    message: &[u8],
+    signature: &BigUint,
+    rng: &mut Rng,
+) -> RsaPublicKey {
+    let bits = public_key.n.bits();
+    let padded = BigUint::from_bytes_be(&encode_signature(message, public_key.size()));
+    let pool = &small_primes(SMOOTHNESS_BOUND)[1..];
+
+    loop {
+        let (p, p_factors) = smooth_prime(rng, bits / 2, pool);
+        if !is_generator(signature, &p, &p_factors) {
+            continue;
+        }
+        let e_p = match discrete_log(signature, &padded, &p, &p_factors) {
+            Some(e_p) => e_p,
+            None => continue,
+        };
+
+        let q_pool: Vec<u32> = pool
+            .iter()
+            .copied()
+            .filter(|factor| !p_factors.contains(factor))
+            .collect();
+
+        for _ in 0..Q_ATTEMPTS {
+            let (q, q_factors) = smooth_prime(rng, bits - bits / 2, &q_pool);
+            let n = &p * &q;
+            if n.bits() != bits || &n <= signature || !is_generator(signature, &q, &q_factors) {
+                continue;
+            }
+
+            let e_q = match discrete_log(signature, &padded, &q, &q_factors) {
+                Some(e_q) => e_q,
+                None => continue,
+            };
+            if e_p.is_odd() != e_q.is_odd() {
+                continue;
+            }
+
+            // e' = e_p mod p - 1 already fixes e' mod 2, so q's half of the system drops it
+            let q_half = &(&q - &BigUint::one()) >> 1;
+            let e = crt(
+                &[e_p.clone(), &e_q % &q_half],
+                &[&p - &BigUint::one(), q_half],
+            );
+            return RsaPublicKey { e, n };
+        }
+    }
+}
@@ -0,0 +1,219 @@
+use crate::hash::md4::{self, INITIAL_STATE, STEPS};
+use crate::random::Rng;
+
+// What a sufficient condition asks of one bit of a register value.
+#[derive(Clone, Copy, Debug)]
+enum Condition {
+    Zero,
+    One,
+    // Equal to the same bit of the register written one step earlier
+    Previous,
+    // Equal to the same bit of the register written two steps earlier
+    TwoBack,
+    // The opposite of the same bit of the register written one step earlier
+    NotPrevious,
+}
+
+use self::Condition::*;
+
+// Wang et al.'s sufficient conditions, as (step, bit, condition) with bits counted from 1 as in
+// the paper. Steps 0-15 are a1, d1, c1, b1, ..., b4; steps 16-31 are a5, d5, ..., b8.
+#[rustfmt::skip]
+const CONDITIONS: &[(usize, u32, Condition)] = &[
+    // a1, d1, c1, b1
+    (0, 7, Previous),
+    (1, 7, Zero), (1, 8, Previous), (1, 11, Previous),
+    (2, 7, One), (2, 8, One), (2, 11, Zero), (2, 26, Previous),
+    (3, 7, One), (3, 8, Zero), (3, 11, Zero), (3, 26, Zero),
+    // a2, d2, c2, b2
+    (4, 8, One), (4, 11, One), (4, 26, Zero), (4, 14, Previous),
+    (5, 14, Zero), (5, 19, Previous), (5, 20, Previous), (5, 21, Previous), (5, 22, Previous), (5, 26, One),
+    (6, 13, Previous), (6, 14, Zero), (6, 15, Previous), (6, 19, Zero), (6, 20, Zero), (6, 21, One),
+    (6, 22, Zero),
+    (7, 13, One), (7, 14, One), (7, 15, Zero), (7, 17, Previous), (7, 19, Zero), (7, 20, Zero),
+    (7, 21, Zero), (7, 22, Zero),
+    // a3, d3, c3, b3
+    (8, 13, One), (8, 14, One), (8, 15, One), (8, 17, Zero), (8, 19, Zero), (8, 20, Zero), (8, 21, Zero),
+    (8, 22, One), (8, 23, Previous), (8, 26, Previous),
+    (9, 13, One), (9, 14, One), (9, 15, One), (9, 17, Zero), (9, 20, Zero), (9, 21, One), (9, 22, One),
+    (9, 23, Zero), (9, 26, One), (9, 30, Previous),
+    (10, 17, One), (10, 20, Zero), (10, 21, Zero), (10, 22, Zero), (10, 23, Zero), (10, 26, Zero),
+    (10, 30, One), (10, 32, Previous),
+    (11, 20, Zero), (11, 21, One), (11, 22, One), (11, 23, Previous), (11, 26, One), (11, 30, Zero),
+    (11, 32, Zero),
+    // a4, d4, c4, b4
+    (12, 23, Zero), (12, 26, Zero), (12, 27, Previous), (12, 29, Previous), (12, 30, One), (12, 32, Zero),
+    (13, 23, Zero), (13, 26, Zero), (13, 27, One), (13, 29, One), (13, 30, Zero), (13, 32, One),
+    (14, 19, Previous), (14, 23, One), (14, 26, One), (14, 27, Zero), (14, 29, Zero), (14, 30, Zero),
+    (15, 19, Zero), (15, 26, One), (15, 27, One), (15, 29, One), (15, 30, Zero),
+    // a5, d5, c5, b5
+    (16, 19, TwoBack), (16, 26, One), (16, 27, Zero), (16, 29, One), (16, 32, One),
+    (17, 19, Previous), (17, 26, TwoBack), (17, 27, TwoBack), (17, 29, TwoBack), (17, 32, TwoBack),
+    (18, 26, Previous), (18, 27, Previous), (18, 29, Previous), (18, 30, Previous), (18, 32, Previous),
+    (19, 29, Previous), (19, 30, One), (19, 32, Zero),
+    // a6, d6, c6
+    (20, 29, One), (20, 32, One),
+    (21, 29, TwoBack),
+    (22, 29, Previous), (22, 30, NotPrevious), (22, 32, NotPrevious),
+    // b9, a10
+    (35, 32, One),
+    (36, 32, One),
+];
+
+const A5: usize = 16;
+const D5: usize = 17;
+
+pub struct Md4Collision {
+    pub first: [u8; 64],
+    pub second: [u8; 64],
+    pub attempts: u64,
+}
+
+// Challenge 55: Wang's differential. Random blocks are massaged so that every round 1
+// condition holds (single-step modification) along with those on a5 and d5 (multi-step
+// modification), leaving the remaining conditions to chance.
+pub fn find_collision(rng: &mut Rng) -> Md4Collision {
+    let mut attempts = 0;
+
+    loop {
+        attempts += 1;
+
+        let mut words = [0; 16];
+        for word in words.iter_mut() {
+            *word = rng.next_u32();
+        }
+
+        modify_round_1(&mut words);
+        correct_a5(&mut words);
+        correct_d5(&mut words);
+
+        let other = differential(&words);
+        if md4::compress(INITIAL_STATE, &words) == md4::compress(INITIAL_STATE, &other) {
+            return Md4Collision {
+                first: md4::words_to_block(&words),
+                second: md4::words_to_block(&other),
+                attempts,
+            };
+        }
+    }
+}
+
+// M' = M + (0, 2^31, 2^31 - 2^28, 0, ..., 0, -2^16, 0, 0, 0)
+fn differential(words: &[u32; 16]) -> [u32; 16] {
+    let mut other = *words;
+    other[1] = other[1].wrapping_add(1 << 31);
+    other[2] = other[2].wrapping_add((1 << 31) - (1 << 28));
+    other[12] = other[12].wrapping_sub(1 << 16);
+    other
+}
+
+fn previous(values: &[u32], step: usize) -> [u32; 4] {
+    [
+        values[step],
+        values[step + 1],
+        values[step + 2],
+        values[step + 3],
+    ]
+}
+
+// The value a condition wants for its bit, as a mask over the register value. `values` holds
+// the chaining state followed by each register value as in `md4::register_values`.
+fn wanted(values: &[u32], step: usize, bit: u32, condition: Condition) -> u32 {
+    let mask = 1 << (bit - 1);
+    match condition {
+        Zero => 0,
+        One => mask,
+        Previous => values[step + 3] & mask,
+        TwoBack => values[step + 2] & mask,
+        NotPrevious => !values[step + 3] & mask,
+    }
+}
+
+// Forces every condition on a step's register value.
+fn enforce(values: &[u32], step: usize, mut value: u32) -> u32 {
+    for &(_, bit, condition) in CONDITIONS.iter().filter(|(index, _, _)| *index == step) {
+        let mask = 1 << (bit - 1);
+        value = (value & !mask) | wanted(values, step, bit, condition);
+    }
+    value
+}
+
+fn holds(values: &[u32; STEPS + 4], step: usize, bit: u32, condition: Condition) -> bool {
+    values[step + 4] & (1 << (bit - 1)) == wanted(values, step, bit, condition)
+}
+
+fn satisfied(values: &[u32; STEPS + 4], through_step: usize) -> bool {
+    CONDITIONS
+        .iter()
+        .take_while(|(step, _, _)| *step <= through_step)
+        .all(|&(step, bit, condition)| holds(values, step, bit, condition))
+}
+
+// Sets each round 1 register value to satisfy its conditions, then solves for the message
+// word that produces it.
+fn modify_round_1(words: &mut [u32; 16]) {
+    let mut values = [0; STEPS + 4];
+    let [a, b, c, d] = INITIAL_STATE;
+    values[..4].copy_from_slice(&[a, d, c, b]);
+
+    for step in 0..16 {
+        let value = md4::step(step, previous(&values, step), words[step]);
+        values[step + 4] = enforce(&values, step, value);
+        words[step] = md4::step_word_for(step, previous(&values, step), values[step + 4]);
+    }
+}
+
+// Replaces the word behind round 1 step `step` so that it produces `value`, then adjusts the
+// next four words so that the rest of round 1 is unchanged.
+fn rewrite_round_1(words: &mut [u32; 16], step: usize, value: u32) {
+    let mut values = md4::register_values(INITIAL_STATE, words);
+    values[step + 4] = value;
+
+    for index in step..step + 5 {
+        words[index] = md4::step_word_for(index, previous(&values, index), values[index + 4]);
+    }
+}
+
+// a5 is computed from m0, so fixing a5 means changing m0 and with it a1. The conditions on a5
+// sit on bits that a1 and its neighbours don't constrain, so round 1 usually survives; when it
+// doesn't, the change is dropped.
+fn correct_a5(words: &mut [u32; 16]) {
+    let values = md4::register_values(INITIAL_STATE, words);
+    let a5 = enforce(&values, A5, values[A5 + 4]);
+    if a5 == values[A5 + 4] {
+        return;
+    }
+
+    let m0 = md4::step_word_for(A5, previous(&values, A5), a5);
+    let mut candidate = *words;
+    rewrite_round_1(&mut candidate, 0, md4::step(0, previous(&values, 0), m0));
+
+    if satisfied(&md4::register_values(INITIAL_STATE, &candidate), A5) {
+        *words = candidate;
+    }
+}
+
+// d5 is computed from m4, which also makes a2. Flipping bit i - 2 of a2 moves m4 by enough to
+// flip bit i of d5, and those bits of a2 are unconstrained, so one bit at a time is corrected
+// by rewriting a2 and the words after it.
+fn correct_d5(words: &mut [u32; 16]) {
+    for &(step, bit, condition) in CONDITIONS.iter().filter(|(step, _, _)| *step == D5) {
+        if holds(
+            &md4::register_values(INITIAL_STATE, words),
+            step,
+            bit,
+            condition,
+        ) {
+            continue;
+        }
+
+        let mut candidate = *words;
+        let a2 = md4::register_values(INITIAL_STATE, words)[8];
+        rewrite_round_1(&mut candidate, 4, a2 ^ (1 << (bit - 3)));
+
+        let values = md4::register_values(INITIAL_STATE, &candidate);
+        if holds(&values, step, bit, condition) && satisfied(&values, A5) {
+            *words = candidate;
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::hash::sha1;
+use crate::math::bigint::BigUint;
+use crate::pubkey::pkcs1::SHA1_DIGEST_INFO;
+use crate::pubkey::rsa::RsaPublicKey;
+
+// Challenge 42: against a verifier that ignores bytes after the hash, put the real
+// 00 01 FF 00 DigestInfo HASH prefix at the top of the block and let the cube root's
+// rounding error land in the unchecked tail. Only works for e = 3.
+pub fn forge_pkcs1_signature(public_key: &RsaPublicKey, message: &[u8]) -> BigUint {
+    if public_key.e != BigUint::from(3u32) {
+        panic!("Signature forgery requires e = 3");
+    }
+
+    let prefix = [
+        &[0x00, 0x01, 0xff, 0x00],
+        &SHA1_DIGEST_INFO[..],
+        &sha1(message),
+    ]
+    .concat();
+    let mut block = prefix;
+    block.resize(public_key.size(), 0x00);
+
+    let target = BigUint::from_bytes_be(&block);
+    let root = target.nth_root(3);
+
+    if root.pow(3) == target {
+        root
+    } else {
+        &root + &BigUint::one()
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::{BTreeMap, HashMap};
+use std::thread;
+
+use crate::oracles::rc4::Rc4CookieServer;
+use crate::random::Rng;
+
+// Keystream bytes 16 and 32 (indices 15 and 31) lean towards 240 and 224 respectively.
+const BIASES: [(usize, u8); 2] = [(15, 0xf0), (31, 0xe0)];
+
+#[derive(Clone, Debug)]
+pub struct BiasAttackConfig {
+    // Encryptions per request alignment; the challenge suggests 2^24 for the full cookie
+    pub trials: u64,
+    pub threads: usize,
+    pub seed: u64,
+}
+
+impl Default for BiasAttackConfig {
+    fn default() -> Self {
+        BiasAttackConfig {
+            trials: 1 << 24,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            seed: 56,
+        }
+    }
+}
+
+// Challenge 56: recovers every byte of the cookie.
+pub fn recover_cookie(server: &Rc4CookieServer, config: &BiasAttackConfig) -> Option<Vec<u8>> {
+    let length = server.encrypt(&[], &mut Rng::new(config.seed)).len();
+    let positions: Vec<usize> = (0..length).collect();
+
+    recover_cookie_bytes(server, &positions, config)
+}
+
+// Recovers the cookie bytes at the given positions. Each position is pushed onto a biased
+// keystream byte by prefixing the request with filler; a single alignment serves one position
+// below 16 through Z16 and another above it through Z32, so positions past 31 are out of reach.
+pub fn recover_cookie_bytes(
+    server: &Rc4CookieServer,
+    positions: &[usize],
+    config: &BiasAttackConfig,
+) -> Option<Vec<u8>> {
+    let mut alignments: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for &position in positions {
+        let (index, _) = BIASES.iter().find(|(index, _)| position <= *index)?;
+        alignments.entry(index - position).or_default().push(*index);
+    }
+
+    let mut recovered: HashMap<usize, u8> = HashMap::new();
+    for (prefix_length, indices) in alignments {
+        let histograms = count_ciphertext_bytes(server, prefix_length, &indices, config);
+
+        for (index, counts) in indices.into_iter().zip(histograms) {
+            let (_, bias) = BIASES.iter().find(|(biased, _)| *biased == index)?;
+            let (most_common, _) = counts.iter().enumerate().max_by_key(|(_, &count)| count)?;
+            recovered.insert(index - prefix_length, most_common as u8 ^ bias);
+        }
+    }
+
+    positions
+        .iter()
+        .map(|position| recovered.get(position).copied())
+        .collect()
+}
+
+// Histograms of the ciphertext bytes at each of `indices`, over many encryptions of
+// `prefix_length` bytes of filler followed by the cookie.
+fn count_ciphertext_bytes(
+    server: &Rc4CookieServer,
+    prefix_length: usize,
+    indices: &[usize],
+    config: &BiasAttackConfig,
+) -> Vec<[u64; 256]> {
+    let threads = config.threads.max(1) as u64;
+    let request = vec![b'A'; prefix_length];
+
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|worker| {
+                let request = &request;
+                let trials = config.trials / threads + u64::from(worker < config.trials % threads);
+                let seed = config.seed ^ (prefix_length as u64) << 32 ^ worker;
+
+                scope.spawn(move || {
+                    let mut rng = Rng::new(seed);
+                    let mut histograms = vec![[0u64; 256]; indices.len()];
+                    for _ in 0..trials {
+                        let ciphertext = server.encrypt(request, &mut rng);
+                        for (counts, &index) in histograms.iter_mut().zip(indices) {
+                            counts[ciphertext[index] as usize] += 1;
+                        }
+                    }
+                    histograms
+                })
+            })
+            .collect();
+
+        let mut histograms = vec![[0u64; 256]; indices.len()];
+        for worker in workers {
+            for (totals, counts) in histograms.iter_mut().zip(worker.join().unwrap()) {
+                for (total, count) in totals.iter_mut().zip(counts) {
+                    *total += count;
+                }
+            }
+        }
+        histograms
+    })
+}
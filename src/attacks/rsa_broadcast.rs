@@ -0,0 +1,18 @@
+use crate::math::bigint::BigUint;
+use crate::math::number_theory::crt;
+use crate::pubkey::rsa::RsaPublicKey;
+
+// Challenge 40: the same message encrypted under three e = 3 keys. CRT gives m^3 modulo
+// n_0 * n_1 * n_2, which is larger than m^3 itself, so an integer cube root recovers m.
+pub fn hastad_broadcast(ciphertexts: &[BigUint], public_keys: &[RsaPublicKey]) -> BigUint {
+    let moduli: Vec<BigUint> = public_keys.iter().map(|key| key.n.clone()).collect();
+    let exponent = public_keys[0].e.to_u64().expect("Exponent must be small") as u32;
+
+    if ciphertexts.len() < exponent as usize
+        || public_keys.iter().any(|key| key.e != public_keys[0].e)
+    {
+        panic!("Need e ciphertexts under keys sharing the same exponent e");
+    }
+
+    crt(ciphertexts, &moduli).nth_root(exponent)
+}
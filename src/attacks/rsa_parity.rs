@@ -0,0 +1,36 @@
+use crate::math::bigint::BigUint;
+use crate::oracles::rsa::RsaParityOracle;
+
+// Challenge 46: doubling the plaintext (multiplying the ciphertext by 2^e) and asking for
+// its parity reveals whether it wrapped the odd modulus, halving the interval the plaintext
+// can lie in. After one step per modulus bit the interval contains a single integer.
+//
+// The interval is tracked exactly as [lower * n / 2^i, (lower + 1) * n / 2^i), and
+// `progress` is handed its current upper bound after every oracle call.
+pub fn parity_oracle_attack<O, F>(oracle: &mut O, ciphertext: &BigUint, mut progress: F) -> BigUint
+where
+    O: RsaParityOracle,
+    F: FnMut(&BigUint),
+{
+    let public_key = oracle.public_key().clone();
+    let n = &public_key.n;
+    let doubler = public_key.encrypt(&BigUint::from(2u32));
+
+    let mut ciphertext = ciphertext.clone();
+    let mut lower = BigUint::zero();
+    let steps = n.bits();
+
+    for step in 1..=steps {
+        ciphertext = &(&ciphertext * &doubler) % n;
+        lower = &lower << 1;
+        if !oracle.is_plaintext_even(&ciphertext) {
+            lower = &lower + &BigUint::one();
+        }
+
+        progress(&(&(&(&lower + &BigUint::one()) * n) >> step));
+    }
+
+    // The only integer in the final interval is the ceiling of its lower end
+    let scale = BigUint::one() << steps;
+    &(&(&lower * n) + &(&scale - &BigUint::one())) >> steps
+}
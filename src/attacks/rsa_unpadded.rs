@@ -0,0 +1,26 @@
+use crate::math::bigint::BigUint;
+use crate::oracles::rsa::RsaDecryptionOracle;
+use crate::random::Rng;
+
+// Challenge 41: blind the ciphertext as C' = S^e * C so the oracle sees something new,
+// then unblind its answer P' = S * P by multiplying with S^-1 mod N.
+pub fn recover_unpadded_message<O: RsaDecryptionOracle>(
+    oracle: &mut O,
+    ciphertext: &BigUint,
+    rng: &mut Rng,
+) -> Option<BigUint> {
+    let public_key = oracle.public_key().clone();
+    let n = &public_key.n;
+
+    let (s, s_inverse) = loop {
+        let s = BigUint::random_range(rng, &BigUint::from(2u32), n);
+        if let Some(inverse) = s.modinv(n) {
+            break (s, inverse);
+        }
+    };
+
+    let blinded = &(&public_key.encrypt(&s) * ciphertext) % n;
+    let blinded_plaintext = oracle.decrypt(&blinded)?;
+
+    Some(&(&blinded_plaintext * &s_inverse) % n)
+}
@@ -0,0 +1,61 @@
+use crate::math::bigint::BigUint;
+use crate::protocols::channel::Endpoint;
+use crate::protocols::srp::{password_exponent, session_proof, SrpMessage, SrpParameters};
+
+// Challenge 37: with A = 0 (or any multiple of N) the server computes S = (A * v^u)^b = 0,
+// so the proof for S = 0 is accepted without knowing the password.
+pub fn zero_key_login(endpoint: &Endpoint<SrpMessage>, email: &str, public_key: BigUint) -> bool {
+    endpoint.send(SrpMessage::Hello {
+        email: email.to_string(),
+        public_key,
+    });
+
+    let salt = match endpoint.receive() {
+        Some(SrpMessage::Challenge { salt, .. }) => salt,
+        message => panic!("Expected a challenge, got {:?}", message),
+    };
+
+    endpoint.send(SrpMessage::Proof(session_proof(&BigUint::zero(), &salt)));
+
+    match endpoint.receive() {
+        Some(SrpMessage::Verdict(accepted)) => accepted,
+        message => panic!("Expected a verdict, got {:?}", message),
+    }
+}
+
+// Challenge 38: pose as a simplified-SRP server with b = 1, B = g and u = 1. The client's
+// proof then depends only on A, the salt and the password, since S = A * v mod N with
+// v = g^x, so the password can be cracked offline.
+pub fn simplified_srp_dictionary_attack(
+    endpoint: &Endpoint<SrpMessage>,
+    parameters: &SrpParameters,
+    wordlist: &[&str],
+) -> Option<String> {
+    let client_public_key = match endpoint.receive() {
+        Some(SrpMessage::Hello { public_key, .. }) => public_key,
+        message => panic!("Expected a hello, got {:?}", message),
+    };
+
+    let salt = Vec::new();
+    endpoint.send(SrpMessage::SimplifiedChallenge {
+        salt: salt.clone(),
+        public_key: parameters.g.clone(),
+        u: BigUint::one(),
+    });
+
+    let proof = match endpoint.receive() {
+        Some(SrpMessage::Proof(proof)) => proof,
+        message => panic!("Expected a proof, got {:?}", message),
+    };
+    endpoint.send(SrpMessage::Verdict(true));
+
+    let SrpParameters { n, g, .. } = parameters;
+    wordlist
+        .iter()
+        .find(|word| {
+            let verifier = g.modpow(&password_exponent(&salt, word), n);
+            let shared_secret = &(&client_public_key * &verifier) % n;
+            session_proof(&shared_secret, &salt) == proof
+        })
+        .map(|word| word.to_string())
+}
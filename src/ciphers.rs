@@ -1,3 +1,8 @@
+pub mod aes;
+pub mod cbc;
+pub mod ctr;
+pub mod gcm;
+pub mod rc4;
 pub mod repeating_key_xor;
 pub mod single_byte_xor;
 
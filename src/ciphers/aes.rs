@@ -0,0 +1,255 @@
+pub const BLOCK_SIZE: usize = 16;
+
+#[rustfmt::skip]
+const SBOX: [u8; 256] = [
+    0x63, 0x7c, 0x77, 0x7b, 0xf2, 0x6b, 0x6f, 0xc5, 0x30, 0x01, 0x67, 0x2b, 0xfe, 0xd7, 0xab, 0x76,
+    0xca, 0x82, 0xc9, 0x7d, 0xfa, 0x59, 0x47, 0xf0, 0xad, 0xd4, 0xa2, 0xaf, 0x9c, 0xa4, 0x72, 0xc0,
+    0xb7, 0xfd, 0x93, 0x26, 0x36, 0x3f, 0xf7, 0xcc, 0x34, 0xa5, 0xe5, 0xf1, 0x71, 0xd8, 0x31, 0x15,
+    0x04, 0xc7, 0x23, 0xc3, 0x18, 0x96, 0x05, 0x9a, 0x07, 0x12, 0x80, 0xe2, 0xeb, 0x27, 0xb2, 0x75,
+    0x09, 0x83, 0x2c, 0x1a, 0x1b, 0x6e, 0x5a, 0xa0, 0x52, 0x3b, 0xd6, 0xb3, 0x29, 0xe3, 0x2f, 0x84,
+    0x53, 0xd1, 0x00, 0xed, 0x20, 0xfc, 0xb1, 0x5b, 0x6a, 0xcb, 0xbe, 0x39, 0x4a, 0x4c, 0x58, 0xcf,
+    0xd0, 0xef, 0xaa, 0xfb, 0x43, 0x4d, 0x33, 0x85, 0x45, 0xf9, 0x02, 0x7f, 0x50, 0x3c, 0x9f, 0xa8,
+    0x51, 0xa3, 0x40, 0x8f, 0x92, 0x9d, 0x38, 0xf5, 0xbc, 0xb6, 0xda, 0x21, 0x10, 0xff, 0xf3, 0xd2,
+    0xcd, 0x0c, 0x13, 0xec, 0x5f, 0x97, 0x44, 0x17, 0xc4, 0xa7, 0x7e, 0x3d, 0x64, 0x5d, 0x19, 0x73,
+    0x60, 0x81, 0x4f, 0xdc, 0x22, 0x2a, 0x90, 0x88, 0x46, 0xee, 0xb8, 0x14, 0xde, 0x5e, 0x0b, 0xdb,
+    0xe0, 0x32, 0x3a, 0x0a, 0x49, 0x06, 0x24, 0x5c, 0xc2, 0xd3, 0xac, 0x62, 0x91, 0x95, 0xe4, 0x79,
+    0xe7, 0xc8, 0x37, 0x6d, 0x8d, 0xd5, 0x4e, 0xa9, 0x6c, 0x56, 0xf4, 0xea, 0x65, 0x7a, 0xae, 0x08,
+    0xba, 0x78, 0x25, 0x2e, 0x1c, 0xa6, 0xb4, 0xc6, 0xe8, 0xdd, 0x74, 0x1f, 0x4b, 0xbd, 0x8b, 0x8a,
+    0x70, 0x3e, 0xb5, 0x66, 0x48, 0x03, 0xf6, 0x0e, 0x61, 0x35, 0x57, 0xb9, 0x86, 0xc1, 0x1d, 0x9e,
+    0xe1, 0xf8, 0x98, 0x11, 0x69, 0xd9, 0x8e, 0x94, 0x9b, 0x1e, 0x87, 0xe9, 0xce, 0x55, 0x28, 0xdf,
+    0x8c, 0xa1, 0x89, 0x0d, 0xbf, 0xe6, 0x42, 0x68, 0x41, 0x99, 0x2d, 0x0f, 0xb0, 0x54, 0xbb, 0x16,
+];
+
+const INVERSE_SBOX: [u8; 256] = invert_sbox();
+
+const fn invert_sbox() -> [u8; 256] {
+    let mut inverse = [0; 256];
+    let mut index = 0;
+    while index < 256 {
+        inverse[SBOX[index] as usize] = index as u8;
+        index += 1;
+    }
+    inverse
+}
+
+// AES with a 128, 192 or 256-bit key, operating on single 16-byte blocks. Modes of operation
+// live in their own modules and build on `encrypt_block` / `decrypt_block`.
+#[derive(Clone)]
+pub struct Aes {
+    round_keys: Vec<[u8; 16]>,
+}
+
+impl Aes {
+    pub fn new(key: &[u8]) -> Self {
+        let key_words = match key.len() {
+            16 | 24 | 32 => key.len() / 4,
+            length => panic!("Invalid AES key length: {}", length),
+        };
+        let rounds = key_words + 6;
+        let total_words = 4 * (rounds + 1);
+
+        let mut words: Vec<[u8; 4]> = key
+            .chunks(4)
+            .map(|chunk| [chunk[0], chunk[1], chunk[2], chunk[3]])
+            .collect();
+
+        let mut round_constant = 1u8;
+        for index in key_words..total_words {
+            let mut word = words[index - 1];
+
+            if index % key_words == 0 {
+                word = [
+                    SBOX[word[1] as usize] ^ round_constant,
+                    SBOX[word[2] as usize],
+                    SBOX[word[3] as usize],
+                    SBOX[word[0] as usize],
+                ];
+                round_constant = xtime(round_constant);
+            } else if key_words > 6 && index % key_words == 4 {
+                word = [
+                    SBOX[word[0] as usize],
+                    SBOX[word[1] as usize],
+                    SBOX[word[2] as usize],
+                    SBOX[word[3] as usize],
+                ];
+            }
+
+            let previous = words[index - key_words];
+            words.push([
+                word[0] ^ previous[0],
+                word[1] ^ previous[1],
+                word[2] ^ previous[2],
+                word[3] ^ previous[3],
+            ]);
+        }
+
+        let round_keys = words
+            .chunks(4)
+            .map(|chunk| {
+                let mut round_key = [0; 16];
+                for (index, word) in chunk.iter().enumerate() {
+                    round_key[index * 4..index * 4 + 4].copy_from_slice(word);
+                }
+                round_key
+            })
+            .collect();
+
+        Aes { round_keys }
+    }
+
+    pub fn encrypt_block(&self, block: &mut [u8; 16]) {
+        let rounds = self.round_keys.len() - 1;
+
+        add_round_key(block, &self.round_keys[0]);
+        for round in 1..rounds {
+            sub_bytes(block, &SBOX);
+            shift_rows(block);
+            mix_columns(block);
+            add_round_key(block, &self.round_keys[round]);
+        }
+        sub_bytes(block, &SBOX);
+        shift_rows(block);
+        add_round_key(block, &self.round_keys[rounds]);
+    }
+
+    pub fn decrypt_block(&self, block: &mut [u8; 16]) {
+        let rounds = self.round_keys.len() - 1;
+
+        add_round_key(block, &self.round_keys[rounds]);
+        for round in (1..rounds).rev() {
+            inverse_shift_rows(block);
+            sub_bytes(block, &INVERSE_SBOX);
+            add_round_key(block, &self.round_keys[round]);
+            inverse_mix_columns(block);
+        }
+        inverse_shift_rows(block);
+        sub_bytes(block, &INVERSE_SBOX);
+        add_round_key(block, &self.round_keys[0]);
+    }
+
+    pub fn encrypt(&self, block: &[u8]) -> [u8; 16] {
+        let mut result = to_block(block);
+        self.encrypt_block(&mut result);
+        result
+    }
+
+    pub fn decrypt(&self, block: &[u8]) -> [u8; 16] {
+        let mut result = to_block(block);
+        self.decrypt_block(&mut result);
+        result
+    }
+}
+
+pub fn to_block(bytes: &[u8]) -> [u8; 16] {
+    let mut block = [0; 16];
+    block.copy_from_slice(bytes);
+    block
+}
+
+fn xtime(byte: u8) -> u8 {
+    (byte << 1) ^ if byte & 0x80 != 0 { 0x1b } else { 0 }
+}
+
+fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut result = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            result ^= a;
+        }
+        a = xtime(a);
+        b >>= 1;
+    }
+    result
+}
+
+fn add_round_key(block: &mut [u8; 16], round_key: &[u8; 16]) {
+    for (byte, key_byte) in block.iter_mut().zip(round_key.iter()) {
+        *byte ^= key_byte;
+    }
+}
+
+fn sub_bytes(block: &mut [u8; 16], table: &[u8; 256]) {
+    for byte in block.iter_mut() {
+        *byte = table[*byte as usize];
+    }
+}
+
+// The state is column-major: byte (row, column) lives at index 4 * column + row.
+fn shift_rows(block: &mut [u8; 16]) {
+    let original = *block;
+    for column in 0..4 {
+        for row in 1..4 {
+            block[4 * column + row] = original[4 * ((column + row) % 4) + row];
+        }
+    }
+}
+
+fn inverse_shift_rows(block: &mut [u8; 16]) {
+    let original = *block;
+    for column in 0..4 {
+        for row in 1..4 {
+            block[4 * ((column + row) % 4) + row] = original[4 * column + row];
+        }
+    }
+}
+
+fn mix_columns(block: &mut [u8; 16]) {
+    for column in block.chunks_mut(4) {
+        let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+        let all = a0 ^ a1 ^ a2 ^ a3;
+        column[0] ^= all ^ xtime(a0 ^ a1);
+        column[1] ^= all ^ xtime(a1 ^ a2);
+        column[2] ^= all ^ xtime(a2 ^ a3);
+        column[3] ^= all ^ xtime(a3 ^ a0);
+    }
+}
+
+fn inverse_mix_columns(block: &mut [u8; 16]) {
+    for column in block.chunks_mut(4) {
+        let [a0, a1, a2, a3] = [column[0], column[1], column[2], column[3]];
+        column[0] = gf_mul(a0, 14) ^ gf_mul(a1, 11) ^ gf_mul(a2, 13) ^ gf_mul(a3, 9);
+        column[1] = gf_mul(a0, 9) ^ gf_mul(a1, 14) ^ gf_mul(a2, 11) ^ gf_mul(a3, 13);
+        column[2] = gf_mul(a0, 13) ^ gf_mul(a1, 9) ^ gf_mul(a2, 14) ^ gf_mul(a3, 11);
+        column[3] = gf_mul(a0, 11) ^ gf_mul(a1, 13) ^ gf_mul(a2, 9) ^ gf_mul(a3, 14);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::hex::{bytes_to_hex_string, hex_string_to_bytes};
+
+    fn check_vector(key: &str, expected: &str) {
+        let aes = Aes::new(&hex_string_to_bytes(key));
+        let plaintext = hex_string_to_bytes("00112233445566778899aabbccddeeff");
+
+        let ciphertext = aes.encrypt(&plaintext);
+        assert_eq!(bytes_to_hex_string(&ciphertext), expected);
+        assert_eq!(aes.decrypt(&ciphertext).to_vec(), plaintext);
+    }
+
+    // FIPS-197 Appendix C
+    #[test]
+    fn test_aes_128() {
+        check_vector(
+            "000102030405060708090a0b0c0d0e0f",
+            "69c4e0d86a7b0430d8cdb78070b4c55a",
+        );
+    }
+
+    #[test]
+    fn test_aes_192() {
+        check_vector(
+            "000102030405060708090a0b0c0d0e0f1011121314151617",
+            "dda97ca4864cdfe06eaf70a0ec0d7191",
+        );
+    }
+
+    #[test]
+    fn test_aes_256() {
+        check_vector(
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+            "8ea2b7ca516745bfeafc49904b496089",
+        );
+    }
+}
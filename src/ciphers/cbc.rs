@@ -0,0 +1,84 @@
+use crate::byte_operations::bytes_xor;
+use crate::ciphers::aes::{to_block, Aes, BLOCK_SIZE};
+use crate::padding::{pkcs7_pad, pkcs7_unpad};
+
+// AES-CBC over input that is already a whole number of blocks.
+pub fn cbc_encrypt_blocks(key: &[u8], iv: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    if !plaintext.len().is_multiple_of(BLOCK_SIZE) {
+        panic!("Plaintext must be a multiple of the block size");
+    }
+
+    let aes = Aes::new(key);
+    let mut previous = to_block(iv);
+    let mut result = Vec::with_capacity(plaintext.len());
+
+    for block in plaintext.chunks(BLOCK_SIZE) {
+        previous = aes.encrypt(&bytes_xor(block, &previous));
+        result.extend_from_slice(&previous);
+    }
+
+    result
+}
+
+pub fn cbc_decrypt_blocks(key: &[u8], iv: &[u8], ciphertext: &[u8]) -> Vec<u8> {
+    if !ciphertext.len().is_multiple_of(BLOCK_SIZE) {
+        panic!("Ciphertext must be a multiple of the block size");
+    }
+
+    let aes = Aes::new(key);
+    let mut previous = iv;
+    let mut result = Vec::with_capacity(ciphertext.len());
+
+    for block in ciphertext.chunks(BLOCK_SIZE) {
+        result.extend(bytes_xor(&aes.decrypt(block), previous));
+        previous = block;
+    }
+
+    result
+}
+
+pub fn cbc_encrypt(key: &[u8], iv: &[u8], plaintext: &[u8]) -> Vec<u8> {
+    cbc_encrypt_blocks(key, iv, &pkcs7_pad(plaintext, BLOCK_SIZE))
+}
+
+// Returns None if the ciphertext is malformed or the decrypted padding is invalid.
+pub fn cbc_decrypt(key: &[u8], iv: &[u8], ciphertext: &[u8]) -> Option<Vec<u8>> {
+    if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(BLOCK_SIZE) {
+        return None;
+    }
+
+    pkcs7_unpad(&cbc_decrypt_blocks(key, iv, ciphertext), BLOCK_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::hex::{bytes_to_hex_string, hex_string_to_bytes};
+
+    // NIST SP 800-38A F.2.1
+    #[test]
+    fn test_cbc_known_answer() {
+        let key = hex_string_to_bytes("2b7e151628aed2a6abf7158809cf4f3c");
+        let iv = hex_string_to_bytes("000102030405060708090a0b0c0d0e0f");
+        let plaintext =
+            hex_string_to_bytes("6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51");
+
+        let ciphertext = cbc_encrypt_blocks(&key, &iv, &plaintext);
+        assert_eq!(
+            bytes_to_hex_string(&ciphertext),
+            "7649abac8119b246cee98e9b12e9197d5086cb9b507219ee95db113a917678b2"
+        );
+        assert_eq!(cbc_decrypt_blocks(&key, &iv, &ciphertext), plaintext);
+    }
+
+    #[test]
+    fn test_cbc_padded_roundtrip() {
+        let key = b"YELLOW SUBMARINE";
+        let iv = [7; 16];
+        let message = b"Burning 'em, if you ain't quick and nimble";
+
+        let ciphertext = cbc_encrypt(key, &iv, message);
+        assert_eq!(ciphertext.len(), 48);
+        assert_eq!(cbc_decrypt(key, &iv, &ciphertext), Some(message.to_vec()));
+    }
+}
@@ -0,0 +1,44 @@
+use crate::ciphers::aes::{Aes, BLOCK_SIZE};
+
+// AES-CTR with the Cryptopals counter format: a 64-bit little-endian nonce followed by a
+// 64-bit little-endian block count. Encryption and decryption are the same operation.
+pub fn ctr(key: &[u8], nonce: u64, data: &[u8]) -> Vec<u8> {
+    let aes = Aes::new(key);
+
+    data.chunks(BLOCK_SIZE)
+        .enumerate()
+        .flat_map(|(counter, chunk)| {
+            let mut block = [0; BLOCK_SIZE];
+            block[..8].copy_from_slice(&nonce.to_le_bytes());
+            block[8..].copy_from_slice(&(counter as u64).to_le_bytes());
+            aes.encrypt_block(&mut block);
+
+            chunk
+                .iter()
+                .zip(block)
+                .map(|(byte, key_byte)| byte ^ key_byte)
+                .collect::<Vec<u8>>()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::base64::base64_to_bytes;
+
+    // Challenge 18
+    #[test]
+    fn test_ctr_known_answer() {
+        let ciphertext = base64_to_bytes(
+            "L77na/nrFsKvynd6HzOoG7GHTLXsTVu9qvY/2syLXzhPweyyMTJULu/6/kXX0KSvoOLSFQ==",
+        );
+        let plaintext = ctr(b"YELLOW SUBMARINE", 0, &ciphertext);
+
+        assert_eq!(
+            plaintext,
+            b"Yo, VIP Let's kick it Ice, Ice, baby Ice, Ice, baby "
+        );
+        assert_eq!(ctr(b"YELLOW SUBMARINE", 0, &plaintext), ciphertext);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::ciphers::aes::{Aes, BLOCK_SIZE};
+use crate::math::gf128::Gf128;
+
+pub const NONCE_SIZE: usize = 12;
+pub const TAG_SIZE: usize = 16;
+
+// The blocks GHASH runs over: the associated data and the ciphertext, each zero-padded to a
+// whole block, then a block holding their lengths in bits.
+pub fn ghash_blocks(associated_data: &[u8], ciphertext: &[u8]) -> Vec<Gf128> {
+    let mut length_block = [0; BLOCK_SIZE];
+    length_block[..8].copy_from_slice(&(8 * associated_data.len() as u64).to_be_bytes());
+    length_block[8..].copy_from_slice(&(8 * ciphertext.len() as u64).to_be_bytes());
+
+    associated_data
+        .chunks(BLOCK_SIZE)
+        .chain(ciphertext.chunks(BLOCK_SIZE))
+        .chain(std::iter::once(&length_block[..]))
+        .map(Gf128::from_bytes)
+        .collect()
+}
+
+// GHASH reads its blocks as the coefficients of a polynomial in the authentication key h,
+// with no constant term: the length block is multiplied by h, the block before it by h^2, and
+// so on.
+pub fn ghash(h: Gf128, associated_data: &[u8], ciphertext: &[u8]) -> Gf128 {
+    ghash_blocks(associated_data, ciphertext)
+        .into_iter()
+        .fold(Gf128::ZERO, |sum, block| (sum + block) * h)
+}
+
+// The authentication key, the encryption of the all-zero block.
+pub fn authentication_key(aes: &Aes) -> Gf128 {
+    Gf128::from_bytes(&aes.encrypt(&[0; BLOCK_SIZE]))
+}
+
+// The counter block for a 96-bit nonce: the nonce followed by a 32-bit big-endian counter.
+// Counter 1 masks the tag and the keystream starts at 2.
+fn counter_block(nonce: &[u8; NONCE_SIZE], counter: u32) -> [u8; BLOCK_SIZE] {
+    let mut block = [0; BLOCK_SIZE];
+    block[..NONCE_SIZE].copy_from_slice(nonce);
+    block[NONCE_SIZE..].copy_from_slice(&counter.to_be_bytes());
+    block
+}
+
+fn keystream_xor(aes: &Aes, nonce: &[u8; NONCE_SIZE], data: &[u8]) -> Vec<u8> {
+    data.chunks(BLOCK_SIZE)
+        .enumerate()
+        .flat_map(|(index, chunk)| {
+            let keystream = aes.encrypt(&counter_block(nonce, index as u32 + 2));
+            chunk
+                .iter()
+                .zip(keystream)
+                .map(|(byte, key_byte)| byte ^ key_byte)
+                .collect::<Vec<u8>>()
+        })
+        .collect()
+}
+
+fn tag(
+    aes: &Aes,
+    nonce: &[u8; NONCE_SIZE],
+    associated_data: &[u8],
+    ciphertext: &[u8],
+) -> [u8; TAG_SIZE] {
+    let mask = Gf128::from_bytes(&aes.encrypt(&counter_block(nonce, 1)));
+    (ghash(authentication_key(aes), associated_data, ciphertext) + mask).to_bytes()
+}
+
+// AES-GCM with a 96-bit nonce. Returns the ciphertext and the full 16-byte tag.
+pub fn gcm_encrypt(
+    key: &[u8],
+    nonce: &[u8; NONCE_SIZE],
+    associated_data: &[u8],
+    plaintext: &[u8],
+) -> (Vec<u8>, [u8; TAG_SIZE]) {
+    let aes = Aes::new(key);
+    let ciphertext = keystream_xor(&aes, nonce, plaintext);
+    let tag = tag(&aes, nonce, associated_data, &ciphertext);
+    (ciphertext, tag)
+}
+
+// Checks the tag before decrypting anything. The tag may be truncated, in which case only
+// the bytes given are compared.
+pub fn gcm_decrypt(
+    key: &[u8],
+    nonce: &[u8; NONCE_SIZE],
+    associated_data: &[u8],
+    ciphertext: &[u8],
+    tag_bytes: &[u8],
+) -> Option<Vec<u8>> {
+    if tag_bytes.is_empty() || tag_bytes.len() > TAG_SIZE {
+        return None;
+    }
+
+    let aes = Aes::new(key);
+    let expected = tag(&aes, nonce, associated_data, ciphertext);
+    if expected[..tag_bytes.len()] != *tag_bytes {
+        return None;
+    }
+
+    Some(keystream_xor(&aes, nonce, ciphertext))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::hex::{bytes_to_hex_string, hex_string_to_bytes};
+
+    // Test cases 1 and 2 from the GCM specification: an all-zero key and nonce
+    #[test]
+    fn test_gcm_known_answers() {
+        let key = [0; 16];
+        let nonce = [0; NONCE_SIZE];
+
+        let (ciphertext, tag) = gcm_encrypt(&key, &nonce, &[], &[]);
+        assert!(ciphertext.is_empty());
+        assert_eq!(
+            bytes_to_hex_string(&tag),
+            "58e2fccefa7e3061367f1d57a4e7455a"
+        );
+
+        let (ciphertext, tag) = gcm_encrypt(&key, &nonce, &[], &[0; 16]);
+        assert_eq!(
+            bytes_to_hex_string(&ciphertext),
+            "0388dace60b6a392f328c2b971b2fe78"
+        );
+        assert_eq!(
+            bytes_to_hex_string(&tag),
+            "ab6e47d42cec13bdf53a67b21257bddf"
+        );
+    }
+
+    #[test]
+    fn test_gcm_roundtrip_and_tampering() {
+        let key = hex_string_to_bytes("feffe9928665731c6d6a8f9467308308");
+        let nonce = [7; NONCE_SIZE];
+        let plaintext = b"attack at dawn, or maybe a little after breakfast";
+
+        let (mut ciphertext, tag) = gcm_encrypt(&key, &nonce, b"header", plaintext);
+        assert_eq!(
+            gcm_decrypt(&key, &nonce, b"header", &ciphertext, &tag).unwrap(),
+            plaintext
+        );
+        assert!(gcm_decrypt(&key, &nonce, b"header", &ciphertext, &tag[..4]).is_some());
+        assert!(gcm_decrypt(&key, &nonce, b"footer", &ciphertext, &tag).is_none());
+
+        ciphertext[20] ^= 1;
+        assert!(gcm_decrypt(&key, &nonce, b"header", &ciphertext, &tag).is_none());
+    }
+}
@@ -0,0 +1,69 @@
+pub struct Rc4 {
+    state: [u8; 256],
+    i: u8,
+    j: u8,
+}
+
+impl Rc4 {
+    pub fn new(key: &[u8]) -> Self {
+        if key.is_empty() || key.len() > 256 {
+            panic!("Invalid RC4 key length: {}", key.len());
+        }
+
+        let mut state = [0; 256];
+        for (index, byte) in state.iter_mut().enumerate() {
+            *byte = index as u8;
+        }
+
+        let mut j = 0u8;
+        for (index, key_byte) in (0..256).zip(key.iter().cycle()) {
+            j = j.wrapping_add(state[index]).wrapping_add(*key_byte);
+            state.swap(index, j as usize);
+        }
+
+        Rc4 { state, i: 0, j: 0 }
+    }
+
+    pub fn next_byte(&mut self) -> u8 {
+        self.i = self.i.wrapping_add(1);
+        self.j = self.j.wrapping_add(self.state[self.i as usize]);
+        self.state.swap(self.i as usize, self.j as usize);
+
+        let index = self.state[self.i as usize].wrapping_add(self.state[self.j as usize]);
+        self.state[index as usize]
+    }
+
+    pub fn apply_keystream(&mut self, data: &mut [u8]) {
+        for byte in data.iter_mut() {
+            *byte ^= self.next_byte();
+        }
+    }
+}
+
+// Encryption and decryption are the same operation.
+pub fn rc4(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut result = data.to_vec();
+    Rc4::new(key).apply_keystream(&mut result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::hex::bytes_to_hex_string;
+
+    #[test]
+    fn test_rc4_vectors() {
+        let vectors: [(&[u8], &[u8], &str); 3] = [
+            (b"Key", b"Plaintext", "bbf316e8d940af0ad3"),
+            (b"Wiki", b"pedia", "1021bf0420"),
+            (b"Secret", b"Attack at dawn", "45a01f645fc35b383552544b9bf5"),
+        ];
+
+        for (key, plaintext, expected) in vectors {
+            let ciphertext = rc4(key, plaintext);
+            assert_eq!(bytes_to_hex_string(&ciphertext), expected);
+            assert_eq!(rc4(key, &ciphertext), plaintext);
+        }
+    }
+}
@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+
+// Raw DEFLATE (RFC 1951), enough to stand in for zlib in the compression side channel
+// challenges. The compressor does greedy LZ77 matching over a 32 KiB window and emits a single
+// block with the fixed Huffman codes; the decompressor handles stored and fixed blocks only.
+
+const WINDOW_SIZE: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 256;
+const END_OF_BLOCK: u16 = 256;
+
+#[rustfmt::skip]
+const LENGTH_BASES: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31,
+    35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258,
+];
+
+#[rustfmt::skip]
+const LENGTH_EXTRA_BITS: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2,
+    3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+#[rustfmt::skip]
+const DISTANCE_BASES: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193,
+    257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+#[rustfmt::skip]
+const DISTANCE_EXTRA_BITS: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6,
+    7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13,
+];
+
+pub fn compress(bytes: &[u8]) -> Vec<u8> {
+    let mut writer = BitWriter::default();
+    writer.write_bits(1, 1); // BFINAL
+    writer.write_bits(1, 2); // BTYPE = fixed Huffman
+
+    let mut chains: HashMap<[u8; 3], Vec<usize>> = HashMap::new();
+    let mut position = 0;
+    while position < bytes.len() {
+        let (length, distance) = longest_match(bytes, position, &chains);
+
+        let advance = if length >= MIN_MATCH {
+            write_match(&mut writer, length, distance);
+            length
+        } else {
+            write_symbol(&mut writer, bytes[position] as u16);
+            1
+        };
+
+        for index in position..position + advance {
+            if let Some(key) = prefix(bytes, index) {
+                chains.entry(key).or_default().push(index);
+            }
+        }
+        position += advance;
+    }
+
+    write_symbol(&mut writer, END_OF_BLOCK);
+    writer.finish()
+}
+
+// Returns None for malformed input or dynamic Huffman blocks, which compress never produces.
+pub fn decompress(bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut reader = BitReader::new(bytes);
+    let mut output = Vec::new();
+
+    loop {
+        let is_final = reader.read_bits(1)? == 1;
+        match reader.read_bits(2)? {
+            0 => {
+                reader.align();
+                let length = reader.read_bits(16)? as u16;
+                let complement = reader.read_bits(16)? as u16;
+                if length != !complement {
+                    return None;
+                }
+                for _ in 0..length {
+                    output.push(reader.read_bits(8)? as u8);
+                }
+            }
+            1 => loop {
+                let symbol = read_fixed_symbol(&mut reader)?;
+                if symbol < END_OF_BLOCK {
+                    output.push(symbol as u8);
+                    continue;
+                } else if symbol == END_OF_BLOCK {
+                    break;
+                }
+
+                let length_code = (symbol - 257) as usize;
+                let length = *LENGTH_BASES.get(length_code)? as usize
+                    + reader.read_bits(LENGTH_EXTRA_BITS[length_code])? as usize;
+
+                let distance_code = reverse_bits(reader.read_bits(5)?, 5) as usize;
+                let distance = *DISTANCE_BASES.get(distance_code)? as usize
+                    + reader.read_bits(DISTANCE_EXTRA_BITS[distance_code])? as usize;
+                if distance > output.len() {
+                    return None;
+                }
+
+                let start = output.len() - distance;
+                for index in start..start + length {
+                    output.push(output[index]);
+                }
+            },
+            _ => return None,
+        }
+
+        if is_final {
+            return Some(output);
+        }
+    }
+}
+
+fn prefix(bytes: &[u8], position: usize) -> Option<[u8; 3]> {
+    bytes
+        .get(position..position + MIN_MATCH)
+        .map(|slice| [slice[0], slice[1], slice[2]])
+}
+
+// The longest earlier match within the window, preferring the nearest on ties.
+fn longest_match(
+    bytes: &[u8],
+    position: usize,
+    chains: &HashMap<[u8; 3], Vec<usize>>,
+) -> (usize, usize) {
+    let candidates = match prefix(bytes, position).and_then(|key| chains.get(&key)) {
+        Some(candidates) => candidates,
+        None => return (0, 0),
+    };
+
+    let max_length = MAX_MATCH.min(bytes.len() - position);
+    let mut best = (0, 0);
+    for &start in candidates.iter().rev().take(MAX_CHAIN) {
+        if position - start > WINDOW_SIZE {
+            break;
+        }
+
+        let length = (0..max_length)
+            .take_while(|&offset| bytes[start + offset] == bytes[position + offset])
+            .count();
+        if length > best.0 {
+            best = (length, position - start);
+        }
+    }
+
+    best
+}
+
+fn write_match(writer: &mut BitWriter, length: usize, distance: usize) {
+    let length_code = LENGTH_BASES
+        .iter()
+        .rposition(|&base| base as usize <= length)
+        .unwrap();
+    write_symbol(writer, 257 + length_code as u16);
+    writer.write_bits(
+        (length - LENGTH_BASES[length_code] as usize) as u32,
+        LENGTH_EXTRA_BITS[length_code],
+    );
+
+    let distance_code = DISTANCE_BASES
+        .iter()
+        .rposition(|&base| base as usize <= distance)
+        .unwrap();
+    writer.write_bits(reverse_bits(distance_code as u32, 5), 5);
+    writer.write_bits(
+        (distance - DISTANCE_BASES[distance_code] as usize) as u32,
+        DISTANCE_EXTRA_BITS[distance_code],
+    );
+}
+
+// The fixed literal/length code from RFC 1951 section 3.2.6.
+fn write_symbol(writer: &mut BitWriter, symbol: u16) {
+    let (code, length) = match symbol {
+        0..=143 => (0x30 + symbol as u32, 8),
+        144..=255 => (0x190 + (symbol - 144) as u32, 9),
+        256..=279 => ((symbol - 256) as u32, 7),
+        _ => (0xc0 + (symbol - 280) as u32, 8),
+    };
+
+    // Huffman codes are packed starting from their most significant bit
+    writer.write_bits(reverse_bits(code, length), length);
+}
+
+fn read_fixed_symbol(reader: &mut BitReader) -> Option<u16> {
+    let mut code = 0;
+    for length in 1..=9 {
+        code = (code << 1) | reader.read_bits(1)?;
+        match (length, code) {
+            (7, 0x00..=0x17) => return Some(256 + code as u16),
+            (8, 0x30..=0xbf) => return Some((code - 0x30) as u16),
+            (8, 0xc0..=0xc7) => return Some(280 + (code - 0xc0) as u16),
+            (9, 0x190..=0x1ff) => return Some(144 + (code - 0x190) as u16),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn reverse_bits(value: u32, length: u8) -> u32 {
+    if length == 0 {
+        return 0;
+    }
+
+    value.reverse_bits() >> (32 - length)
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u64,
+    count: u8,
+}
+
+impl BitWriter {
+    // Writes the low `count` bits of `value`, least significant first.
+    fn write_bits(&mut self, value: u32, count: u8) {
+        self.buffer |= (value as u64) << self.count;
+        self.count += count;
+        while self.count >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.count -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.count > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        BitReader { bytes, position: 0 }
+    }
+
+    fn read_bits(&mut self, count: u8) -> Option<u32> {
+        let mut value = 0;
+        for index in 0..count {
+            let byte = self.bytes.get(self.position / 8)?;
+            value |= (((byte >> (self.position % 8)) & 1) as u32) << index;
+            self.position += 1;
+        }
+        Some(value)
+    }
+
+    fn align(&mut self) {
+        self.position = self.position.div_ceil(8) * 8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::hex::hex_string_to_bytes;
+    use crate::random::Rng;
+
+    // zlib's raw output for the same input
+    #[test]
+    fn test_decompress_zlib_output() {
+        let compressed = hex_string_to_bytes("cb48cdc9c957c840277514caf38b725200");
+        assert_eq!(
+            decompress(&compressed).unwrap(),
+            b"hello hello hello hello, world"
+        );
+    }
+
+    #[test]
+    fn test_decompress_stored_block() {
+        let compressed = [0x01, 0x03, 0x00, 0xfc, 0xff, b'a', b'b', b'c'];
+        assert_eq!(decompress(&compressed).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = Rng::new(1951);
+        let inputs = [
+            b"".to_vec(),
+            b"a".to_vec(),
+            b"hello hello hello hello, world".to_vec(),
+            vec![b'z'; 1000],
+            rng.bytes(500),
+            (0..5000).map(|_| b"abc"[rng.below(3) as usize]).collect(),
+        ];
+
+        for input in &inputs {
+            assert_eq!(&decompress(&compress(input)).unwrap(), input);
+        }
+    }
+
+    #[test]
+    fn test_repetition_compresses() {
+        let text = b"sessionid=TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=";
+        let repeated = [&text[..], &text[..]].concat();
+
+        assert!(compress(&repeated).len() < compress(text).len() + 8);
+    }
+}
@@ -1,2 +1,6 @@
+pub mod ascii85;
+pub mod base32;
+pub mod base58;
 pub mod base64;
+pub mod detect;
 pub mod hex;
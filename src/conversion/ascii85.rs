@@ -0,0 +1,204 @@
+use std::error::Error;
+use std::fmt;
+
+// Ascii85 as Adobe and btoa write it: four bytes to five base-85 digits, written from '!' to
+// 'u', with a 'z' standing in for a whole group of zero bytes. A short last group is padded with
+// zero bytes to encode and with 'u's to decode, and the padding dropped again afterwards.
+const FIRST_DIGIT: u8 = b'!';
+const LAST_DIGIT: u8 = b'u';
+
+// Positions are byte offsets into the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Ascii85Error {
+    // Includes a 'z' partway through a group
+    InvalidCharacter { position: usize, byte: u8 },
+    // A group, starting at position, whose value doesn't fit in four bytes
+    Overflow { position: usize },
+    // A single digit left over after the last full group, which can't encode a byte
+    InvalidLength { characters: usize },
+}
+
+impl fmt::Display for Ascii85Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Ascii85Error::InvalidCharacter { position, byte } => write!(
+                f,
+                "Invalid Ascii85 byte 0x{:02x} at position {}",
+                byte, position
+            ),
+            Ascii85Error::Overflow { position } => write!(
+                f,
+                "Ascii85 group at position {} overflows four bytes",
+                position
+            ),
+            Ascii85Error::InvalidLength { characters } => write!(
+                f,
+                "Invalid Ascii85 length: {} digits leaves one over",
+                characters
+            ),
+        }
+    }
+}
+
+impl Error for Ascii85Error {}
+
+// Without the <~ ~> delimiters.
+pub fn bytes_to_ascii85(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len().div_ceil(4) * 5);
+    for group in bytes.chunks(4) {
+        let mut block = [0; 4];
+        block[..group.len()].copy_from_slice(group);
+        let mut value = u32::from_be_bytes(block);
+
+        if group.len() == 4 && value == 0 {
+            result.push('z');
+            continue;
+        }
+
+        let mut digits = [0; 5];
+        for digit in digits.iter_mut().rev() {
+            *digit = FIRST_DIGIT + (value % 85) as u8;
+            value /= 85;
+        }
+        result.extend(digits[..=group.len()].iter().copied().map(char::from));
+    }
+    result
+}
+
+// Whitespace is ignored, and the <~ ~> delimiters Adobe puts around the data are optional.
+pub fn try_ascii85_to_bytes<B: AsRef<[u8]>>(ascii85: B) -> Result<Vec<u8>, Ascii85Error> {
+    let input = ascii85.as_ref();
+    let start = if input.starts_with(b"<~") { 2 } else { 0 };
+    let end = if input[start..].ends_with(b"~>") {
+        input.len() - 2
+    } else {
+        input.len()
+    };
+
+    let mut result = Vec::with_capacity(input.len() / 5 * 4 + 3);
+    let mut group = [0; 5];
+    let mut group_length = 0;
+    let mut group_position = 0;
+    let mut characters = 0;
+
+    for (position, &byte) in input.iter().enumerate().take(end).skip(start) {
+        if byte.is_ascii_whitespace() {
+            continue;
+        }
+        if byte == b'z' && group_length == 0 {
+            result.extend_from_slice(&[0; 4]);
+            continue;
+        }
+        if !(FIRST_DIGIT..=LAST_DIGIT).contains(&byte) {
+            return Err(Ascii85Error::InvalidCharacter { position, byte });
+        }
+
+        if group_length == 0 {
+            group_position = position;
+        }
+        group[group_length] = byte - FIRST_DIGIT;
+        group_length += 1;
+        characters += 1;
+
+        if group_length == 5 {
+            decode_group(&group, 4, group_position, &mut result)?;
+            group_length = 0;
+        }
+    }
+
+    match group_length {
+        0 => {}
+        1 => return Err(Ascii85Error::InvalidLength { characters }),
+        _ => {
+            for digit in &mut group[group_length..] {
+                *digit = LAST_DIGIT - FIRST_DIGIT;
+            }
+            decode_group(&group, group_length - 1, group_position, &mut result)?;
+        }
+    }
+
+    Ok(result)
+}
+
+// The first `length` bytes of the group's value.
+fn decode_group(
+    digits: &[u8; 5],
+    length: usize,
+    position: usize,
+    result: &mut Vec<u8>,
+) -> Result<(), Ascii85Error> {
+    let value = digits
+        .iter()
+        .fold(0u64, |value, &digit| 85 * value + digit as u64);
+    if value > u32::MAX as u64 {
+        return Err(Ascii85Error::Overflow { position });
+    }
+    result.extend_from_slice(&(value as u32).to_be_bytes()[..length]);
+    Ok(())
+}
+
+// Panics on input that isn't Ascii85.
+pub fn ascii85_to_bytes<B: AsRef<[u8]>>(ascii85: B) -> Vec<u8> {
+    try_ascii85_to_bytes(ascii85).unwrap_or_else(|error| panic!("{}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Wikipedia's example, and the edge cases around short and all-zero groups
+    #[test]
+    fn test_ascii85_vectors() {
+        let vectors: [(&[u8], &str); 8] = [
+            (b"", ""),
+            (b"M", "9`"),
+            (b"Man", "9jqo"),
+            (b"Man ", "9jqo^"),
+            (b"sure.", "F*2M7/c"),
+            (b"\0\0\0\0", "z"),
+            (b"\0\0\0\0x", "zGQ"),
+            (
+                b"Man is distinguished, not only by his reason",
+                "9jqo^BlbD-BleB1DJ+*+F(f,q/0JhKF<GL>Cj@.4Gp$d7F!,L7@<6@)",
+            ),
+        ];
+
+        for (bytes, encoded) in vectors {
+            assert_eq!(bytes_to_ascii85(bytes), encoded);
+            assert_eq!(ascii85_to_bytes(encoded), bytes);
+        }
+    }
+
+    #[test]
+    fn test_ascii85_delimiters_and_whitespace() {
+        assert_eq!(ascii85_to_bytes("<~9jqo^\n F*2M7/c~>"), b"Man sure.");
+        assert_eq!(ascii85_to_bytes("<~~>"), b"");
+        assert_eq!(ascii85_to_bytes("s8W-!"), [0xff; 4]);
+    }
+
+    #[test]
+    fn test_try_ascii85_errors() {
+        assert_eq!(
+            try_ascii85_to_bytes("9jzo^"),
+            Err(Ascii85Error::InvalidCharacter {
+                position: 2,
+                byte: b'z'
+            })
+        );
+        assert_eq!(
+            try_ascii85_to_bytes("9jqo^s8W-\""),
+            Err(Ascii85Error::Overflow { position: 5 })
+        );
+        assert_eq!(
+            try_ascii85_to_bytes("9jqo^F"),
+            Err(Ascii85Error::InvalidLength { characters: 6 })
+        );
+        assert_eq!(
+            try_ascii85_to_bytes("9jqo~"),
+            Err(Ascii85Error::InvalidCharacter {
+                position: 4,
+                byte: b'~'
+            })
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use std::error::Error;
+use std::fmt;
+
+// RFC 4648 base32, the encoding TOTP secrets are handed out in.
+const BASE_32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+// Positions are byte offsets into the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Base32Error {
+    InvalidCharacter { position: usize, byte: u8 },
+    // Padding in the middle of the data, or the wrong amount of it
+    InvalidPadding { position: usize },
+    // 1, 3 or 6 characters left over after the last full group, which no byte count encodes to
+    InvalidLength { characters: usize },
+}
+
+impl fmt::Display for Base32Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Base32Error::InvalidCharacter { position, byte } => write!(
+                f,
+                "Invalid base32 byte 0x{:02x} at position {}",
+                byte, position
+            ),
+            Base32Error::InvalidPadding { position } => {
+                write!(f, "Invalid base32 padding at position {}", position)
+            }
+            Base32Error::InvalidLength { characters } => write!(
+                f,
+                "Invalid base32 length: {} characters leaves a partial byte",
+                characters
+            ),
+        }
+    }
+}
+
+impl Error for Base32Error {}
+
+// Five bytes to eight characters, with the last group padded out with '='.
+pub fn bytes_to_base32(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    for group in bytes.chunks(5) {
+        let mut block = [0; 8];
+        block[3..3 + group.len()].copy_from_slice(group);
+        let bits = u64::from_be_bytes(block);
+
+        let symbols = (8 * group.len()).div_ceil(5);
+        for index in 0..8 {
+            if index < symbols {
+                let value = (bits >> (35 - 5 * index)) & 0b1_1111;
+                result.push(char::from(BASE_32_ALPHABET[value as usize]));
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+// Either case, since secrets are often written out in lowercase.
+pub fn try_base32_character_to_byte(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a'),
+        b'2'..=b'7' => Some(c - b'2' + 26),
+        _ => None,
+    }
+}
+
+// Forgiving about the ways secrets get written down: lowercase, whitespace and missing padding
+// are all fine, and stray bits in the last character are ignored. Padding that is there has to
+// be the right amount.
+pub fn try_base32_to_bytes<B: AsRef<[u8]>>(base32: B) -> Result<Vec<u8>, Base32Error> {
+    let input = base32.as_ref();
+    let mut result = Vec::with_capacity(input.len() * 5 / 8);
+    let mut bits = 0u64;
+    let mut bit_count = 0;
+    let mut characters = 0;
+    let mut padding = 0;
+    let mut padding_position = None;
+
+    for (position, &byte) in input.iter().enumerate() {
+        if byte.is_ascii_whitespace() {
+            continue;
+        }
+        if byte == b'=' {
+            padding += 1;
+            padding_position.get_or_insert(position);
+            continue;
+        }
+        if let Some(position) = padding_position {
+            return Err(Base32Error::InvalidPadding { position });
+        }
+
+        let value = try_base32_character_to_byte(byte)
+            .ok_or(Base32Error::InvalidCharacter { position, byte })?;
+        bits = (bits << 5) | value as u64;
+        bit_count += 5;
+        characters += 1;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            result.push((bits >> bit_count) as u8);
+        }
+    }
+
+    let leftover = characters % 8;
+    if let 1 | 3 | 6 = leftover {
+        return Err(Base32Error::InvalidLength { characters });
+    }
+    if let Some(position) = padding_position {
+        if padding != (8 - leftover) % 8 {
+            return Err(Base32Error::InvalidPadding { position });
+        }
+    }
+
+    Ok(result)
+}
+
+// Panics on input that isn't base32.
+pub fn base32_to_bytes<B: AsRef<[u8]>>(base32: B) -> Vec<u8> {
+    try_base32_to_bytes(base32).unwrap_or_else(|error| panic!("{}", error))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 4648, section 10
+    const TEST_VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "MY======"),
+        ("fo", "MZXQ===="),
+        ("foo", "MZXW6==="),
+        ("foob", "MZXW6YQ="),
+        ("fooba", "MZXW6YTB"),
+        ("foobar", "MZXW6YTBOI======"),
+    ];
+
+    #[test]
+    fn test_base32_rfc_vectors() {
+        for (plain, encoded) in TEST_VECTORS {
+            assert_eq!(bytes_to_base32(plain.as_bytes()), encoded);
+            assert_eq!(base32_to_bytes(encoded), plain.as_bytes());
+        }
+    }
+
+    #[test]
+    fn test_base32_totp_secret() {
+        let expected = b"Hello!\xde\xad\xbe\xef";
+        assert_eq!(base32_to_bytes("JBSWY3DPEHPK3PXP"), expected);
+        assert_eq!(base32_to_bytes("jbsw y3dp ehpk 3pxp"), expected);
+        assert_eq!(base32_to_bytes("MZXW6"), b"foo");
+    }
+
+    #[test]
+    fn test_try_base32_errors() {
+        assert_eq!(
+            try_base32_to_bytes("MZXW1==="),
+            Err(Base32Error::InvalidCharacter {
+                position: 4,
+                byte: b'1'
+            })
+        );
+        assert_eq!(
+            try_base32_to_bytes("MZXW6=="),
+            Err(Base32Error::InvalidPadding { position: 5 })
+        );
+        assert_eq!(
+            try_base32_to_bytes("MY==MY=="),
+            Err(Base32Error::InvalidPadding { position: 2 })
+        );
+        assert_eq!(
+            try_base32_to_bytes("MZX"),
+            Err(Base32Error::InvalidLength { characters: 3 })
+        );
+    }
+}
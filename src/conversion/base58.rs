@@ -0,0 +1,204 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::hash::sha256::sha256;
+
+// Bitcoin's alphabet, which leaves out 0, O, I and l so that none can be mistaken for another.
+const BASE_58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const INVALID: u8 = 0xff;
+
+const BASE_58_VALUES: [u8; 256] = {
+    let mut values = [INVALID; 256];
+    let mut digit = 0;
+    while digit < 58 {
+        values[BASE_58_ALPHABET[digit] as usize] = digit as u8;
+        digit += 1;
+    }
+    values
+};
+
+pub const CHECKSUM_SIZE: usize = 4;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Base58Error {
+    // Position is the byte offset of the character in the input
+    InvalidCharacter { position: usize, byte: u8 },
+    // Base58Check input that decodes to fewer bytes than the checksum alone
+    MissingChecksum { length: usize },
+    InvalidChecksum,
+}
+
+impl fmt::Display for Base58Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Base58Error::InvalidCharacter { position, byte } => write!(
+                f,
+                "Invalid base58 byte 0x{:02x} at position {}",
+                byte, position
+            ),
+            Base58Error::MissingChecksum { length } => write!(
+                f,
+                "Base58Check data is {} bytes, too short for a checksum",
+                length
+            ),
+            Base58Error::InvalidChecksum => write!(f, "Base58Check checksum doesn't match"),
+        }
+    }
+}
+
+impl Error for Base58Error {}
+
+// The bytes as one big-endian number written in base 58, with a '1' for each leading zero byte
+// since those wouldn't otherwise show up. Quadratic in the length, which is fine for the keys
+// and addresses it's used for.
+pub fn bytes_to_base58(bytes: &[u8]) -> String {
+    let zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+
+    // Least significant first
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 138 / 100 + 1);
+    for &byte in &bytes[zeros..] {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    std::iter::repeat_n('1', zeros)
+        .chain(
+            digits
+                .iter()
+                .rev()
+                .map(|&digit| char::from(BASE_58_ALPHABET[digit as usize])),
+        )
+        .collect()
+}
+
+pub fn try_base58_character_to_byte(c: u8) -> Option<u8> {
+    match BASE_58_VALUES[c as usize] {
+        INVALID => None,
+        value => Some(value),
+    }
+}
+
+// Strict: there's no padding to get wrong, and whitespace is an invalid character.
+pub fn try_base58_to_bytes<B: AsRef<[u8]>>(base58: B) -> Result<Vec<u8>, Base58Error> {
+    let input = base58.as_ref();
+    let zeros = input.iter().take_while(|&&c| c == b'1').count();
+
+    // Least significant first
+    let mut bytes: Vec<u8> = Vec::with_capacity(input.len() * 733 / 1000 + 1);
+    for (position, &byte) in input.iter().enumerate().skip(zeros) {
+        let mut carry = try_base58_character_to_byte(byte)
+            .ok_or(Base58Error::InvalidCharacter { position, byte })?
+            as u32;
+        for value in bytes.iter_mut() {
+            carry += *value as u32 * 58;
+            *value = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut result = vec![0; zeros];
+    result.extend(bytes.iter().rev());
+    Ok(result)
+}
+
+// Panics on input that isn't base58.
+pub fn base58_to_bytes<B: AsRef<[u8]>>(base58: B) -> Vec<u8> {
+    try_base58_to_bytes(base58).unwrap_or_else(|error| panic!("{}", error))
+}
+
+// The first four bytes of the double SHA-256 of the payload.
+fn checksum(payload: &[u8]) -> [u8; CHECKSUM_SIZE] {
+    let mut checksum = [0; CHECKSUM_SIZE];
+    checksum.copy_from_slice(&sha256(&sha256(payload))[..CHECKSUM_SIZE]);
+    checksum
+}
+
+// Base58Check, as in Bitcoin addresses: the payload, version byte and all, followed by its
+// checksum, so that a mistyped string is caught rather than decoded.
+pub fn bytes_to_base58check(payload: &[u8]) -> String {
+    let mut bytes = payload.to_vec();
+    bytes.extend_from_slice(&checksum(payload));
+    bytes_to_base58(&bytes)
+}
+
+// Returns the payload, without the checksum.
+pub fn try_base58check_to_bytes<B: AsRef<[u8]>>(base58: B) -> Result<Vec<u8>, Base58Error> {
+    let mut bytes = try_base58_to_bytes(base58)?;
+    if bytes.len() < CHECKSUM_SIZE {
+        return Err(Base58Error::MissingChecksum {
+            length: bytes.len(),
+        });
+    }
+
+    let expected = bytes.split_off(bytes.len() - CHECKSUM_SIZE);
+    if expected != checksum(&bytes) {
+        return Err(Base58Error::InvalidChecksum);
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::hex::hex_string_to_bytes;
+
+    // From the base58 encoding draft, draft-msporny-base58
+    #[test]
+    fn test_base58_vectors() {
+        let vectors = [
+            (b"Hello World!".to_vec(), "2NEpo7TZRRrLZSi2U"),
+            (
+                b"The quick brown fox jumps over the lazy dog.".to_vec(),
+                "USm3fpXnKG5EUBx2ndxBDMPVciP5hGey2Jh4NDv6gmeo1LkMeiKrLJUUBk6Z",
+            ),
+            (hex_string_to_bytes("0000287fb4cd"), "11233QC4"),
+            (Vec::new(), ""),
+        ];
+
+        for (bytes, encoded) in vectors {
+            assert_eq!(bytes_to_base58(&bytes), encoded);
+            assert_eq!(base58_to_bytes(encoded), bytes);
+        }
+    }
+
+    #[test]
+    fn test_try_base58_invalid_character() {
+        assert_eq!(
+            try_base58_to_bytes("2NEpo0TZ"),
+            Err(Base58Error::InvalidCharacter {
+                position: 5,
+                byte: b'0'
+            })
+        );
+    }
+
+    // The address paid by the genesis block: version 0 and the hash of its public key
+    #[test]
+    fn test_base58check_address() {
+        let payload = hex_string_to_bytes("0062e907b15cbf27d5425399ebf6f0fb50ebb88f18");
+        let address = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa";
+        assert_eq!(bytes_to_base58check(&payload), address);
+        assert_eq!(try_base58check_to_bytes(address), Ok(payload));
+
+        assert_eq!(
+            try_base58check_to_bytes("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb"),
+            Err(Base58Error::InvalidChecksum)
+        );
+        assert_eq!(
+            try_base58check_to_bytes("2NE"),
+            Err(Base58Error::MissingChecksum { length: 2 })
+        );
+    }
+}
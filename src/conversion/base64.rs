@@ -1,17 +1,612 @@
-use std::io::{BufReader, Read};
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
 
 use crate::conversion::hex::{hex_string_to_bytes, bytes_to_hex_string};
 
-#[rustfmt::skip]
-const BASE_64_TABLE: [char; 65] = [
-    'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J',
-    'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S', 'T',
-    'U', 'V', 'W', 'X', 'Y', 'Z', 'a', 'b', 'c', 'd',
-    'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n',
-    'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 
-    'y', 'z', '0', '1', '2', '3', '4', '5', '6', '7', 
-    '8', '9', '+', '/', '='
-];
+const INVALID: u8 = 0xff;
+
+// The 64 symbols of an encoding, with the reverse table for decoding.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Alphabet {
+    symbols: [u8; 64],
+    values: [u8; 256],
+}
+
+impl Alphabet {
+    pub const STANDARD: Alphabet =
+        Alphabet::build(*b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/");
+    // RFC 4648's alphabet for URLs and file names, as used by JWTs
+    pub const URL_SAFE: Alphabet =
+        Alphabet::build(*b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_");
+
+    const fn build(symbols: [u8; 64]) -> Alphabet {
+        let mut values = [INVALID; 256];
+        let mut index = 0;
+        while index < 64 {
+            values[symbols[index] as usize] = index as u8;
+            index += 1;
+        }
+        Alphabet { symbols, values }
+    }
+
+    // 64 distinct printable ASCII symbols, none of them the padding character.
+    pub fn custom(symbols: &[u8]) -> Option<Alphabet> {
+        if symbols.len() != 64 {
+            return None;
+        }
+
+        let mut array = [0; 64];
+        array.copy_from_slice(symbols);
+        let alphabet = Alphabet::build(array);
+
+        let valid = symbols.iter().enumerate().all(|(index, &symbol)| {
+            symbol.is_ascii_graphic()
+                && symbol != b'='
+                && alphabet.values[symbol as usize] == index as u8
+        });
+        if valid {
+            Some(alphabet)
+        } else {
+            None
+        }
+    }
+
+    pub fn symbol(&self, value: u8) -> u8 {
+        self.symbols[value as usize]
+    }
+
+    pub fn value(&self, symbol: u8) -> Option<u8> {
+        match self.values[symbol as usize] {
+            INVALID => None,
+            value => Some(value),
+        }
+    }
+}
+
+impl fmt::Debug for Alphabet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Alphabet({})", String::from_utf8_lossy(&self.symbols))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Padding {
+    // Written when encoding, and the decoder insists on it
+    Required,
+    // Written when encoding, and the decoder takes it or leaves it
+    Optional,
+    // Never written, and rejected by the decoder
+    None,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+// Line breaks after every `width` characters of output, and none after the last line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LineWrap {
+    width: usize,
+    ending: LineEnding,
+}
+
+impl LineWrap {
+    // At least one character per line.
+    pub fn new(width: usize, ending: LineEnding) -> Option<LineWrap> {
+        if width == 0 {
+            None
+        } else {
+            Some(LineWrap { width, ending })
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn ending(&self) -> LineEnding {
+        self.ending
+    }
+}
+
+// How forgiving the decoder is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Base64Mode {
+    // Exactly the canonical encoding: no whitespace besides the configured line breaks, and
+    // unused bits in the last character left zero
+    Strict,
+    // Skips whitespace anywhere (so CRLF line endings are fine) and ignores stray bits in the
+    // last character
+    Lenient,
+}
+
+// One set of choices used for both directions, so whatever a config encodes it also decodes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Base64Config {
+    pub alphabet: Alphabet,
+    pub padding: Padding,
+    pub line_wrap: Option<LineWrap>,
+    pub mode: Base64Mode,
+}
+
+impl Base64Config {
+    pub const STANDARD: Base64Config = Base64Config {
+        alphabet: Alphabet::STANDARD,
+        padding: Padding::Required,
+        line_wrap: None,
+        mode: Base64Mode::Strict,
+    };
+
+    // JWT segments: URL-safe symbols and no padding
+    pub const URL_SAFE: Base64Config = Base64Config {
+        alphabet: Alphabet::URL_SAFE,
+        padding: Padding::None,
+        line_wrap: None,
+        mode: Base64Mode::Strict,
+    };
+
+    // RFC 2045 bodies: 76-character lines ending in CRLF
+    pub const MIME: Base64Config = Base64Config {
+        line_wrap: Some(LineWrap {
+            width: 76,
+            ending: LineEnding::CrLf,
+        }),
+        ..Base64Config::STANDARD
+    };
+
+    // RFC 7468 bodies: 64-character lines ending in LF
+    pub const PEM: Base64Config = Base64Config {
+        line_wrap: Some(LineWrap {
+            width: 64,
+            ending: LineEnding::Lf,
+        }),
+        ..Base64Config::STANDARD
+    };
+
+    // The length encode produces for `length` bytes, line breaks included.
+    pub fn encoded_length(&self, length: usize) -> usize {
+        let symbols = self.symbol_count(length);
+        match self.line_wrap {
+            Some(wrap) if symbols > 0 => {
+                symbols + (symbols - 1) / wrap.width * wrap.ending.as_str().len()
+            }
+            _ => symbols,
+        }
+    }
+
+    fn symbol_count(&self, length: usize) -> usize {
+        match self.padding {
+            Padding::None => length / 3 * 4 + [0, 2, 3][length % 3],
+            _ => length.div_ceil(3) * 4,
+        }
+    }
+
+    pub fn encode(&self, bytes: &[u8]) -> String {
+        let mut output = vec![0; self.encoded_length(bytes.len())];
+        self.encode_into(bytes, &mut output);
+        String::from_utf8(output).expect("base64 alphabets are ASCII")
+    }
+
+    // Encodes into the front of output, which needs encoded_length bytes, and returns how many
+    // were written. With line wrapping the symbols go in unwrapped at the back, and then each
+    // line moves forward into place.
+    pub fn encode_into(&self, bytes: &[u8], output: &mut [u8]) -> usize {
+        let length = self.encoded_length(bytes.len());
+        assert!(
+            output.len() >= length,
+            "Base64 output needs {} bytes, got {}",
+            length,
+            output.len()
+        );
+        let output = &mut output[..length];
+
+        let wrap = match self.line_wrap {
+            Some(wrap) => wrap,
+            None => {
+                self.encode_symbols(bytes, output);
+                return length;
+            }
+        };
+
+        let ending = wrap.ending.as_str().as_bytes();
+        let symbols = self.symbol_count(bytes.len());
+        let offset = length - symbols;
+        self.encode_symbols(bytes, &mut output[offset..]);
+
+        for (line, start) in (0..symbols).step_by(wrap.width).enumerate() {
+            let end = std::cmp::min(start + wrap.width, symbols);
+            let destination = line * (wrap.width + ending.len());
+            output.copy_within(offset + start..offset + end, destination);
+            if end < symbols {
+                let break_start = destination + end - start;
+                output[break_start..break_start + ending.len()].copy_from_slice(ending);
+            }
+        }
+        length
+    }
+
+    // Symbols for whole groups are looked up straight from each three bytes.
+    fn encode_symbols(&self, bytes: &[u8], output: &mut [u8]) {
+        let symbols = &self.alphabet.symbols;
+        let mut groups = bytes.chunks_exact(3);
+        let mut blocks = output.chunks_mut(4);
+
+        for (group, block) in (&mut groups).zip(&mut blocks) {
+            let bits = (group[0] as usize) << 16 | (group[1] as usize) << 8 | group[2] as usize;
+            block[0] = symbols[bits >> 18];
+            block[1] = symbols[(bits >> 12) & 0b11_1111];
+            block[2] = symbols[(bits >> 6) & 0b11_1111];
+            block[3] = symbols[bits & 0b11_1111];
+        }
+
+        let rest = groups.remainder();
+        if let Some(block) = blocks.next() {
+            let mut group = [0; 3];
+            group[..rest.len()].copy_from_slice(rest);
+            let bits = (group[0] as usize) << 16 | (group[1] as usize) << 8;
+            for (index, symbol) in block.iter_mut().enumerate() {
+                *symbol = if index <= rest.len() {
+                    symbols[(bits >> (18 - 6 * index)) & 0b11_1111]
+                } else {
+                    b'='
+                };
+            }
+        }
+    }
+
+    pub fn decode<B: AsRef<[u8]>>(&self, base64: B) -> Result<Vec<u8>, Base64Error> {
+        let input = base64.as_ref();
+        let mut output = vec![0; input.len() / 4 * 3 + 2];
+        let length = self.decode_into(input, &mut output)?;
+        output.truncate(length);
+        Ok(output)
+    }
+
+    // Decodes into the front of output and returns the number of bytes written. Panics if
+    // output is too short for the result, which is at most 3 bytes for every 4 of input, plus
+    // 2. Strict input without line wrapping is looked up a whole group at a time; the last
+    // group, and everything from the first one that isn't plain symbols, goes through the same
+    // byte-by-byte checks as the streaming decoder.
+    pub fn decode_into(&self, input: &[u8], output: &mut [u8]) -> Result<usize, Base64Error> {
+        let mut start = 0;
+        if self.mode == Base64Mode::Strict && self.line_wrap.is_none() {
+            let groups = input.len().saturating_sub(1) / 4;
+            assert!(
+                output.len() >= 3 * groups,
+                "Base64 output needs at least {} bytes, got {}",
+                3 * groups,
+                output.len()
+            );
+
+            let values = &self.alphabet.values;
+            for (group, bytes) in input[..4 * groups]
+                .chunks_exact(4)
+                .zip(output.chunks_exact_mut(3))
+            {
+                let group = [
+                    values[group[0] as usize],
+                    values[group[1] as usize],
+                    values[group[2] as usize],
+                    values[group[3] as usize],
+                ];
+                if (group[0] | group[1] | group[2] | group[3]) > 0b11_1111 {
+                    break;
+                }
+
+                let bits = (group[0] as u32) << 18
+                    | (group[1] as u32) << 12
+                    | (group[2] as u32) << 6
+                    | group[3] as u32;
+                bytes.copy_from_slice(&bits.to_be_bytes()[1..]);
+                start += 4;
+            }
+        }
+
+        // Picks up where the table left off, with every character so far a symbol
+        let mut decoder = Decoder::new(*self);
+        decoder.position = start;
+        decoder.characters = start;
+        let mut rest = SliceOutput {
+            slice: &mut output[start / 4 * 3..],
+            length: 0,
+        };
+        for &byte in &input[start..] {
+            decoder.push(byte, &mut rest)?;
+        }
+        decoder.finish(&mut rest)?;
+        Ok(start / 4 * 3 + rest.length)
+    }
+}
+
+// Decoder output into a caller's buffer.
+struct SliceOutput<'a> {
+    slice: &'a mut [u8],
+    length: usize,
+}
+
+impl Extend<u8> for SliceOutput<'_> {
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, bytes: I) {
+        for byte in bytes {
+            assert!(
+                self.length < self.slice.len(),
+                "Base64 output buffer is too short"
+            );
+            self.slice[self.length] = byte;
+            self.length += 1;
+        }
+    }
+}
+
+// Encoding state between writes: up to two bytes short of a group, and how far along the
+// current line is.
+struct Encoder {
+    config: Base64Config,
+    pending: [u8; 3],
+    pending_length: usize,
+    column: usize,
+}
+
+impl Encoder {
+    fn new(config: Base64Config) -> Encoder {
+        Encoder {
+            config,
+            pending: [0; 3],
+            pending_length: 0,
+            column: 0,
+        }
+    }
+
+    fn update(&mut self, mut bytes: &[u8], output: &mut Vec<u8>) {
+        if self.pending_length > 0 {
+            let taken = std::cmp::min(3 - self.pending_length, bytes.len());
+            self.pending[self.pending_length..self.pending_length + taken]
+                .copy_from_slice(&bytes[..taken]);
+            self.pending_length += taken;
+            bytes = &bytes[taken..];
+            if self.pending_length < 3 {
+                return;
+            }
+
+            let group = self.pending;
+            self.encode_group(&group, output);
+            self.pending_length = 0;
+        }
+
+        let mut groups = bytes.chunks_exact(3);
+        for group in &mut groups {
+            self.encode_group(group, output);
+        }
+        let rest = groups.remainder();
+        self.pending[..rest.len()].copy_from_slice(rest);
+        self.pending_length = rest.len();
+    }
+
+    fn finish(&mut self, output: &mut Vec<u8>) {
+        if self.pending_length > 0 {
+            let group = self.pending;
+            self.encode_group(&group[..self.pending_length], output);
+            self.pending_length = 0;
+        }
+    }
+
+    // Line breaks go in before the symbol that would overflow a line, so there's never one at
+    // the end.
+    fn encode_group(&mut self, group: &[u8], output: &mut Vec<u8>) {
+        let config = self.config;
+        let mut block = [0; 4];
+        block[1..=group.len()].copy_from_slice(group);
+        let bits = u32::from_be_bytes(block);
+
+        let padding = match config.padding {
+            Padding::None => 0,
+            _ => 3 - group.len(),
+        };
+        let symbols = (0..=group.len())
+            .map(|index| {
+                config
+                    .alphabet
+                    .symbol(((bits >> (18 - 6 * index)) & 0b11_1111) as u8)
+            })
+            .chain(std::iter::repeat_n(b'=', padding));
+
+        for symbol in symbols {
+            if let Some(wrap) = config.line_wrap {
+                if self.column == wrap.width {
+                    output.extend_from_slice(wrap.ending.as_str().as_bytes());
+                    self.column = 0;
+                }
+                self.column += 1;
+            }
+            output.push(symbol);
+        }
+    }
+}
+
+// Decoding state, fed a byte at a time so that a slice and a stream go through the same
+// checks. Full groups are written out as soon as they're complete.
+struct Decoder {
+    config: Base64Config,
+    position: usize,
+    group: [u8; 4],
+    group_length: usize,
+    characters: usize,
+    last_position: usize,
+    padding: usize,
+    padding_position: Option<usize>,
+    column: usize,
+    // A CR still waiting for its LF
+    carriage_return: Option<usize>,
+    // The break after a short line, which is only allowed to end the input
+    short_line_break: Option<usize>,
+}
+
+impl Decoder {
+    fn new(config: Base64Config) -> Decoder {
+        Decoder {
+            config,
+            position: 0,
+            group: [0; 4],
+            group_length: 0,
+            characters: 0,
+            last_position: 0,
+            padding: 0,
+            padding_position: None,
+            column: 0,
+            carriage_return: None,
+            short_line_break: None,
+        }
+    }
+
+    fn push<O: Extend<u8>>(&mut self, byte: u8, output: &mut O) -> Result<(), Base64Error> {
+        let position = self.position;
+        self.position += 1;
+
+        if self.config.mode == Base64Mode::Lenient && byte.is_ascii_whitespace() {
+            return Ok(());
+        }
+
+        if let Some(wrap) = self
+            .config
+            .line_wrap
+            .filter(|_| self.config.mode == Base64Mode::Strict)
+        {
+            if let Some(carriage_return) = self.carriage_return.filter(|_| byte != b'\n') {
+                return Err(Base64Error::InvalidLineBreak {
+                    position: carriage_return,
+                });
+            }
+
+            if byte == b'\r' || byte == b'\n' {
+                return match (wrap.ending, byte, self.carriage_return.take()) {
+                    (LineEnding::CrLf, b'\r', None) => {
+                        self.carriage_return = Some(position);
+                        Ok(())
+                    }
+                    (LineEnding::CrLf, b'\n', Some(carriage_return)) => {
+                        self.end_line(carriage_return, wrap)
+                    }
+                    (LineEnding::Lf, b'\n', None) => self.end_line(position, wrap),
+                    _ => Err(Base64Error::InvalidLineBreak { position }),
+                };
+            }
+
+            if let Some(position) = self.short_line_break {
+                return Err(Base64Error::InvalidLineBreak { position });
+            }
+            self.column += 1;
+            if self.column > wrap.width {
+                return Err(Base64Error::InvalidLineBreak { position });
+            }
+        }
+
+        if byte == b'=' {
+            if self.config.padding == Padding::None {
+                return Err(Base64Error::InvalidPadding { position });
+            }
+            self.padding += 1;
+            self.padding_position.get_or_insert(position);
+            return Ok(());
+        }
+        if let Some(position) = self.padding_position {
+            return Err(Base64Error::InvalidPadding { position });
+        }
+
+        let value = self
+            .config
+            .alphabet
+            .value(byte)
+            .ok_or(Base64Error::InvalidCharacter { position, byte })?;
+        self.group[self.group_length] = value;
+        self.group_length += 1;
+        self.characters += 1;
+        self.last_position = position;
+
+        if self.group_length == 4 {
+            decode_group(&self.group, output);
+            self.group_length = 0;
+        }
+        Ok(())
+    }
+
+    // Full lines can end anywhere, short ones only at the end of the input
+    fn end_line(&mut self, position: usize, wrap: LineWrap) -> Result<(), Base64Error> {
+        let column = std::mem::replace(&mut self.column, 0);
+        if column == 0 {
+            return Err(Base64Error::InvalidLineBreak { position });
+        }
+        if column < wrap.width {
+            self.short_line_break = Some(position);
+        }
+        Ok(())
+    }
+
+    fn finish<O: Extend<u8>>(&mut self, output: &mut O) -> Result<(), Base64Error> {
+        if let Some(position) = self.carriage_return {
+            return Err(Base64Error::InvalidLineBreak { position });
+        }
+
+        let leftover = self.group_length;
+        if leftover == 1 {
+            return Err(Base64Error::InvalidLength {
+                characters: self.characters,
+            });
+        }
+
+        let expected_padding = (4 - leftover) % 4;
+        let padding_allowed = match self.config.padding {
+            Padding::Required => self.padding == expected_padding,
+            Padding::Optional => self.padding == 0 || self.padding == expected_padding,
+            Padding::None => true,
+        };
+        if !padding_allowed {
+            return Err(Base64Error::InvalidPadding {
+                position: self.padding_position.unwrap_or(self.position),
+            });
+        }
+
+        // The last character of a partial group carries bits past the end of the data
+        let unused_mask = match leftover {
+            2 => 0b1111,
+            3 => 0b11,
+            _ => 0,
+        };
+        if self.config.mode == Base64Mode::Strict
+            && leftover > 0
+            && self.group[leftover - 1] & unused_mask != 0
+        {
+            return Err(Base64Error::NonCanonicalTrailingBits {
+                position: self.last_position,
+            });
+        }
+
+        if leftover > 0 {
+            decode_group(&self.group[..leftover], output);
+            self.group_length = 0;
+        }
+        Ok(())
+    }
+}
+
+// Two to four symbol values into one to three bytes.
+fn decode_group<O: Extend<u8>>(values: &[u8], output: &mut O) {
+    let bits = values
+        .iter()
+        .fold(0u32, |bits, &value| (bits << 6) | value as u32)
+        << (6 * (4 - values.len()));
+    output.extend(bits.to_be_bytes()[1..values.len()].iter().copied());
+}
 
 pub fn string_to_base64(string: &str) -> String {
     let bytes = string.bytes().collect::<Vec<u8>>();
@@ -23,108 +618,204 @@ pub fn hex_to_base64(hex_string: &str) -> String {
     bytes_to_base64(&bytes)
 }
 
-#[rustfmt::skip]
 pub fn bytes_to_base64(bytes: &[u8]) -> String {
-    let padding_count = match bytes.len() % 3 {
-        0 => 0,
-        1 => 2,
-        2 => 1,
-        _ => unreachable!(),
-    };
+    Base64Config::STANDARD.encode(bytes)
+}
 
-    let padding = match padding_count {
-        1 => vec![0],
-        2 => vec![0, 0],
-        _ => Vec::new(),
-    };
-    
-    let padded_bytes = [bytes, padding.as_slice()].concat();
+// Positions are byte offsets into the input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Base64Error {
+    InvalidCharacter { position: usize, byte: u8 },
+    // Padding in the middle of the data, the wrong amount of it, none where it's required, or
+    // any where it's not allowed
+    InvalidPadding { position: usize },
+    // A single character left over after the last full group, which can't encode a byte
+    InvalidLength { characters: usize },
+    NonCanonicalTrailingBits { position: usize },
+    // A line break where the configured wrapping doesn't put one, or a line running past it
+    InvalidLineBreak { position: usize },
+}
 
-    let mut reader = BufReader::new(padded_bytes.as_slice());
+impl fmt::Display for Base64Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Base64Error::InvalidCharacter { position, byte } => write!(
+                f,
+                "Invalid base64 byte 0x{:02x} at position {}",
+                byte, position
+            ),
+            Base64Error::InvalidPadding { position } => {
+                write!(f, "Invalid base64 padding at position {}", position)
+            }
+            Base64Error::InvalidLength { characters } => write!(
+                f,
+                "Invalid base64 length: {} characters leaves one over",
+                characters
+            ),
+            Base64Error::NonCanonicalTrailingBits { position } => write!(
+                f,
+                "Non-zero unused bits in the base64 character at position {}",
+                position
+            ),
+            Base64Error::InvalidLineBreak { position } => {
+                write!(f, "Invalid base64 line break at position {}", position)
+            }
+        }
+    }
+}
 
-    let mut working_buffer: [u8; 3] = [0, 0, 0];
-    let mut result: Vec<u8> = Vec::new();
+impl Error for Base64Error {}
 
-    while let Ok(()) = reader.read_exact(&mut working_buffer) {
-        result.push((working_buffer[0] & 0b1111_1100) >> 2);
-        result.push((working_buffer[0] & 0b0000_0011) << 4 | (working_buffer[1] & 0b1111_0000) >> 4);
-        result.push((working_buffer[1] & 0b0000_1111) << 2 | (working_buffer[2] & 0b1100_0000) >> 6);
-        result.push( working_buffer[2] & 0b0011_1111);
-    }
+const STREAM_BUFFER_SIZE: usize = 4096;
 
-    let end = result.len() - 1;
+// Decodes base64 text read from the inner reader, a buffer at a time, so it works on inputs of
+// any size in constant memory. Errors come back as io::ErrorKind::InvalidData wrapping a
+// Base64Error, after everything that decoded before them.
+pub struct Base64Reader<R: Read> {
+    inner: R,
+    decoder: Decoder,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    output_position: usize,
+    finished: bool,
+    error: Option<Base64Error>,
+}
 
-    for i in 0..padding_count {
-        result[end - i] = 64;
+impl<R: Read> Base64Reader<R> {
+    pub fn new(inner: R, config: Base64Config) -> Base64Reader<R> {
+        Base64Reader {
+            inner,
+            decoder: Decoder::new(config),
+            input: vec![0; STREAM_BUFFER_SIZE],
+            output: Vec::with_capacity(STREAM_BUFFER_SIZE),
+            output_position: 0,
+            finished: false,
+            error: None,
+        }
     }
 
-    result
-        .into_iter()
-        .map(|c| BASE_64_TABLE[c as usize])
-        .collect()
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
 }
 
-pub fn base_64_character_to_byte(c: u8) -> u8 {
-    if c >= 65 && c <= 90 {
-        c - 65
-    } else if c >= 97 && c <= 122 {
-        c - 71
-    } else if c >= 48 && c <= 57 {
-        c + 4
-    } else if c == 43 {
-        62
-    } else if c == 47 {
-        63
-    } else if c == 61 {
-        0
-    } else {
-        unreachable!()
+impl<R: Read> Read for Base64Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.output_position < self.output.len() {
+                let available = &self.output[self.output_position..];
+                let length = std::cmp::min(available.len(), buf.len());
+                buf[..length].copy_from_slice(&available[..length]);
+                self.output_position += length;
+                return Ok(length);
+            }
+            if let Some(error) = self.error.take() {
+                self.finished = true;
+                return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+            }
+            if self.finished || buf.is_empty() {
+                return Ok(0);
+            }
+
+            self.output.clear();
+            self.output_position = 0;
+            let read = match self.inner.read(&mut self.input) {
+                Ok(read) => read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+
+            let decoder = &mut self.decoder;
+            let output = &mut self.output;
+            let result = if read == 0 {
+                self.finished = true;
+                decoder.finish(output)
+            } else {
+                self.input[..read]
+                    .iter()
+                    .try_for_each(|&byte| decoder.push(byte, output))
+            };
+            // Whatever decoded before the error is still handed out first
+            self.error = result.err();
+        }
     }
 }
 
-pub fn base64_to_hex_string<B: AsRef<[u8]>>(base64: B) -> String {
-    bytes_to_hex_string(&base64_to_bytes(base64))
+// Encodes everything written to it onto the inner writer. Up to two bytes are held back until
+// a group is complete, so finish has to be called to write out the end and any padding.
+pub struct Base64Writer<W: Write> {
+    inner: W,
+    encoder: Encoder,
 }
 
-#[rustfmt::skip]
-pub fn base64_to_bytes<B: AsRef<[u8]>>(base64: B) -> Vec<u8> {
-    let bytes: Vec<u8> = base64.as_ref()
-        .iter()
-        .cloned()
-        .filter(|c| *c != 10 && *c != 61) // filter out line feed and =
-        .map(base_64_character_to_byte)
-        .collect();
-
-    let padding_count = match bytes.len() % 4 {
-        0 => 0,
-        2 => 2, 
-        3 => 1,
-        1 => panic!("Invalid base64"),
-        _ => unreachable!(),
-    };
+impl<W: Write> Base64Writer<W> {
+    pub fn new(inner: W, config: Base64Config) -> Base64Writer<W> {
+        Base64Writer {
+            inner,
+            encoder: Encoder::new(config),
+        }
+    }
 
-    let padding = match padding_count {
-        1 => vec![0],
-        2 => vec![0, 0],
-        _ => Vec::new(),
-    };
-    
-    let padded_bytes = [bytes, padding].concat();
+    pub fn finish(mut self) -> io::Result<W> {
+        let mut output = Vec::with_capacity(8);
+        self.encoder.finish(&mut output);
+        self.inner.write_all(&output)?;
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
 
-    let mut reader = BufReader::new(padded_bytes.as_slice());
+impl<W: Write> Write for Base64Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut output = Vec::with_capacity(buf.len().div_ceil(3) * 4 + 8);
+        self.encoder.update(buf, &mut output);
+        self.inner.write_all(&output)?;
+        Ok(buf.len())
+    }
 
-    let mut working_buffer: [u8; 4] = [0, 0, 0, 0];
-    let mut result = Vec::new();
-    
-    while let Ok(()) = reader.read_exact(&mut working_buffer) {
-        result.push(working_buffer[0] << 2 | (working_buffer[1] & 0b0011_0000) >> 4);
-        result.push((working_buffer[1] & 0b0000_1111) << 4 | (working_buffer[2] & 0b0011_1100) >> 2);
-        result.push((working_buffer[2] & 0b0000_0011) << 6 | working_buffer[3]);
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
     }
+}
 
-    let result_length = result.len() - padding_count;
+pub fn try_base64_character_to_byte(c: u8) -> Option<u8> {
+    Alphabet::STANDARD.value(c)
+}
 
-    result.into_iter().take(result_length).collect()
+// Padding decodes as zero bits. Panics on anything outside the alphabet.
+pub fn base_64_character_to_byte(c: u8) -> u8 {
+    match c {
+        b'=' => 0,
+        _ => try_base64_character_to_byte(c)
+            .unwrap_or_else(|| panic!("Invalid base64 character: {}", c)),
+    }
+}
+
+// The standard alphabet. Strict decoding wants the padding; lenient decoding takes it or leaves
+// it.
+pub fn try_base64_to_bytes<B: AsRef<[u8]>>(
+    base64: B,
+    mode: Base64Mode,
+) -> Result<Vec<u8>, Base64Error> {
+    let padding = match mode {
+        Base64Mode::Strict => Padding::Required,
+        Base64Mode::Lenient => Padding::Optional,
+    };
+    Base64Config {
+        padding,
+        mode,
+        ..Base64Config::STANDARD
+    }
+    .decode(base64)
+}
+
+pub fn base64_to_hex_string<B: AsRef<[u8]>>(base64: B) -> String {
+    bytes_to_hex_string(&base64_to_bytes(base64))
+}
+
+// Lenient decoding; panics on input that isn't base64 even then.
+pub fn base64_to_bytes<B: AsRef<[u8]>>(base64: B) -> Vec<u8> {
+    try_base64_to_bytes(base64, Base64Mode::Lenient).unwrap_or_else(|error| panic!("{}", error))
 }
 
 #[cfg(test)]
@@ -188,5 +879,306 @@ mod tests {
 
         assert_eq!(String::from_utf8_lossy(&bytes), original);
     }
+
+    #[test]
+    fn test_try_base64_strict() {
+        assert_eq!(
+            try_base64_to_bytes("TWE=", Base64Mode::Strict),
+            Ok(b"Ma".to_vec())
+        );
+        assert_eq!(
+            try_base64_to_bytes("TWE", Base64Mode::Strict),
+            Err(Base64Error::InvalidPadding { position: 3 })
+        );
+        assert_eq!(
+            try_base64_to_bytes("TQ=", Base64Mode::Strict),
+            Err(Base64Error::InvalidPadding { position: 2 })
+        );
+        assert_eq!(
+            try_base64_to_bytes("TQ==TQ==", Base64Mode::Strict),
+            Err(Base64Error::InvalidPadding { position: 2 })
+        );
+        assert_eq!(
+            try_base64_to_bytes("TWFu\r\n", Base64Mode::Strict),
+            Err(Base64Error::InvalidCharacter {
+                position: 4,
+                byte: b'\r'
+            })
+        );
+        assert_eq!(
+            try_base64_to_bytes("TWFuT", Base64Mode::Strict),
+            Err(Base64Error::InvalidLength { characters: 5 })
+        );
+    }
+
+    #[test]
+    fn test_try_base64_non_canonical_bits() {
+        // "TR==" and "TWF=" carry set bits past the end of the data
+        assert_eq!(
+            try_base64_to_bytes("TR==", Base64Mode::Strict),
+            Err(Base64Error::NonCanonicalTrailingBits { position: 1 })
+        );
+        assert_eq!(
+            try_base64_to_bytes("TWF=", Base64Mode::Strict),
+            Err(Base64Error::NonCanonicalTrailingBits { position: 2 })
+        );
+        assert_eq!(
+            try_base64_to_bytes("TR==", Base64Mode::Lenient),
+            Ok(b"M".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_try_base64_lenient_whitespace() {
+        let crlf = "SSdtIGtp\r\nbGxpbmcg\r\n eW91ciBi\tcmFpbg==\r\n";
+        assert_eq!(
+            try_base64_to_bytes(crlf, Base64Mode::Lenient),
+            Ok(b"I'm killing your brain".to_vec())
+        );
+        assert_eq!(
+            try_base64_to_bytes("TWE", Base64Mode::Lenient),
+            Ok(b"Ma".to_vec())
+        );
+        assert_eq!(
+            try_base64_to_bytes("TQ=\n=", Base64Mode::Lenient),
+            Ok(b"M".to_vec())
+        );
+        assert_eq!(
+            try_base64_to_bytes("TQ==TQ==", Base64Mode::Lenient),
+            Err(Base64Error::InvalidPadding { position: 2 })
+        );
+        assert_eq!(
+            try_base64_to_bytes("TW-u", Base64Mode::Lenient),
+            Err(Base64Error::InvalidCharacter {
+                position: 2,
+                byte: b'-'
+            })
+        );
+    }
+
+    #[test]
+    fn test_url_safe_without_padding() {
+        let bytes = [0xfb, 0xff, 0xbf, 0x3e];
+        assert_eq!(bytes_to_base64(&bytes), "+/+/Pg==");
+        assert_eq!(Base64Config::URL_SAFE.encode(&bytes), "-_-_Pg");
+        assert_eq!(Base64Config::URL_SAFE.decode("-_-_Pg"), Ok(bytes.to_vec()));
+        assert_eq!(
+            Base64Config::URL_SAFE.decode("-_-_Pg=="),
+            Err(Base64Error::InvalidPadding { position: 6 })
+        );
+        assert_eq!(
+            Base64Config::URL_SAFE.decode("+/+/Pg"),
+            Err(Base64Error::InvalidCharacter {
+                position: 0,
+                byte: b'+'
+            })
+        );
+    }
+
+    #[test]
+    fn test_optional_padding() {
+        let config = Base64Config {
+            padding: Padding::Optional,
+            ..Base64Config::STANDARD
+        };
+        assert_eq!(config.encode(b"M"), "TQ==");
+        assert_eq!(config.decode("TQ=="), Ok(b"M".to_vec()));
+        assert_eq!(config.decode("TQ"), Ok(b"M".to_vec()));
+        assert_eq!(
+            config.decode("TQ="),
+            Err(Base64Error::InvalidPadding { position: 2 })
+        );
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        let symbols: Vec<u8> = Alphabet::STANDARD.symbols.iter().rev().copied().collect();
+        let config = Base64Config {
+            alphabet: Alphabet::custom(&symbols).unwrap(),
+            ..Base64Config::STANDARD
+        };
+        assert_eq!(config.encode(b"Man"), "sp6R");
+        assert_eq!(config.decode("sp6R"), Ok(b"Man".to_vec()));
+
+        let mut repeated = symbols.clone();
+        repeated[1] = repeated[0];
+        assert!(Alphabet::custom(&repeated).is_none());
+        let mut padded = symbols;
+        padded[0] = b'=';
+        assert!(Alphabet::custom(&padded).is_none());
+    }
+
+    #[test]
+    fn test_line_wrapping() {
+        let bytes: Vec<u8> = (0..100).collect();
+
+        let pem = Base64Config::PEM.encode(&bytes);
+        let lines: Vec<&str> = pem.split('\n').collect();
+        assert_eq!(
+            lines.iter().map(|line| line.len()).collect::<Vec<_>>(),
+            [64, 64, 8]
+        );
+        assert_eq!(lines.concat(), bytes_to_base64(&bytes));
+        assert_eq!(Base64Config::PEM.decode(&pem), Ok(bytes.clone()));
+        assert_eq!(
+            Base64Config::PEM.decode(pem.clone() + "\n"),
+            Ok(bytes.clone())
+        );
+
+        let mime = Base64Config::MIME.encode(&bytes);
+        assert_eq!(mime.find("\r\n"), Some(76));
+        assert_eq!(Base64Config::MIME.decode(&mime), Ok(bytes.clone()));
+
+        // The wrong ending, or a break in the wrong place
+        assert_eq!(
+            Base64Config::PEM.decode(&mime),
+            Err(Base64Error::InvalidLineBreak { position: 64 })
+        );
+        assert_eq!(
+            Base64Config::MIME.decode(&pem),
+            Err(Base64Error::InvalidLineBreak { position: 64 })
+        );
+        assert_eq!(
+            Base64Config::PEM.decode("TWFu\nTWFu"),
+            Err(Base64Error::InvalidLineBreak { position: 4 })
+        );
+
+        // Any width of at least one character
+        let narrow = Base64Config {
+            line_wrap: LineWrap::new(3, LineEnding::Lf),
+            ..Base64Config::STANDARD
+        };
+        assert_eq!(narrow.encode(b"Man"), "TWF\nu");
+        assert_eq!(narrow.encoded_length(3), 5);
+        assert_eq!(narrow.decode("TWF\nu"), Ok(b"Man".to_vec()));
+        assert_eq!(LineWrap::new(0, LineEnding::Lf), None);
+    }
+
+    // Reads in awkward little pieces, to catch state lost between reads
+    struct Trickle<'a> {
+        data: &'a [u8],
+        step: usize,
+    }
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let length = std::cmp::min(std::cmp::min(self.step, buf.len()), self.data.len());
+            buf[..length].copy_from_slice(&self.data[..length]);
+            self.data = &self.data[length..];
+            Ok(length)
+        }
+    }
+
+    #[test]
+    fn test_base64_writer_matches_encode() {
+        let bytes: Vec<u8> = (0..10_000).map(|i| (i * 7 + i / 13) as u8).collect();
+
+        for config in [
+            Base64Config::STANDARD,
+            Base64Config::URL_SAFE,
+            Base64Config::MIME,
+        ] {
+            let mut writer = Base64Writer::new(Vec::new(), config);
+            for piece in bytes.chunks(5) {
+                writer.write_all(piece).unwrap();
+            }
+            let encoded = writer.finish().unwrap();
+            assert_eq!(String::from_utf8(encoded).unwrap(), config.encode(&bytes));
+        }
+    }
+
+    #[test]
+    fn test_base64_reader_roundtrip() {
+        let bytes: Vec<u8> = (0..10_000).map(|i| (i * 7 + i / 13) as u8).collect();
+
+        for config in [
+            Base64Config::STANDARD,
+            Base64Config::URL_SAFE,
+            Base64Config::PEM,
+        ] {
+            let encoded = config.encode(&bytes);
+            for step in [1, 3, 4, 65, 5000] {
+                let mut reader = Base64Reader::new(
+                    Trickle {
+                        data: encoded.as_bytes(),
+                        step,
+                    },
+                    config,
+                );
+                let mut decoded = Vec::new();
+                reader.read_to_end(&mut decoded).unwrap();
+                assert_eq!(decoded, bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn test_base64_reader_error() {
+        let mut reader = Base64Reader::new("TWFuTW=u".as_bytes(), Base64Config::STANDARD);
+        let mut decoded = Vec::new();
+        let error = reader.read_to_end(&mut decoded).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            error
+                .into_inner()
+                .unwrap()
+                .downcast::<Base64Error>()
+                .ok()
+                .map(|e| *e),
+            Some(Base64Error::InvalidPadding { position: 6 })
+        );
+        assert_eq!(decoded, b"Man");
+    }
+
+    #[test]
+    fn test_base64_into_slices() {
+        let bytes: Vec<u8> = (0..1000).map(|i| (i * 7 + i / 13) as u8).collect();
+
+        for config in [
+            Base64Config::STANDARD,
+            Base64Config::URL_SAFE,
+            Base64Config::MIME,
+            Base64Config::PEM,
+        ] {
+            for length in [0, 1, 2, 3, 47, 48, 49, 57, 1000] {
+                let encoded_length = config.encoded_length(length);
+                let mut encoded = vec![0; encoded_length];
+                assert_eq!(
+                    config.encode_into(&bytes[..length], &mut encoded),
+                    encoded_length
+                );
+
+                let mut writer = Base64Writer::new(Vec::new(), config);
+                writer.write_all(&bytes[..length]).unwrap();
+                assert_eq!(encoded, writer.finish().unwrap());
+
+                let mut decoded = vec![0; length];
+                assert_eq!(config.decode_into(&encoded, &mut decoded), Ok(length));
+                assert_eq!(decoded, bytes[..length]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_base64_into_slice_errors() {
+        let mut encoded = bytes_to_base64(&[0; 30]).into_bytes();
+        encoded[21] = b'*';
+        let mut decoded = [0; 30];
+        assert_eq!(
+            Base64Config::STANDARD.decode_into(&encoded, &mut decoded),
+            Err(Base64Error::InvalidCharacter {
+                position: 21,
+                byte: b'*'
+            })
+        );
+        assert_eq!(
+            Base64Config::STANDARD.decode_into(b"TWFuTQ=xTWFu", &mut decoded),
+            Err(Base64Error::InvalidPadding { position: 6 })
+        );
+        assert_eq!(
+            Base64Config::STANDARD.decode_into(b"TWFuTWFuT", &mut decoded),
+            Err(Base64Error::InvalidLength { characters: 9 })
+        );
+    }
 }
 
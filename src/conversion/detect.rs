@@ -0,0 +1,290 @@
+// Guessing what an unknown blob is encoded as, and peeling off layer after layer of encoding
+// until what's left reads like plaintext.
+
+use crate::conversion::base32::try_base32_to_bytes;
+use crate::conversion::base64::{Alphabet, Base64Config, Base64Mode, Padding};
+use crate::conversion::hex::{try_ascii_hex_to_byte, try_hex_to_bytes, OddLength};
+use crate::decode::english_score;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    Hex,
+    Base64,
+    Base64Url,
+    Base32,
+    // URL percent-encoding, %XX escapes and + for a space as in form data
+    Percent,
+    // The input taken as it is
+    Raw,
+}
+
+const ENCODINGS: [Encoding; 5] = [
+    Encoding::Hex,
+    Encoding::Base64,
+    Encoding::Base64Url,
+    Encoding::Base32,
+    Encoding::Percent,
+];
+
+// One reading of an input: the encoding it's taken to be, how sure that is from 0 to 1, and what
+// it decodes to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layer {
+    pub encoding: Encoding,
+    pub confidence: f64,
+    pub decoded: Vec<u8>,
+}
+
+// How much the bytes read like English, from 0 to 1: the share of printable characters, times
+// how far the share of common letters is from that of random symbols (about 0.35 for base64) to
+// that of text with its spaces (0.75 and up).
+pub fn plaintext_confidence(bytes: &[u8]) -> f64 {
+    if bytes.is_empty() {
+        return 0.0;
+    }
+
+    let length = bytes.len() as f64;
+    let printable = bytes
+        .iter()
+        .filter(|byte| byte.is_ascii_graphic() || byte.is_ascii_whitespace())
+        .count();
+    let common = english_score(bytes) as f64 / length;
+    printable as f64 / length * ((common - 0.35) / 0.4).clamp(0.0, 1.0)
+}
+
+// Every way the input could be read, most confident first. Raw is always among them, scored by
+// how much the input reads like plaintext, and wins ties.
+//
+// An encoding's confidence is how well the characters fit it, scaled down by half when what
+// they decode to is neither plaintext nor another encoding.
+pub fn detect<B: AsRef<[u8]>>(input: B) -> Vec<Layer> {
+    let input = input.as_ref();
+    let text = input.trim_ascii();
+
+    let mut layers = vec![Layer {
+        encoding: Encoding::Raw,
+        confidence: plaintext_confidence(input),
+        decoded: input.to_vec(),
+    }];
+    for encoding in ENCODINGS {
+        if let Some((fit, decoded)) = decode_as(encoding, text) {
+            let confidence = fit * (0.5 + 0.5 * output_quality(&decoded));
+            layers.push(Layer {
+                encoding,
+                confidence,
+                decoded,
+            });
+        }
+    }
+
+    layers.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    layers
+}
+
+// Takes the most confident reading of the input over and over, until the input itself is the
+// most confident reading of it. Every decoding except percent-decoding is shorter than what it
+// came from, and percent-decoding is either shorter or the same length with fewer `+`s, so each
+// pass lowers (length, number of `+`) and this always stops. The last layer is Raw and holds the
+// result.
+pub fn peel<B: AsRef<[u8]>>(input: B) -> Vec<Layer> {
+    let mut layers = Vec::new();
+    let mut current = input.as_ref().to_vec();
+
+    loop {
+        let best = detect(&current).swap_remove(0);
+        let done = best.encoding == Encoding::Raw;
+        current = best.decoded.clone();
+        layers.push(best);
+        if done {
+            return layers;
+        }
+    }
+}
+
+// Whether the decoded bytes look like they were worth decoding: plaintext, or another layer.
+fn output_quality(decoded: &[u8]) -> f64 {
+    let text = decoded.trim_ascii();
+    ENCODINGS
+        .iter()
+        .filter_map(|&encoding| decode_as(encoding, text))
+        .map(|(fit, _)| fit)
+        .fold(plaintext_confidence(decoded), f64::max)
+}
+
+// How well the text's characters fit the encoding, from 0 to 1, and what they decode to. None
+// if it isn't that encoding at all.
+fn decode_as(encoding: Encoding, text: &[u8]) -> Option<(f64, Vec<u8>)> {
+    if text.is_empty() || !text.is_ascii() {
+        return None;
+    }
+
+    let symbols: Vec<u8> = text
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    // Wrapped lines are normal in base64 and base32, but spaces mean words
+    let spaces = text.iter().any(|&byte| byte == b' ' || byte == b'\t');
+
+    match encoding {
+        Encoding::Hex => {
+            let decoded =
+                try_hex_to_bytes(std::str::from_utf8(text).ok()?, OddLength::Reject).ok()?;
+            // All digits could just as well be a number
+            let fit = if symbols.iter().any(|byte| byte.is_ascii_alphabetic()) {
+                0.95
+            } else {
+                0.6
+            };
+            Some((fit, decoded))
+        }
+        Encoding::Base64 | Encoding::Base64Url => {
+            let url_safe = symbols.iter().any(|&byte| byte == b'-' || byte == b'_');
+            if spaces || url_safe != (encoding == Encoding::Base64Url) {
+                return None;
+            }
+
+            let config = Base64Config {
+                alphabet: if url_safe {
+                    Alphabet::URL_SAFE
+                } else {
+                    Alphabet::STANDARD
+                },
+                padding: Padding::Optional,
+                line_wrap: None,
+                mode: Base64Mode::Lenient,
+            };
+            let decoded = config.decode(&symbols).ok()?;
+
+            let mut fit = 0.6;
+            if symbols.len().is_multiple_of(4) {
+                fit += 0.2;
+            }
+            if symbols.iter().any(|byte| b"+/-_=".contains(byte)) {
+                fit += 0.15;
+            }
+            Some((fit, decoded))
+        }
+        Encoding::Base32 => {
+            let upper = symbols.iter().any(u8::is_ascii_uppercase);
+            let lower = symbols.iter().any(u8::is_ascii_lowercase);
+            if spaces || (upper && lower) {
+                return None;
+            }
+
+            let decoded = try_base32_to_bytes(&symbols).ok()?;
+            let fit = match (symbols.len() % 8, symbols.contains(&b'=')) {
+                (0, true) => 0.9,
+                (0, false) => 0.8,
+                _ => 0.6,
+            };
+            Some((fit, decoded))
+        }
+        Encoding::Percent => {
+            let decoded = percent_decode(text)?;
+            // A + on its own is just as likely base64, or a plus
+            let fit = if text.contains(&b'%') { 0.9 } else { 0.8 };
+            Some((fit, decoded))
+        }
+        Encoding::Raw => None,
+    }
+}
+
+// %XX escapes and + for a space, with everything else left as it is. Only counts if there's at
+// least one of them and every % starts an escape.
+fn percent_decode(text: &[u8]) -> Option<Vec<u8>> {
+    if !text.contains(&b'%') && !text.contains(&b'+') {
+        return None;
+    }
+
+    let mut result = Vec::with_capacity(text.len());
+    let mut index = 0;
+    while index < text.len() {
+        if text[index] == b'%' {
+            let high = try_ascii_hex_to_byte(*text.get(index + 1)?)?;
+            let low = try_ascii_hex_to_byte(*text.get(index + 2)?)?;
+            result.push((high << 4) | low);
+            index += 3;
+        } else if text[index] == b'+' {
+            result.push(b' ');
+            index += 1;
+        } else {
+            result.push(text[index]);
+            index += 1;
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::base32::bytes_to_base32;
+    use crate::conversion::base64::bytes_to_base64;
+    use crate::conversion::hex::bytes_to_hex_string;
+
+    fn encodings(layers: &[Layer]) -> Vec<Encoding> {
+        layers.iter().map(|layer| layer.encoding).collect()
+    }
+
+    #[test]
+    fn test_detect_single_encodings() {
+        let plaintext = b"attack at dawn";
+        let inputs = [
+            (bytes_to_hex_string(plaintext), Encoding::Hex),
+            (bytes_to_base64(plaintext), Encoding::Base64),
+            (bytes_to_base32(plaintext), Encoding::Base32),
+            ("attack%20at%20dawn".to_string(), Encoding::Percent),
+            ("attack+at+dawn".to_string(), Encoding::Percent),
+        ];
+
+        for (input, encoding) in inputs {
+            let best = &detect(input.as_bytes())[0];
+            assert_eq!(best.encoding, encoding, "{}", input);
+            assert_eq!(best.decoded, plaintext);
+        }
+
+        let url_safe = Base64Config::URL_SAFE.encode(b"<<???>>>");
+        assert_eq!(url_safe, "PDw_Pz8-Pj4");
+        let best = &detect(&url_safe)[0];
+        assert_eq!(best.encoding, Encoding::Base64Url);
+        assert_eq!(best.decoded, b"<<???>>>");
+    }
+
+    #[test]
+    fn test_peel_nested_layers() {
+        let plaintext = b"attack at dawn";
+        let input =
+            bytes_to_base64(bytes_to_hex_string(bytes_to_base64(plaintext).as_bytes()).as_bytes());
+
+        let layers = peel(input + "\n");
+        assert_eq!(
+            encodings(&layers),
+            [
+                Encoding::Base64,
+                Encoding::Hex,
+                Encoding::Base64,
+                Encoding::Raw
+            ]
+        );
+        assert_eq!(layers.last().unwrap().decoded, plaintext);
+        assert!(layers.iter().all(|layer| layer.confidence > 0.5));
+    }
+
+    #[test]
+    fn test_peel_stops_at_plaintext() {
+        assert_eq!(encodings(&peel("attack at dawn")), [Encoding::Raw]);
+        // Hex digits, but English words are the better reading
+        assert_eq!(encodings(&peel("decade")), [Encoding::Raw]);
+
+        // Binary under one layer of base64 still gets decoded
+        let layers = peel("3q2+7w==");
+        assert_eq!(encodings(&layers), [Encoding::Base64, Encoding::Raw]);
+        assert_eq!(layers[1].decoded, [0xde, 0xad, 0xbe, 0xef]);
+
+        // Decoding + as a space keeps the length, but there is one + fewer to decode next time
+        let layers = peel("a+b");
+        assert_eq!(encodings(&layers), [Encoding::Percent, Encoding::Raw]);
+        assert_eq!(layers[1].decoded, b"a b");
+    }
+}
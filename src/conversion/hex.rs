@@ -1,35 +1,184 @@
-pub fn hex_string_to_bytes(hex_string: &str) -> Vec<u8> {
-    let mut hex_bytes: Vec<u8> = hex_string.bytes().map(ascii_hex_to_byte).collect();
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+// What to do with an odd number of hex digits, which leaves half a byte over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OddLength {
+    Reject,
+    // A leading zero digit, as if the input were a number: "abc" is 0x0a 0xbc
+    PadLeft,
+    // A trailing zero digit: "abc" is 0xab 0xc0
+    PadRight,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HexError {
+    // Position is the byte offset of the character in the input
+    InvalidCharacter { position: usize, character: char },
+    OddLength { digits: usize },
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HexError::InvalidCharacter {
+                position,
+                character,
+            } => write!(
+                f,
+                "Invalid hex character {:?} at position {}",
+                character, position
+            ),
+            HexError::OddLength { digits } => write!(f, "Odd number of hex digits: {}", digits),
+        }
+    }
+}
+
+impl Error for HexError {}
+
+// Hex as it turns up pasted out of logs: whitespace anywhere is ignored, and each
+// whitespace-separated chunk may start with 0x or 0X.
+pub fn try_hex_to_bytes(hex_string: &str, odd_length: OddLength) -> Result<Vec<u8>, HexError> {
+    let mut nibbles = Vec::with_capacity(hex_string.len());
+    let mut chunk_start = true;
+    let mut characters = hex_string.char_indices().peekable();
+
+    while let Some((position, character)) = characters.next() {
+        if character.is_whitespace() {
+            chunk_start = true;
+            continue;
+        }
+
+        if chunk_start && character == '0' {
+            if let Some(&(_, 'x')) | Some(&(_, 'X')) = characters.peek() {
+                characters.next();
+                chunk_start = false;
+                continue;
+            }
+        }
+        chunk_start = false;
 
-    if hex_bytes.len() % 2 == 1 {
-        hex_bytes.insert(0, 0);
+        let nibble = Some(character)
+            .filter(char::is_ascii)
+            .and_then(|character| try_ascii_hex_to_byte(character as u8))
+            .ok_or(HexError::InvalidCharacter {
+                position,
+                character,
+            })?;
+        nibbles.push(nibble);
     }
 
-    hex_bytes
-        .as_slice()
+    if nibbles.len() % 2 == 1 {
+        match odd_length {
+            OddLength::Reject => {
+                return Err(HexError::OddLength {
+                    digits: nibbles.len(),
+                })
+            }
+            OddLength::PadLeft => nibbles.insert(0, 0),
+            OddLength::PadRight => nibbles.push(0),
+        }
+    }
+
+    Ok(nibbles
         .chunks(2)
-        .map(|c| (c[0] << 4) | (c[1]))
-        .collect()
+        .map(|pair| (pair[0] << 4) | pair[1])
+        .collect())
+}
+
+// Odd-length input gets a leading zero digit. Panics on anything that isn't hex or whitespace.
+// Plain even-length hex takes the table-driven path; anything else goes through try_hex_to_bytes.
+pub fn hex_string_to_bytes(hex_string: &str) -> Vec<u8> {
+    let mut bytes = vec![0; hex_string.len() / 2];
+    match decode_hex_into(hex_string.as_bytes(), &mut bytes) {
+        Ok(_) => bytes,
+        Err(_) => try_hex_to_bytes(hex_string, OddLength::PadLeft)
+            .unwrap_or_else(|error| panic!("{}", error)),
+    }
 }
 
 pub fn bytes_to_hex_string(bytes: &[u8]) -> String {
-    let result: Vec<[char; 2]> = bytes.iter().map(|b| byte_to_ascii_hex_pair(*b)).collect();
+    let mut output = vec![0; 2 * bytes.len()];
+    encode_hex_into(bytes, &mut output);
+    String::from_utf8(output).expect("hex digits are ASCII")
+}
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+const INVALID: u8 = 0xff;
+
+const HEX_VALUES: [u8; 256] = {
+    let mut values = [INVALID; 256];
+    let mut digit = 0;
+    while digit < 16 {
+        values[HEX_DIGITS[digit] as usize] = digit as u8;
+        values[HEX_DIGITS[digit].to_ascii_uppercase() as usize] = digit as u8;
+        digit += 1;
+    }
+    values
+};
+
+// Lowercase hex into the front of output, which needs room for two digits per byte. Returns the
+// number of digits written.
+pub fn encode_hex_into(bytes: &[u8], output: &mut [u8]) -> usize {
+    let length = 2 * bytes.len();
+    assert!(
+        output.len() >= length,
+        "Hex output needs {} bytes, got {}",
+        length,
+        output.len()
+    );
 
-    result.iter().flatten().collect()
+    for (byte, pair) in bytes.iter().zip(output.chunks_exact_mut(2)) {
+        pair[0] = HEX_DIGITS[(byte >> 4) as usize];
+        pair[1] = HEX_DIGITS[(byte & 0xf) as usize];
+    }
+    length
 }
 
-pub fn ascii_hex_to_byte(hex: u8) -> u8 {
-    if hex >= 48 && hex <= 57 {
-        hex - 48
-    } else if hex >= 65 && hex <= 70 {
-        hex - 55
-    } else if hex >= 97 && hex <= 102 {
-        hex - 87
-    } else {
-        panic!("Invalid hex character: {}", hex);
+// The strict counterpart of try_hex_to_bytes for plain hex: digits only, an even number of them.
+// Decodes into the front of output, which needs room for half as many bytes as there are digits,
+// and returns the number of bytes written.
+pub fn decode_hex_into(hex: &[u8], output: &mut [u8]) -> Result<usize, HexError> {
+    if hex.len() % 2 == 1 {
+        return Err(HexError::OddLength { digits: hex.len() });
+    }
+    let length = hex.len() / 2;
+    assert!(
+        output.len() >= length,
+        "Hex output needs {} bytes, got {}",
+        length,
+        output.len()
+    );
+
+    for (index, (pair, byte)) in hex.chunks_exact(2).zip(output.iter_mut()).enumerate() {
+        let high = HEX_VALUES[pair[0] as usize];
+        let low = HEX_VALUES[pair[1] as usize];
+        if (high | low) > 0xf {
+            let offset = if high == INVALID { 0 } else { 1 };
+            return Err(HexError::InvalidCharacter {
+                position: 2 * index + offset,
+                character: char::from(pair[offset]),
+            });
+        }
+        *byte = (high << 4) | low;
+    }
+    Ok(length)
+}
+
+pub fn try_ascii_hex_to_byte(hex: u8) -> Option<u8> {
+    match hex {
+        b'0'..=b'9' => Some(hex - b'0'),
+        b'A'..=b'F' => Some(hex - b'A' + 10),
+        b'a'..=b'f' => Some(hex - b'a' + 10),
+        _ => None,
     }
 }
 
+pub fn ascii_hex_to_byte(hex: u8) -> u8 {
+    try_ascii_hex_to_byte(hex).unwrap_or_else(|| panic!("Invalid hex character: {}", hex))
+}
+
 pub fn byte_to_ascii_hex_pair(byte: u8) -> [char; 2] {
     let c1 = (byte & 0b1111_0000) >> 4;
     let c2 = byte & 0b0000_1111;
@@ -39,13 +188,173 @@ pub fn byte_to_ascii_hex_pair(byte: u8) -> [char; 2] {
 pub fn byte_to_ascii_hex(byte: u8) -> char {
     if byte <= 9 {
         (byte + 48) as char
-    } else if byte >= 10 && byte <= 15 {
+    } else if byte <= 15 {
         (byte + 87) as char
     } else {
         panic!("Invalid hex value: {}", byte);
     }
 }
 
+const STREAM_BUFFER_SIZE: usize = 4096;
+
+// Decodes hex text read from the inner reader in constant memory, with the same tolerance for
+// whitespace and 0x prefixes as try_hex_to_bytes. Whitespace here is ASCII only, and a non-ASCII
+// byte is reported as the character with that code point. An odd number of digits is an error,
+// since padding on the left would need the whole input up front. Errors come back as
+// io::ErrorKind::InvalidData wrapping a HexError, after everything that decoded before them.
+pub struct HexReader<R: Read> {
+    inner: R,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    output_position: usize,
+    position: usize,
+    digits: usize,
+    high_nibble: Option<u8>,
+    chunk_start: bool,
+    // A 0 at the start of a chunk, which might be the start of a prefix
+    pending_zero: bool,
+    finished: bool,
+    error: Option<HexError>,
+}
+
+impl<R: Read> HexReader<R> {
+    pub fn new(inner: R) -> HexReader<R> {
+        HexReader {
+            inner,
+            input: vec![0; STREAM_BUFFER_SIZE],
+            output: Vec::with_capacity(STREAM_BUFFER_SIZE / 2),
+            output_position: 0,
+            position: 0,
+            digits: 0,
+            high_nibble: None,
+            chunk_start: true,
+            pending_zero: false,
+            finished: false,
+            error: None,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn push_nibble(&mut self, nibble: u8) {
+        self.digits += 1;
+        match self.high_nibble.take() {
+            Some(high) => self.output.push((high << 4) | nibble),
+            None => self.high_nibble = Some(nibble),
+        }
+    }
+
+    fn push(&mut self, byte: u8) -> Result<(), HexError> {
+        let position = self.position;
+        self.position += 1;
+
+        if std::mem::replace(&mut self.pending_zero, false) {
+            if byte == b'x' || byte == b'X' {
+                return Ok(());
+            }
+            self.push_nibble(0);
+        }
+
+        if byte.is_ascii_whitespace() {
+            self.chunk_start = true;
+            return Ok(());
+        }
+        if std::mem::replace(&mut self.chunk_start, false) && byte == b'0' {
+            self.pending_zero = true;
+            return Ok(());
+        }
+
+        let nibble = try_ascii_hex_to_byte(byte).ok_or(HexError::InvalidCharacter {
+            position,
+            character: char::from(byte),
+        })?;
+        self.push_nibble(nibble);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), HexError> {
+        if std::mem::replace(&mut self.pending_zero, false) {
+            self.push_nibble(0);
+        }
+        if self.high_nibble.is_some() {
+            return Err(HexError::OddLength {
+                digits: self.digits,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for HexReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            if self.output_position < self.output.len() {
+                let available = &self.output[self.output_position..];
+                let length = std::cmp::min(available.len(), buf.len());
+                buf[..length].copy_from_slice(&available[..length]);
+                self.output_position += length;
+                return Ok(length);
+            }
+            if let Some(error) = self.error.take() {
+                self.finished = true;
+                return Err(io::Error::new(io::ErrorKind::InvalidData, error));
+            }
+            if self.finished || buf.is_empty() {
+                return Ok(0);
+            }
+
+            self.output.clear();
+            self.output_position = 0;
+            let read = match self.inner.read(&mut self.input) {
+                Ok(read) => read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            };
+
+            let result = if read == 0 {
+                self.finished = true;
+                self.finish()
+            } else {
+                (0..read).try_for_each(|index| {
+                    let byte = self.input[index];
+                    self.push(byte)
+                })
+            };
+            self.error = result.err();
+        }
+    }
+}
+
+// Writes everything written to it onto the inner writer as lowercase hex.
+pub struct HexWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> HexWriter<W> {
+    pub fn new(inner: W) -> HexWriter<W> {
+        HexWriter { inner }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for HexWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut output = vec![0; 2 * buf.len()];
+        encode_hex_into(buf, &mut output);
+        self.inner.write_all(&output)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -85,4 +394,138 @@ mod tests {
         let expected_output = "01c0111001f010100061a024b53535009181c1";
         assert_eq!(bytes_to_hex_string(&test_bytes), expected_output);
     }
+
+    #[test]
+    fn test_try_hex_to_bytes_invalid_character() {
+        assert_eq!(
+            try_hex_to_bytes("12 3g", OddLength::Reject),
+            Err(HexError::InvalidCharacter {
+                position: 4,
+                character: 'g'
+            })
+        );
+        assert_eq!(
+            try_hex_to_bytes("ab\u{e9}", OddLength::Reject),
+            Err(HexError::InvalidCharacter {
+                position: 2,
+                character: '\u{e9}'
+            })
+        );
+    }
+
+    #[test]
+    fn test_try_hex_to_bytes_odd_length() {
+        assert_eq!(
+            try_hex_to_bytes("abc", OddLength::Reject),
+            Err(HexError::OddLength { digits: 3 })
+        );
+        assert_eq!(
+            try_hex_to_bytes("abc", OddLength::PadLeft),
+            Ok(vec![0x0a, 0xbc])
+        );
+        assert_eq!(
+            try_hex_to_bytes("abc", OddLength::PadRight),
+            Ok(vec![0xab, 0xc0])
+        );
+    }
+
+    #[test]
+    fn test_try_hex_to_bytes_pasted_input() {
+        let pasted = "  0xDEAD beef\r\n\t0Xca fe ";
+        assert_eq!(
+            try_hex_to_bytes(pasted, OddLength::Reject),
+            Ok(vec![0xde, 0xad, 0xbe, 0xef, 0xca, 0xfe])
+        );
+
+        // Only a chunk's leading 0x is a prefix
+        assert_eq!(
+            try_hex_to_bytes("ab0x", OddLength::Reject),
+            Err(HexError::InvalidCharacter {
+                position: 3,
+                character: 'x'
+            })
+        );
+        assert_eq!(try_hex_to_bytes("0x", OddLength::Reject), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_hex_writer_and_reader() {
+        let bytes: Vec<u8> = (0..10_000).map(|i| (i * 31 + i / 7) as u8).collect();
+
+        let mut writer = HexWriter::new(Vec::new());
+        for piece in bytes.chunks(7) {
+            writer.write_all(piece).unwrap();
+        }
+        let encoded = writer.into_inner();
+        assert_eq!(encoded, bytes_to_hex_string(&bytes).into_bytes());
+
+        let mut decoded = Vec::new();
+        HexReader::new(&encoded[..])
+            .read_to_end(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, bytes);
+    }
+
+    #[test]
+    fn test_hex_reader_pasted_input() {
+        // Enough leading whitespace that the prefix straddles two reads
+        let input = " ".repeat(STREAM_BUFFER_SIZE - 1) + "0xDEAD 0Xbeef\n00 01\n0";
+        let mut decoded = Vec::new();
+        let error = HexReader::new(input.as_bytes())
+            .read_to_end(&mut decoded)
+            .unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(decoded, [0xde, 0xad, 0xbe, 0xef, 0x00, 0x01]);
+
+        let mut decoded = Vec::new();
+        let error = HexReader::new(&b"abcd ef0g"[..])
+            .read_to_end(&mut decoded)
+            .unwrap_err();
+        assert_eq!(
+            error
+                .into_inner()
+                .unwrap()
+                .downcast::<HexError>()
+                .ok()
+                .map(|e| *e),
+            Some(HexError::InvalidCharacter {
+                position: 8,
+                character: 'g'
+            })
+        );
+        assert_eq!(decoded, [0xab, 0xcd, 0xef]);
+    }
+
+    #[test]
+    fn test_hex_into_slices() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let mut hex = [0; 600];
+        assert_eq!(encode_hex_into(&bytes, &mut hex), 512);
+        assert_eq!(hex[..512], *bytes_to_hex_string(&bytes).as_bytes());
+
+        let mut decoded = [0; 300];
+        assert_eq!(decode_hex_into(&hex[..512], &mut decoded), Ok(256));
+        assert_eq!(decoded[..256], bytes[..]);
+        assert_eq!(decode_hex_into(b"ABcd", &mut decoded), Ok(2));
+        assert_eq!(decoded[..2], [0xab, 0xcd]);
+
+        assert_eq!(
+            decode_hex_into(b"abc", &mut decoded),
+            Err(HexError::OddLength { digits: 3 })
+        );
+        assert_eq!(
+            decode_hex_into(b"ab 0", &mut decoded),
+            Err(HexError::InvalidCharacter {
+                position: 2,
+                character: ' '
+            })
+        );
+        assert_eq!(
+            decode_hex_into(b"a\xe9", &mut decoded),
+            Err(HexError::InvalidCharacter {
+                position: 1,
+                character: '\u{e9}'
+            })
+        );
+    }
 }
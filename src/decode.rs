@@ -1,5 +1,3 @@
-use std::collections::HashSet;
-
 use crate::ciphers;
 
 pub struct SingleByteXorResult {
@@ -8,25 +6,23 @@ pub struct SingleByteXorResult {
     pub xor_byte: u8,
 }
 
+// How many of the bytes are among the most common characters in English text.
+pub fn english_score(bytes: &[u8]) -> usize {
+    let most_common_letters = b"ETAOINSHRDLUetaoinshrdlu ";
+
+    bytes
+        .iter()
+        .filter(|byte| most_common_letters.contains(byte))
+        .count()
+}
+
 pub fn single_byte_xor<B: AsRef<[u8]>>(bytes: B) -> SingleByteXorResult {
     let mut result: Vec<SingleByteXorResult> = Vec::new();
 
-    let most_common_letters: HashSet<char> = [
-        'E', 'T', 'A', 'O', 'I', 'N', 'S', 'H', 'R', 'D', 'L', 'U', 'e', 't', 'a', 'o', 'i', 'n',
-        's', 'h', 'r', 'd', 'l', 'u', ' ',
-    ]
-    .iter()
-    .copied()
-    .collect();
-
     for xor_byte in 0..127 {
         let result_bytes = ciphers::single_byte_xor(bytes.as_ref(), xor_byte);
         let message = String::from_utf8_lossy(&result_bytes).into_owned();
-
-        let score: usize = result_bytes
-            .into_iter()
-            .filter(|byte| most_common_letters.contains(&(*byte as char)))
-            .count();
+        let score = english_score(&result_bytes);
 
         result.push(SingleByteXorResult { message, score, xor_byte });
     }
@@ -0,0 +1,92 @@
+pub mod curves;
+pub mod montgomery;
+pub mod weierstrass;
+
+use crate::math::bigint::BigUint;
+
+// Arithmetic in the prime field both curve forms live over. Operands are already reduced
+// mod p.
+pub fn add_mod(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    let sum = a + b;
+    if &sum >= p {
+        &sum - p
+    } else {
+        sum
+    }
+}
+
+pub fn sub_mod(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    if a >= b {
+        a - b
+    } else {
+        &(a + p) - b
+    }
+}
+
+pub fn mul_mod(a: &BigUint, b: &BigUint, p: &BigUint) -> BigUint {
+    &(a * b) % p
+}
+
+// Fermat's little theorem: a^(p - 2) = a^-1. At curve sizes the Montgomery modpow is a few
+// times quicker than extended Euclid. Zero maps to zero.
+pub fn invert_mod(a: &BigUint, p: &BigUint) -> BigUint {
+    a.modpow(&(p - &BigUint::from(2u32)), p)
+}
+
+// Montgomery's trick: the inverses of every value for the price of one inversion and three
+// multiplications each. The values must all be non-zero.
+pub fn batch_invert(values: &[BigUint], p: &BigUint) -> Vec<BigUint> {
+    let mut prefixes = Vec::with_capacity(values.len());
+    let mut product = BigUint::one();
+    for value in values {
+        prefixes.push(product.clone());
+        product = mul_mod(&product, value, p);
+    }
+
+    let mut inverse = invert_mod(&product, p);
+    let mut inverses = vec![BigUint::zero(); values.len()];
+    for (index, value) in values.iter().enumerate().rev() {
+        inverses[index] = mul_mod(&inverse, &prefixes[index], p);
+        inverse = mul_mod(&inverse, value, p);
+    }
+
+    inverses
+}
+
+// A decimal field element, where a leading minus sign means the negation mod p.
+pub fn parse_field_element(decimal: &str, p: &BigUint) -> BigUint {
+    match decimal.strip_prefix('-') {
+        Some(magnitude) => sub_mod(
+            &BigUint::zero(),
+            &(&BigUint::from_decimal(magnitude) % p),
+            p,
+        ),
+        None => &BigUint::from_decimal(decimal) % p,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_invert() {
+        let p = BigUint::from(1_000_003u32);
+        let values: Vec<BigUint> = [2u32, 3, 999_999, 123_456].map(BigUint::from).to_vec();
+
+        let inverses = batch_invert(&values, &p);
+        for (value, inverse) in values.iter().zip(inverses.iter()) {
+            assert!(mul_mod(value, inverse, &p).is_one());
+            assert_eq!(inverse, &value.modinv(&p).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_parse_field_element() {
+        let p = BigUint::from(101u32);
+
+        assert_eq!(parse_field_element("7", &p), BigUint::from(7u32));
+        assert_eq!(parse_field_element("-7", &p), BigUint::from(94u32));
+        assert_eq!(parse_field_element("-0", &p), BigUint::zero());
+    }
+}
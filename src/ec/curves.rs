@@ -0,0 +1,25 @@
+use crate::ec::montgomery::MontgomerySpec;
+use crate::ec::weierstrass::WeierstrassSpec;
+
+// The 128-bit curve from Challenge 59: y^2 = x^3 - 95051x + 11279326, with a base point of
+// prime order n and 8n points in all.
+pub const CHALLENGE_59: WeierstrassSpec = WeierstrassSpec {
+    p: "233970423115425145524320034830162017933",
+    a: "-95051",
+    b: "11279326",
+    gx: "182",
+    gy: "85518893674295321206118380980485522083",
+    order: "29246302889428143187362802287225875743",
+    cofactor: "8",
+};
+
+// The same group in Montgomery form, from Challenge 60: v^2 = u^3 + 534u^2 + u, where
+// u = x - 178.
+pub const CHALLENGE_60: MontgomerySpec = MontgomerySpec {
+    p: "233970423115425145524320034830162017933",
+    a: "534",
+    b: "1",
+    u: "4",
+    order: "29246302889428143187362802287225875743",
+    cofactor: "8",
+};
@@ -0,0 +1,313 @@
+use crate::ec::weierstrass::WeierstrassCurve;
+use crate::ec::{add_mod, invert_mod, mul_mod, parse_field_element, sub_mod};
+use crate::math::bigint::BigUint;
+use crate::math::number_theory::is_quadratic_residue;
+use crate::random::Rng;
+
+// A Montgomery curve Bv^2 = u^3 + Au^2 + u and the u coordinate of a base point, as decimal
+// strings (see `ec::curves`).
+#[derive(Clone, Copy, Debug)]
+pub struct MontgomerySpec {
+    pub p: &'static str,
+    pub a: &'static str,
+    pub b: &'static str,
+    pub u: &'static str,
+    pub order: &'static str,
+    pub cofactor: &'static str,
+}
+
+// (X : Z) stands for u = X / Z; Z = 0 is the point at infinity. The ladder never learns v,
+// so a point and its negation look the same.
+#[derive(Clone, Debug)]
+pub struct LadderPoint {
+    pub x: BigUint,
+    pub z: BigUint,
+}
+
+impl LadderPoint {
+    pub fn infinity() -> Self {
+        LadderPoint {
+            x: BigUint::one(),
+            z: BigUint::zero(),
+        }
+    }
+
+    pub fn from_u(u: &BigUint) -> Self {
+        LadderPoint {
+            x: u.clone(),
+            z: BigUint::one(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MontgomeryCurve {
+    pub p: BigUint,
+    pub a: BigUint,
+    pub b: BigUint,
+    // (A + 2) / 4, the constant in the doubling formula
+    a24: BigUint,
+}
+
+impl MontgomeryCurve {
+    pub fn new(p: BigUint, a: BigUint, b: BigUint) -> Self {
+        let a24 = mul_mod(
+            &add_mod(&a, &BigUint::from(2u32), &p),
+            &invert_mod(&BigUint::from(4u32), &p),
+            &p,
+        );
+        MontgomeryCurve { p, a, b, a24 }
+    }
+
+    // (u^3 + Au^2 + u) / B, which is v^2 for points on the curve
+    pub fn v_squared(&self, u: &BigUint) -> BigUint {
+        let p = &self.p;
+        let quadratic = add_mod(&mul_mod(&add_mod(u, &self.a, p), u, p), &BigUint::one(), p);
+        mul_mod(&mul_mod(&quadratic, u, p), &invert_mod(&self.b, p), p)
+    }
+
+    // Every u is on either the curve or its quadratic twist, where v^2 has to be a non-square.
+    pub fn is_on_curve(&self, u: &BigUint) -> bool {
+        is_quadratic_residue(&self.v_squared(u), &self.p)
+    }
+
+    pub fn double(&self, point: &LadderPoint) -> LadderPoint {
+        let p = &self.p;
+        let sum = add_mod(&point.x, &point.z, p);
+        let difference = sub_mod(&point.x, &point.z, p);
+        let sum_squared = mul_mod(&sum, &sum, p);
+        let difference_squared = mul_mod(&difference, &difference, p);
+        // 4XZ
+        let cross = sub_mod(&sum_squared, &difference_squared, p);
+
+        let x = mul_mod(&sum_squared, &difference_squared, p);
+        let z = mul_mod(
+            &cross,
+            &add_mod(&difference_squared, &mul_mod(&self.a24, &cross, p), p),
+            p,
+        );
+        LadderPoint { x, z }
+    }
+
+    // first + second, given first - second. Without v that difference is needed to tell
+    // first + second apart from first - second.
+    pub fn differential_add(
+        &self,
+        first: &LadderPoint,
+        second: &LadderPoint,
+        difference: &LadderPoint,
+    ) -> LadderPoint {
+        let p = &self.p;
+        let da = mul_mod(
+            &sub_mod(&first.x, &first.z, p),
+            &add_mod(&second.x, &second.z, p),
+            p,
+        );
+        let cb = mul_mod(
+            &add_mod(&first.x, &first.z, p),
+            &sub_mod(&second.x, &second.z, p),
+            p,
+        );
+
+        let sum = add_mod(&da, &cb, p);
+        let difference_term = sub_mod(&da, &cb, p);
+        let x = mul_mod(&difference.z, &mul_mod(&sum, &sum, p), p);
+        let z = mul_mod(
+            &difference.x,
+            &mul_mod(&difference_term, &difference_term, p),
+            p,
+        );
+        LadderPoint { x, z }
+    }
+
+    // The u coordinate of k * P from the u coordinate of P. Each step keeps (kP, (k + 1)P),
+    // whose difference is always P. The point at infinity comes out as u = 0, as in RFC 7748.
+    pub fn ladder(&self, u: &BigUint, k: &BigUint) -> BigUint {
+        let base = LadderPoint::from_u(&(u % &self.p));
+        let mut low = LadderPoint::infinity();
+        let mut high = base.clone();
+
+        for index in (0..k.bits()).rev() {
+            let sum = self.differential_add(&low, &high, &base);
+            if k.bit(index) {
+                high = self.double(&high);
+                low = sum;
+            } else {
+                low = self.double(&low);
+                high = sum;
+            }
+        }
+
+        self.to_u(&low)
+    }
+
+    pub fn to_u(&self, point: &LadderPoint) -> BigUint {
+        mul_mod(&point.x, &invert_mod(&point.z, &self.p), &self.p)
+    }
+
+    // The isomorphic short Weierstrass curve. Scaling by B gives
+    // Y^2 = U^3 + AB U^2 + B^2 U, and shifting U by AB / 3 clears the square term, leaving
+    // a = B^2 (3 - A^2) / 3 and b = B^3 (2A^3 - 9A) / 27.
+    pub fn to_weierstrass(&self) -> WeierstrassCurve {
+        let p = &self.p;
+        let a_squared = mul_mod(&self.a, &self.a, p);
+        let b_squared = mul_mod(&self.b, &self.b, p);
+
+        let three_inverse = invert_mod(&BigUint::from(3u32), p);
+        let linear = mul_mod(
+            &mul_mod(&b_squared, &sub_mod(&BigUint::from(3u32), &a_squared, p), p),
+            &three_inverse,
+            p,
+        );
+
+        let twice_cube = mul_mod(&BigUint::from(2u32), &mul_mod(&a_squared, &self.a, p), p);
+        let constant = mul_mod(
+            &mul_mod(
+                &mul_mod(&b_squared, &self.b, p),
+                &sub_mod(&twice_cube, &mul_mod(&BigUint::from(9u32), &self.a, p), p),
+                p,
+            ),
+            &invert_mod(&BigUint::from(27u32), p),
+            p,
+        );
+
+        WeierstrassCurve::new(p.clone(), linear, constant)
+    }
+
+    // x = Bu + AB / 3 on the curve from `to_weierstrass`.
+    pub fn u_to_x(&self, u: &BigUint) -> BigUint {
+        let p = &self.p;
+        let shift = mul_mod(
+            &mul_mod(&self.a, &self.b, p),
+            &invert_mod(&BigUint::from(3u32), p),
+            p,
+        );
+        add_mod(&mul_mod(&self.b, &(u % p), p), &shift, p)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct MontgomeryKeypair {
+    pub private_key: BigUint,
+    pub public_key: BigUint,
+}
+
+// A curve and the u coordinate of a base point of prime order: X25519-style key agreement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MontgomeryGroup {
+    pub curve: MontgomeryCurve,
+    pub base: BigUint,
+    pub order: BigUint,
+    pub cofactor: BigUint,
+}
+
+impl MontgomeryGroup {
+    pub fn from_spec(spec: &MontgomerySpec) -> Self {
+        let p = BigUint::from_decimal(spec.p);
+        let curve = MontgomeryCurve::new(
+            p.clone(),
+            parse_field_element(spec.a, &p),
+            parse_field_element(spec.b, &p),
+        );
+
+        MontgomeryGroup {
+            curve,
+            base: parse_field_element(spec.u, &p),
+            order: BigUint::from_decimal(spec.order),
+            cofactor: BigUint::from_decimal(spec.cofactor),
+        }
+    }
+
+    // The curve and its twist together have 2p + 2 points.
+    pub fn twist_order(&self) -> BigUint {
+        let curve_order = &self.order * &self.cofactor;
+        &(&(&self.curve.p + &BigUint::one()) << 1) - &curve_order
+    }
+
+    pub fn generate_keypair(&self, rng: &mut Rng) -> MontgomeryKeypair {
+        self.keypair_from_private(BigUint::random_range(rng, &BigUint::one(), &self.order))
+    }
+
+    pub fn keypair_from_private(&self, private_key: BigUint) -> MontgomeryKeypair {
+        let public_key = self.curve.ladder(&self.base, &private_key);
+        MontgomeryKeypair {
+            private_key,
+            public_key,
+        }
+    }
+
+    // Any u is accepted: the ladder can't tell the curve from its twist.
+    pub fn shared_secret(
+        &self,
+        keypair: &MontgomeryKeypair,
+        other_public_key: &BigUint,
+    ) -> BigUint {
+        self.curve.ladder(other_public_key, &keypair.private_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::curves;
+    use crate::ec::weierstrass::{Point, WeierstrassGroup};
+
+    #[test]
+    fn test_ladder_matches_weierstrass() {
+        let group = MontgomeryGroup::from_spec(&curves::CHALLENGE_60);
+        let weierstrass = WeierstrassGroup::from_spec(&curves::CHALLENGE_59);
+
+        assert_eq!(group.curve.to_weierstrass(), weierstrass.curve);
+        assert_eq!(
+            Some(&group.curve.u_to_x(&group.base)),
+            weierstrass.generator.x()
+        );
+
+        for k in [1u32, 2, 3, 1000, 65537] {
+            let k = BigUint::from(k);
+            let u = group.curve.ladder(&group.base, &k);
+            let point = weierstrass
+                .curve
+                .scalar_multiply(&weierstrass.generator, &k);
+            assert_eq!(Some(&group.curve.u_to_x(&u)), point.x());
+        }
+
+        assert!(group.curve.ladder(&group.base, &group.order).is_zero());
+        assert_eq!(
+            weierstrass
+                .curve
+                .scalar_multiply(&weierstrass.generator, &group.order),
+            Point::Infinity
+        );
+    }
+
+    #[test]
+    fn test_twist_order() {
+        let mut rng = Rng::new(60);
+        let group = MontgomeryGroup::from_spec(&curves::CHALLENGE_60);
+        let twist_order = group.twist_order();
+
+        let mut twist_points = 0;
+        while twist_points < 5 {
+            let u = BigUint::random_below(&mut rng, &group.curve.p);
+            if !group.curve.is_on_curve(&u) {
+                assert!(group.curve.ladder(&u, &twist_order).is_zero());
+                twist_points += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_key_agreement() {
+        let mut rng = Rng::new(61);
+        let group = MontgomeryGroup::from_spec(&curves::CHALLENGE_60);
+        let alice = group.generate_keypair(&mut rng);
+        let bob = group.generate_keypair(&mut rng);
+
+        assert!(group.curve.is_on_curve(&alice.public_key));
+        assert_eq!(
+            group.shared_secret(&alice, &bob.public_key),
+            group.shared_secret(&bob, &alice.public_key)
+        );
+    }
+}
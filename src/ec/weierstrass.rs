@@ -0,0 +1,378 @@
+use crate::ec::{add_mod, invert_mod, mul_mod, parse_field_element, sub_mod};
+use crate::math::bigint::BigUint;
+use crate::math::number_theory::sqrt_mod;
+use crate::random::Rng;
+
+// A short Weierstrass curve y^2 = x^3 + ax + b and a base point, as decimal strings so that a
+// new curve is just a new constant (see `ec::curves`).
+#[derive(Clone, Copy, Debug)]
+pub struct WeierstrassSpec {
+    pub p: &'static str,
+    pub a: &'static str,
+    pub b: &'static str,
+    pub gx: &'static str,
+    pub gy: &'static str,
+    // Order of the base point, and the number of points on the curve divided by it
+    pub order: &'static str,
+    pub cofactor: &'static str,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Point {
+    Infinity,
+    Affine { x: BigUint, y: BigUint },
+}
+
+impl Point {
+    pub fn new(x: BigUint, y: BigUint) -> Self {
+        Point::Affine { x, y }
+    }
+
+    pub fn x(&self) -> Option<&BigUint> {
+        match self {
+            Point::Infinity => None,
+            Point::Affine { x, .. } => Some(x),
+        }
+    }
+}
+
+// (X : Y : Z) stands for the affine point (X / Z^2, Y / Z^3); Z = 0 is the point at infinity.
+// Scalar multiplication works in these coordinates so it only needs one inversion at the end.
+#[derive(Clone, Debug)]
+pub struct JacobianPoint {
+    pub x: BigUint,
+    pub y: BigUint,
+    pub z: BigUint,
+}
+
+impl JacobianPoint {
+    pub fn infinity() -> Self {
+        JacobianPoint {
+            x: BigUint::one(),
+            y: BigUint::one(),
+            z: BigUint::zero(),
+        }
+    }
+
+    pub fn from_affine(point: &Point) -> Self {
+        match point {
+            Point::Infinity => JacobianPoint::infinity(),
+            Point::Affine { x, y } => JacobianPoint {
+                x: x.clone(),
+                y: y.clone(),
+                z: BigUint::one(),
+            },
+        }
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+}
+
+// The curve alone. None of the arithmetic below reads b, which is exactly what the invalid
+// curve attack exploits: points on a curve with a different b are added by the same formulas.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeierstrassCurve {
+    pub p: BigUint,
+    pub a: BigUint,
+    pub b: BigUint,
+}
+
+impl WeierstrassCurve {
+    pub fn new(p: BigUint, a: BigUint, b: BigUint) -> Self {
+        WeierstrassCurve { p, a, b }
+    }
+
+    // The same field and a, but a different b.
+    pub fn with_b(&self, b: BigUint) -> Self {
+        WeierstrassCurve { b, ..self.clone() }
+    }
+
+    // x^3 + ax + b
+    pub fn rhs(&self, x: &BigUint) -> BigUint {
+        let p = &self.p;
+        let x_squared = mul_mod(x, x, p);
+        let cubic = mul_mod(&add_mod(&x_squared, &self.a, p), x, p);
+        add_mod(&cubic, &self.b, p)
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        match point {
+            Point::Infinity => true,
+            Point::Affine { x, y } => mul_mod(y, y, &self.p) == self.rhs(x),
+        }
+    }
+
+    // One of the (up to two) points with this x coordinate.
+    pub fn lift_x(&self, x: &BigUint) -> Option<Point> {
+        let y = sqrt_mod(&self.rhs(x), &self.p)?;
+        Some(Point::new(x % &self.p, y))
+    }
+
+    pub fn random_point(&self, rng: &mut Rng) -> Point {
+        loop {
+            let x = BigUint::random_below(rng, &self.p);
+            if let Some(point) = self.lift_x(&x) {
+                return point;
+            }
+        }
+    }
+
+    pub fn negate(&self, point: &Point) -> Point {
+        match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine { x, y } => Point::new(x.clone(), sub_mod(&BigUint::zero(), y, &self.p)),
+        }
+    }
+
+    pub fn add(&self, first: &Point, second: &Point) -> Point {
+        let p = &self.p;
+        let (x1, y1, x2, y2) = match (first, second) {
+            (Point::Infinity, _) => return second.clone(),
+            (_, Point::Infinity) => return first.clone(),
+            (Point::Affine { x: x1, y: y1 }, Point::Affine { x: x2, y: y2 }) => (x1, y1, x2, y2),
+        };
+
+        let slope = if x1 == x2 {
+            if add_mod(y1, y2, p).is_zero() {
+                return Point::Infinity;
+            }
+            // Tangent: (3x^2 + a) / 2y
+            let x_squared = mul_mod(x1, x1, p);
+            let numerator = add_mod(&mul_mod(&BigUint::from(3u32), &x_squared, p), &self.a, p);
+            mul_mod(&numerator, &invert_mod(&add_mod(y1, y1, p), p), p)
+        } else {
+            mul_mod(&sub_mod(y2, y1, p), &invert_mod(&sub_mod(x2, x1, p), p), p)
+        };
+
+        let x3 = sub_mod(&sub_mod(&mul_mod(&slope, &slope, p), x1, p), x2, p);
+        let y3 = sub_mod(&mul_mod(&slope, &sub_mod(x1, &x3, p), p), y1, p);
+        Point::new(x3, y3)
+    }
+
+    pub fn double(&self, point: &Point) -> Point {
+        self.add(point, point)
+    }
+
+    pub fn to_affine(&self, point: &JacobianPoint) -> Point {
+        if point.is_infinity() {
+            return Point::Infinity;
+        }
+
+        let p = &self.p;
+        let z_inverse = invert_mod(&point.z, p);
+        let z_inverse_squared = mul_mod(&z_inverse, &z_inverse, p);
+        let x = mul_mod(&point.x, &z_inverse_squared, p);
+        let y = mul_mod(&point.y, &mul_mod(&z_inverse_squared, &z_inverse, p), p);
+        Point::new(x, y)
+    }
+
+    pub fn jacobian_double(&self, point: &JacobianPoint) -> JacobianPoint {
+        let p = &self.p;
+        if point.is_infinity() || point.y.is_zero() {
+            return JacobianPoint::infinity();
+        }
+
+        let xx = mul_mod(&point.x, &point.x, p);
+        let yy = mul_mod(&point.y, &point.y, p);
+        let yyyy = mul_mod(&yy, &yy, p);
+        let zz = mul_mod(&point.z, &point.z, p);
+
+        // S = 4XY^2, M = 3X^2 + aZ^4
+        let s = mul_mod(&BigUint::from(4u32), &mul_mod(&point.x, &yy, p), p);
+        let m = add_mod(
+            &mul_mod(&BigUint::from(3u32), &xx, p),
+            &mul_mod(&self.a, &mul_mod(&zz, &zz, p), p),
+            p,
+        );
+
+        let x = sub_mod(&mul_mod(&m, &m, p), &add_mod(&s, &s, p), p);
+        let y = sub_mod(
+            &mul_mod(&m, &sub_mod(&s, &x, p), p),
+            &mul_mod(&BigUint::from(8u32), &yyyy, p),
+            p,
+        );
+        let z = mul_mod(&BigUint::from(2u32), &mul_mod(&point.y, &point.z, p), p);
+        JacobianPoint { x, y, z }
+    }
+
+    // Mixed addition of an affine point, which saves a handful of multiplications over adding
+    // two Jacobian points.
+    pub fn jacobian_add_affine(&self, first: &JacobianPoint, second: &Point) -> JacobianPoint {
+        let p = &self.p;
+        let (x2, y2) = match second {
+            Point::Infinity => return first.clone(),
+            Point::Affine { x, y } => (x, y),
+        };
+        if first.is_infinity() {
+            return JacobianPoint::from_affine(second);
+        }
+
+        let zz = mul_mod(&first.z, &first.z, p);
+        let u2 = mul_mod(x2, &zz, p);
+        let s2 = mul_mod(y2, &mul_mod(&first.z, &zz, p), p);
+        let h = sub_mod(&u2, &first.x, p);
+        let r = sub_mod(&s2, &first.y, p);
+
+        if h.is_zero() {
+            return if r.is_zero() {
+                self.jacobian_double(first)
+            } else {
+                JacobianPoint::infinity()
+            };
+        }
+
+        let hh = mul_mod(&h, &h, p);
+        let hhh = mul_mod(&h, &hh, p);
+        let v = mul_mod(&first.x, &hh, p);
+
+        let x = sub_mod(
+            &sub_mod(&mul_mod(&r, &r, p), &hhh, p),
+            &add_mod(&v, &v, p),
+            p,
+        );
+        let y = sub_mod(
+            &mul_mod(&r, &sub_mod(&v, &x, p), p),
+            &mul_mod(&first.y, &hhh, p),
+            p,
+        );
+        let z = mul_mod(&first.z, &h, p);
+        JacobianPoint { x, y, z }
+    }
+
+    // Double-and-add, most significant bit first.
+    pub fn scalar_multiply(&self, point: &Point, scalar: &BigUint) -> Point {
+        let mut result = JacobianPoint::infinity();
+        for index in (0..scalar.bits()).rev() {
+            result = self.jacobian_double(&result);
+            if scalar.bit(index) {
+                result = self.jacobian_add_affine(&result, point);
+            }
+        }
+
+        self.to_affine(&result)
+    }
+
+    // SEC 1 uncompressed encoding: 04 || x || y, or a single zero byte for infinity.
+    pub fn encode(&self, point: &Point) -> Vec<u8> {
+        let length = self.p.bits().div_ceil(8);
+        match point {
+            Point::Infinity => vec![0],
+            Point::Affine { x, y } => {
+                let mut bytes = vec![4];
+                bytes.extend(x.to_bytes_be_padded(length));
+                bytes.extend(y.to_bytes_be_padded(length));
+                bytes
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EcKeypair {
+    pub private_key: BigUint,
+    pub public_key: Point,
+}
+
+// A curve together with a base point of prime order, for ECDH (and later ECDSA).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeierstrassGroup {
+    pub curve: WeierstrassCurve,
+    pub generator: Point,
+    pub order: BigUint,
+    pub cofactor: BigUint,
+}
+
+impl WeierstrassGroup {
+    pub fn from_spec(spec: &WeierstrassSpec) -> Self {
+        let p = BigUint::from_decimal(spec.p);
+        let curve = WeierstrassCurve::new(
+            p.clone(),
+            parse_field_element(spec.a, &p),
+            parse_field_element(spec.b, &p),
+        );
+        let generator = Point::new(
+            parse_field_element(spec.gx, &p),
+            parse_field_element(spec.gy, &p),
+        );
+
+        WeierstrassGroup {
+            curve,
+            generator,
+            order: BigUint::from_decimal(spec.order),
+            cofactor: BigUint::from_decimal(spec.cofactor),
+        }
+    }
+
+    pub fn generate_keypair(&self, rng: &mut Rng) -> EcKeypair {
+        self.keypair_from_private(BigUint::random_range(rng, &BigUint::one(), &self.order))
+    }
+
+    pub fn keypair_from_private(&self, private_key: BigUint) -> EcKeypair {
+        let public_key = self.curve.scalar_multiply(&self.generator, &private_key);
+        EcKeypair {
+            private_key,
+            public_key,
+        }
+    }
+
+    // No check that the other key is on the curve, let alone in the subgroup.
+    pub fn shared_secret(&self, keypair: &EcKeypair, other_public_key: &Point) -> Point {
+        self.curve
+            .scalar_multiply(other_public_key, &keypair.private_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::curves;
+
+    #[test]
+    fn test_generator_has_stated_order() {
+        let group = WeierstrassGroup::from_spec(&curves::CHALLENGE_59);
+
+        assert!(group.curve.contains(&group.generator));
+        assert_eq!(
+            group.curve.scalar_multiply(&group.generator, &group.order),
+            Point::Infinity
+        );
+    }
+
+    #[test]
+    fn test_scalar_multiply_matches_repeated_addition() {
+        let group = WeierstrassGroup::from_spec(&curves::CHALLENGE_59);
+        let curve = &group.curve;
+
+        let mut sum = Point::Infinity;
+        for k in 0..40u32 {
+            assert_eq!(
+                curve.scalar_multiply(&group.generator, &BigUint::from(k)),
+                sum
+            );
+            assert!(curve.contains(&sum));
+            sum = curve.add(&sum, &group.generator);
+        }
+
+        let minus_one = &group.order - &BigUint::one();
+        assert_eq!(
+            curve.scalar_multiply(&group.generator, &minus_one),
+            curve.negate(&group.generator)
+        );
+    }
+
+    #[test]
+    fn test_ecdh() {
+        let mut rng = Rng::new(59);
+        let group = WeierstrassGroup::from_spec(&curves::CHALLENGE_59);
+        let alice = group.generate_keypair(&mut rng);
+        let bob = group.generate_keypair(&mut rng);
+
+        assert!(group.curve.contains(&alice.public_key));
+        assert_eq!(
+            group.shared_secret(&alice, &bob.public_key),
+            group.shared_secret(&bob, &alice.public_key)
+        );
+    }
+}
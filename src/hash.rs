@@ -0,0 +1,22 @@
+pub mod md4;
+pub mod sha1;
+pub mod sha256;
+pub mod weak_md;
+
+pub use self::md4::md4;
+pub use self::sha1::sha1;
+pub use self::sha256::sha256;
+
+// Merkle-Damgard strengthening: a single 1 bit, zeros up to 56 mod 64, then the message
+// length in bits as a big-endian u64.
+pub fn md_padding(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+
+    padded.extend_from_slice(&((bytes.len() as u64) * 8).to_be_bytes());
+    padded
+}
@@ -0,0 +1,180 @@
+// MD4 (RFC 1320), with the compression function broken out step by step so the collision
+// attack can inspect and steer the intermediate register values.
+
+pub const INITIAL_STATE: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+pub const STEPS: usize = 48;
+
+const ROUND_CONSTANTS: [u32; 3] = [0, 0x5a82_7999, 0x6ed9_eba1];
+
+const SHIFTS: [[u32; 4]; 3] = [[3, 7, 11, 19], [3, 5, 9, 13], [3, 9, 11, 15]];
+
+#[rustfmt::skip]
+const WORD_ORDER: [[usize; 16]; 3] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [0, 4, 8, 12, 1, 5, 9, 13, 2, 6, 10, 14, 3, 7, 11, 15],
+    [0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15],
+];
+
+pub fn md4(bytes: &[u8]) -> [u8; 16] {
+    let mut state = INITIAL_STATE;
+
+    for block in padding(bytes).chunks(64) {
+        state = compress(state, &block_to_words(block));
+    }
+
+    let mut digest = [0; 16];
+    for (chunk, word) in digest.chunks_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
+
+// Like the SHA family's padding, but with the bit length little-endian.
+pub fn padding(bytes: &[u8]) -> Vec<u8> {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+
+    while padded.len() % 64 != 56 {
+        padded.push(0);
+    }
+
+    padded.extend_from_slice(&((bytes.len() as u64) * 8).to_le_bytes());
+    padded
+}
+
+pub fn block_to_words(block: &[u8]) -> [u32; 16] {
+    let mut words = [0; 16];
+    for (word, chunk) in words.iter_mut().zip(block.chunks(4)) {
+        *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    words
+}
+
+pub fn words_to_block(words: &[u32; 16]) -> [u8; 64] {
+    let mut block = [0; 64];
+    for (chunk, word) in block.chunks_mut(4).zip(words.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    block
+}
+
+pub fn f(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (!x & z)
+}
+
+pub fn g(x: u32, y: u32, z: u32) -> u32 {
+    (x & y) | (x & z) | (y & z)
+}
+
+pub fn h(x: u32, y: u32, z: u32) -> u32 {
+    x ^ y ^ z
+}
+
+// The word index and rotation used by a step.
+pub fn step_word(step: usize) -> usize {
+    WORD_ORDER[step / 16][step % 16]
+}
+
+pub fn step_shift(step: usize) -> u32 {
+    SHIFTS[step / 16][step % 4]
+}
+
+// The sum that gets rotated into the new register value, before the message word is added.
+// `previous` holds the four most recent register values, oldest first.
+fn step_base(step: usize, previous: [u32; 4]) -> u32 {
+    let [oldest, third, second, last] = previous;
+    let function = match step / 16 {
+        0 => f(last, second, third),
+        1 => g(last, second, third),
+        _ => h(last, second, third),
+    };
+
+    oldest
+        .wrapping_add(function)
+        .wrapping_add(ROUND_CONSTANTS[step / 16])
+}
+
+// Computes one step: the new register value from the previous four and the message word.
+pub fn step(step: usize, previous: [u32; 4], word: u32) -> u32 {
+    step_base(step, previous)
+        .wrapping_add(word)
+        .rotate_left(step_shift(step))
+}
+
+// The inverse of `step`: the message word that makes the step produce `value`.
+pub fn step_word_for(step: usize, previous: [u32; 4], value: u32) -> u32 {
+    value
+        .rotate_right(step_shift(step))
+        .wrapping_sub(step_base(step, previous))
+}
+
+// Every register value written during compression, preceded by the chaining state in the
+// order the steps consume it: a0, d0, c0, b0, a1, d1, c1, b1, a2, ...
+pub fn register_values(state: [u32; 4], words: &[u32; 16]) -> [u32; STEPS + 4] {
+    let [a, b, c, d] = state;
+    let mut values = [0; STEPS + 4];
+    values[..4].copy_from_slice(&[a, d, c, b]);
+
+    for index in 0..STEPS {
+        let previous = [
+            values[index],
+            values[index + 1],
+            values[index + 2],
+            values[index + 3],
+        ];
+        values[index + 4] = step(index, previous, words[step_word(index)]);
+    }
+
+    values
+}
+
+pub fn compress(state: [u32; 4], words: &[u32; 16]) -> [u32; 4] {
+    let values = register_values(state, words);
+    let [a, d, c, b] = [
+        values[STEPS],
+        values[STEPS + 1],
+        values[STEPS + 2],
+        values[STEPS + 3],
+    ];
+
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::hex::bytes_to_hex_string;
+
+    // RFC 1320 A.5
+    #[test]
+    fn test_md4_vectors() {
+        let vectors: [(&[u8], &str); 4] = [
+            (b"", "31d6cfe0d16ae931b73c59d7e0c089c0"),
+            (b"abc", "a448017aaf21d8525fc10ae87aa6729d"),
+            (b"message digest", "d9130a8164549fe818874806e1c7014b"),
+            (
+                b"12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "e33b4ddc9c38f2199c3e7b164fcc0536",
+            ),
+        ];
+
+        for (input, expected) in vectors {
+            assert_eq!(bytes_to_hex_string(&md4(input)), expected);
+        }
+    }
+
+    #[test]
+    fn test_step_word_for_inverts_step() {
+        let previous = [1, 2, 3, 4];
+        for index in [0, 17, 40] {
+            let value = step(index, previous, 0xdead_beef);
+            assert_eq!(step_word_for(index, previous, value), 0xdead_beef);
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use crate::hash::md_padding;
+
+const INITIAL_STATE: [u32; 5] = [
+    0x6745_2301,
+    0xefcd_ab89,
+    0x98ba_dcfe,
+    0x1032_5476,
+    0xc3d2_e1f0,
+];
+
+pub fn sha1(bytes: &[u8]) -> [u8; 20] {
+    let mut state = INITIAL_STATE;
+
+    for block in md_padding(bytes).chunks(64) {
+        process_block(&mut state, block);
+    }
+
+    let mut digest = [0; 20];
+    for (chunk, word) in digest.chunks_mut(4).zip(state.iter()) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+fn process_block(state: &mut [u32; 5], block: &[u8]) {
+    let mut w = [0u32; 80];
+
+    for (index, chunk) in block.chunks(4).enumerate() {
+        w[index] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    for index in 16..80 {
+        w[index] = (w[index - 3] ^ w[index - 8] ^ w[index - 14] ^ w[index - 16]).rotate_left(1);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e] = *state;
+
+    for (index, word) in w.iter().enumerate() {
+        let (f, k) = match index {
+            0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+            20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+            40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+            _ => (b ^ c ^ d, 0xca62_c1d6),
+        };
+
+        let temp = a
+            .rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(k)
+            .wrapping_add(*word);
+
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    state[0] = state[0].wrapping_add(a);
+    state[1] = state[1].wrapping_add(b);
+    state[2] = state[2].wrapping_add(c);
+    state[3] = state[3].wrapping_add(d);
+    state[4] = state[4].wrapping_add(e);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::hex::bytes_to_hex_string;
+
+    #[test]
+    fn test_sha1_empty() {
+        assert_eq!(
+            bytes_to_hex_string(&sha1(b"")),
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+    }
+
+    #[test]
+    fn test_sha1_abc() {
+        assert_eq!(
+            bytes_to_hex_string(&sha1(b"abc")),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+    }
+
+    #[test]
+    fn test_sha1_two_blocks() {
+        let message = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+        assert_eq!(
+            bytes_to_hex_string(&sha1(message)),
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use crate::ciphers::aes::{Aes, BLOCK_SIZE};
+
+// A deliberately weak Merkle-Damgard hash for the Set 7 hash attacks. The state is only a
+// couple of bytes, and the compression function encrypts the message block with AES under
+// the zero-padded state as key, keeping the first state-sized chunk of the output.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WeakMdHash {
+    state_bytes: usize,
+    initial_state: u32,
+}
+
+impl WeakMdHash {
+    pub fn new(state_bytes: usize, initial_state: u32) -> Self {
+        if !(1..=4).contains(&state_bytes) {
+            panic!("Invalid state size: {}", state_bytes);
+        }
+
+        WeakMdHash {
+            state_bytes,
+            initial_state: initial_state & mask(state_bytes),
+        }
+    }
+
+    pub fn state_bytes(&self) -> usize {
+        self.state_bytes
+    }
+
+    pub fn initial_state(&self) -> u32 {
+        self.initial_state
+    }
+
+    pub fn compress(&self, state: u32, block: &[u8]) -> u32 {
+        let mut key = [0; BLOCK_SIZE];
+        key[..self.state_bytes].copy_from_slice(&state.to_be_bytes()[4 - self.state_bytes..]);
+
+        let output = Aes::new(&key).encrypt(block);
+        let mut truncated = [0; 4];
+        truncated[4 - self.state_bytes..].copy_from_slice(&output[..self.state_bytes]);
+        u32::from_be_bytes(truncated)
+    }
+
+    // Runs the compression function over whole blocks, with no padding.
+    pub fn compress_blocks(&self, state: u32, blocks: &[u8]) -> u32 {
+        if !blocks.len().is_multiple_of(BLOCK_SIZE) {
+            panic!("Input must be a multiple of the block size");
+        }
+
+        blocks
+            .chunks(BLOCK_SIZE)
+            .fold(state, |state, block| self.compress(state, block))
+    }
+
+    pub fn hash(&self, message: &[u8]) -> u32 {
+        self.compress_blocks(self.initial_state, &padding(message))
+    }
+}
+
+// Merkle-Damgard strengthening for 16-byte blocks: a single 1 bit, zeros up to 8 mod 16, then
+// the message length in bits as a big-endian u64.
+pub fn padding(message: &[u8]) -> Vec<u8> {
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+
+    while padded.len() % BLOCK_SIZE != 8 {
+        padded.push(0);
+    }
+
+    padded.extend_from_slice(&((message.len() as u64) * 8).to_be_bytes());
+    padded
+}
+
+fn mask(state_bytes: usize) -> u32 {
+    (u64::MAX >> (64 - 8 * state_bytes)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_stays_in_range() {
+        let hash = WeakMdHash::new(2, 0xbeef);
+
+        for length in 0..40 {
+            assert!(hash.hash(&vec![b'a'; length]) <= 0xffff);
+        }
+    }
+
+    #[test]
+    fn test_hash_is_padded_chain() {
+        let hash = WeakMdHash::new(3, 0x123456);
+        let message = b"YELLOW SUBMARINE and then some";
+
+        assert_eq!(padding(message).len(), 48);
+        assert_eq!(
+            hash.hash(message),
+            hash.compress_blocks(hash.initial_state(), &padding(message))
+        );
+        assert_ne!(hash.hash(message), hash.hash(&message[1..]));
+    }
+}
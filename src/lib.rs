@@ -0,0 +1,27 @@
+pub mod attacks;
+pub mod byte_operations;
+pub mod ciphers;
+pub mod compression;
+pub mod conversion;
+pub mod decode;
+pub mod ec;
+pub mod hash;
+pub mod mac;
+pub mod math;
+pub mod oracles;
+pub mod padding;
+pub mod protocols;
+pub mod pubkey;
+pub mod random;
+pub mod utils;
+
+#[cfg(test)]
+pub mod set1;
+#[cfg(test)]
+pub mod set5;
+#[cfg(test)]
+pub mod set6;
+#[cfg(test)]
+pub mod set7;
+#[cfg(test)]
+pub mod set8;
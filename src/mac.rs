@@ -0,0 +1,5 @@
+pub mod cbc_mac;
+pub mod hmac;
+
+pub use self::cbc_mac::cbc_mac;
+pub use self::hmac::{hmac_sha1, hmac_sha256};
@@ -0,0 +1,50 @@
+use crate::ciphers::aes::{to_block, BLOCK_SIZE};
+use crate::ciphers::cbc::{cbc_encrypt, cbc_encrypt_blocks};
+
+// The last block of AES-CBC over the PKCS#7 padded message.
+pub fn cbc_mac(key: &[u8], iv: &[u8], message: &[u8]) -> [u8; 16] {
+    last_block(&cbc_encrypt(key, iv, message))
+}
+
+// The CBC chaining state after a message that is already a whole number of blocks, with no
+// padding applied. Useful for picking up the chain part way through a message.
+pub fn cbc_mac_blocks(key: &[u8], iv: &[u8], message: &[u8]) -> [u8; 16] {
+    if message.is_empty() {
+        return to_block(iv);
+    }
+
+    last_block(&cbc_encrypt_blocks(key, iv, message))
+}
+
+fn last_block(ciphertext: &[u8]) -> [u8; 16] {
+    to_block(&ciphertext[ciphertext.len() - BLOCK_SIZE..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::hex::bytes_to_hex_string;
+
+    // The hash Challenge 50 gives for its snippet
+    #[test]
+    fn test_cbc_mac_known_answer() {
+        let mac = cbc_mac(
+            b"YELLOW SUBMARINE",
+            &[0; 16],
+            b"alert('MZA who was that?');\n",
+        );
+        assert_eq!(
+            bytes_to_hex_string(&mac),
+            "296b8d7cb78a243dda4d0a61d33bbdd1"
+        );
+    }
+
+    #[test]
+    fn test_padded_mac_extends_block_chain() {
+        let key = b"YELLOW SUBMARINE";
+        let message = b"exactly sixteen!";
+
+        let state = cbc_mac_blocks(key, &[0; 16], message);
+        assert_eq!(cbc_mac(key, &[0; 16], message), cbc_mac(key, &state, &[]));
+    }
+}
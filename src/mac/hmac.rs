@@ -0,0 +1,66 @@
+use crate::hash::{sha1, sha256};
+
+const BLOCK_SIZE: usize = 64;
+
+// RFC 2104 HMAC over any hash with a 64-byte block.
+pub fn hmac<const N: usize>(hash: fn(&[u8]) -> [u8; N], key: &[u8], message: &[u8]) -> [u8; N] {
+    let mut block_key = if key.len() > BLOCK_SIZE {
+        hash(key).to_vec()
+    } else {
+        key.to_vec()
+    };
+    block_key.resize(BLOCK_SIZE, 0);
+
+    let inner_key: Vec<u8> = block_key.iter().map(|byte| byte ^ 0x36).collect();
+    let outer_key: Vec<u8> = block_key.iter().map(|byte| byte ^ 0x5c).collect();
+
+    let inner_hash = hash(&[inner_key.as_slice(), message].concat());
+    hash(&[outer_key.as_slice(), &inner_hash].concat())
+}
+
+pub fn hmac_sha1(key: &[u8], message: &[u8]) -> [u8; 20] {
+    hmac(sha1, key, message)
+}
+
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    hmac(sha256, key, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::hex::bytes_to_hex_string;
+
+    // RFC 4231 test case 2
+    #[test]
+    fn test_hmac_sha256() {
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            bytes_to_hex_string(&mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    // RFC 2202 test case 2
+    #[test]
+    fn test_hmac_sha1() {
+        let mac = hmac_sha1(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(
+            bytes_to_hex_string(&mac),
+            "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79"
+        );
+    }
+
+    #[test]
+    fn test_hmac_long_key() {
+        let key = [0xaa; 131];
+        let mac = hmac_sha256(
+            &key,
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+        );
+        assert_eq!(
+            bytes_to_hex_string(&mac),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+}
@@ -1,13 +1,3 @@
-pub mod byte_operations;
-pub mod ciphers;
-pub mod conversion;
-pub mod decode;
-pub mod math;
-pub mod utils;
-
-#[cfg(test)]
-pub mod set1;
-
 fn main() {
     println!("Use 'cargo test' to verify challenge completion");
 }
@@ -1 +1,9 @@
+pub mod bigint;
+pub mod gf2;
+pub mod gf128;
+pub mod gf128_polynomial;
+pub mod lattice;
 pub mod metrics;
+pub mod number_theory;
+pub mod rational;
+pub mod signed;
@@ -0,0 +1,1064 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Rem, Shl, Shr, Sub};
+
+use crate::conversion::hex::{bytes_to_hex_string, hex_string_to_bytes};
+use crate::random::Rng;
+
+// Little-endian base 2^32 digits with no trailing zero digits, so zero is an empty vector.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BigUint {
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> Self {
+        BigUint { digits: Vec::new() }
+    }
+
+    pub fn one() -> Self {
+        BigUint { digits: vec![1] }
+    }
+
+    fn from_digits(mut digits: Vec<u32>) -> Self {
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        BigUint { digits }
+    }
+
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let digits = bytes
+            .rchunks(4)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0u32, |digit, byte| (digit << 8) | u32::from(*byte))
+            })
+            .collect();
+
+        BigUint::from_digits(digits)
+    }
+
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let bytes: Vec<u8> = self
+            .digits
+            .iter()
+            .rev()
+            .flat_map(|digit| digit.to_be_bytes().to_vec())
+            .collect();
+
+        let leading_zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+        bytes[leading_zeros..].to_vec()
+    }
+
+    // Big-endian bytes left-padded with zeros to exactly `length` bytes.
+    pub fn to_bytes_be_padded(&self, length: usize) -> Vec<u8> {
+        let bytes = self.to_bytes_be();
+        if bytes.len() > length {
+            panic!("Value does not fit in {} bytes", length);
+        }
+
+        let mut result = vec![0; length - bytes.len()];
+        result.extend(bytes);
+        result
+    }
+
+    pub fn from_hex(hex_string: &str) -> Self {
+        BigUint::from_bytes_be(&hex_string_to_bytes(hex_string))
+    }
+
+    pub fn to_hex(&self) -> String {
+        let hex = bytes_to_hex_string(&self.to_bytes_be());
+        let trimmed = hex.trim_start_matches('0');
+
+        if trimmed.is_empty() {
+            String::from("0")
+        } else {
+            trimmed.to_string()
+        }
+    }
+
+    pub fn from_decimal(decimal: &str) -> Self {
+        let mut result = BigUint::zero();
+
+        for c in decimal.bytes() {
+            if !c.is_ascii_digit() {
+                panic!("Invalid decimal character: {}", c);
+            }
+            result = mul_small(&result, 10) + BigUint::from(c - b'0');
+        }
+
+        result
+    }
+
+    pub fn to_decimal(&self) -> String {
+        if self.is_zero() {
+            return String::from("0");
+        }
+
+        let mut chunks = Vec::new();
+        let mut value = self.clone();
+
+        while !value.is_zero() {
+            let (quotient, remainder) = divrem_small(&value, 1_000_000_000);
+            chunks.push(remainder);
+            value = quotient;
+        }
+
+        let mut result = chunks.pop().unwrap_or_default().to_string();
+        for chunk in chunks.iter().rev() {
+            result.push_str(&format!("{:09}", chunk));
+        }
+        result
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.digits.len() {
+            0 => Some(0),
+            1 => Some(u64::from(self.digits[0])),
+            2 => Some(u64::from(self.digits[0]) | u64::from(self.digits[1]) << 32),
+            _ => None,
+        }
+    }
+
+    pub fn to_u128(&self) -> Option<u128> {
+        if self.digits.len() > 4 {
+            return None;
+        }
+
+        Some(
+            self.digits
+                .iter()
+                .rev()
+                .fold(0u128, |value, digit| (value << 32) | u128::from(*digit)),
+        )
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.digits == [1]
+    }
+
+    pub fn is_even(&self) -> bool {
+        self.digits.first().is_none_or(|digit| digit & 1 == 0)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        !self.is_even()
+    }
+
+    // Number of significant bits; zero has none.
+    pub fn bits(&self) -> usize {
+        match self.digits.last() {
+            Some(top) => self.digits.len() * 32 - top.leading_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    pub fn bit(&self, index: usize) -> bool {
+        self.digits
+            .get(index / 32)
+            .is_some_and(|digit| (digit >> (index % 32)) & 1 == 1)
+    }
+
+    pub fn set_bit(&mut self, index: usize) {
+        let digit_index = index / 32;
+        if self.digits.len() <= digit_index {
+            self.digits.resize(digit_index + 1, 0);
+        }
+        self.digits[digit_index] |= 1 << (index % 32);
+    }
+
+    pub fn trailing_zeros(&self) -> usize {
+        match self.digits.iter().position(|digit| *digit != 0) {
+            Some(index) => index * 32 + self.digits[index].trailing_zeros() as usize,
+            None => 0,
+        }
+    }
+
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if self < other {
+            None
+        } else {
+            Some(BigUint::from_digits(sub_digits(
+                &self.digits,
+                &other.digits,
+            )))
+        }
+    }
+
+    pub fn divrem(&self, divisor: &BigUint) -> (BigUint, BigUint) {
+        if divisor.is_zero() {
+            panic!("Division by zero");
+        }
+
+        if self < divisor {
+            return (BigUint::zero(), self.clone());
+        }
+
+        if divisor.digits.len() == 1 {
+            let (quotient, remainder) = divrem_small(self, divisor.digits[0]);
+            return (quotient, BigUint::from(remainder));
+        }
+
+        let (quotient, remainder) = divrem_digits(&self.digits, &divisor.digits);
+        (
+            BigUint::from_digits(quotient),
+            BigUint::from_digits(remainder),
+        )
+    }
+
+    // The remainder after dividing by a small divisor, without allocating a quotient.
+    pub fn rem_u32(&self, divisor: u32) -> u32 {
+        if divisor == 0 {
+            panic!("Division by zero");
+        }
+
+        self.digits.iter().rev().fold(0u64, |remainder, digit| {
+            ((remainder << 32) | u64::from(*digit)) % u64::from(divisor)
+        }) as u32
+    }
+
+    pub fn pow(&self, exponent: u32) -> BigUint {
+        let mut result = BigUint::one();
+        let mut base = self.clone();
+        let mut exponent = exponent;
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = &result * &base;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = &base * &base;
+            }
+        }
+
+        result
+    }
+
+    // Floor of the nth root, by Newton's method from an initial guess above the root.
+    pub fn nth_root(&self, n: u32) -> BigUint {
+        if n == 0 {
+            panic!("Zeroth root is undefined");
+        }
+
+        if self.is_zero() || n == 1 {
+            return self.clone();
+        }
+
+        let n_big = BigUint::from(n);
+        let n_minus_one = BigUint::from(n - 1);
+        let mut x = BigUint::one() << self.bits().div_ceil(n as usize);
+
+        loop {
+            let next = &(&(&n_minus_one * &x) + &(self / &x.pow(n - 1))) / &n_big;
+            if next >= x {
+                return x;
+            }
+            x = next;
+        }
+    }
+
+    pub fn modpow(&self, exponent: &BigUint, modulus: &BigUint) -> BigUint {
+        if modulus.is_zero() {
+            panic!("Modulus must be non-zero");
+        }
+
+        if modulus.is_one() {
+            return BigUint::zero();
+        }
+
+        if modulus.is_odd() {
+            return Montgomery::new(modulus).modpow(self, exponent);
+        }
+
+        let base = self % modulus;
+        let mut result = BigUint::one();
+
+        for index in (0..exponent.bits()).rev() {
+            result = &(&result * &result) % modulus;
+            if exponent.bit(index) {
+                result = &(&result * &base) % modulus;
+            }
+        }
+
+        result
+    }
+
+    // Extended Euclid with the Bezout coefficient tracked modulo `modulus`, which avoids
+    // needing signed arithmetic: every remainder r satisfies r = t * self (mod modulus).
+    pub fn modinv(&self, modulus: &BigUint) -> Option<BigUint> {
+        if modulus.is_zero() {
+            panic!("Modulus must be non-zero");
+        }
+
+        let mut old_r = modulus.clone();
+        let mut r = self % modulus;
+        let mut old_t = BigUint::zero();
+        let mut t = BigUint::one();
+
+        while !r.is_zero() {
+            let (quotient, next_r) = old_r.divrem(&r);
+            let product = &(&quotient * &t) % modulus;
+            let next_t = &(&old_t + modulus) - &product;
+            let next_t = &next_t % modulus;
+
+            old_r = r;
+            r = next_r;
+            old_t = t;
+            t = next_t;
+        }
+
+        if old_r.is_one() {
+            Some(&old_t % modulus)
+        } else {
+            None
+        }
+    }
+
+    pub fn gcd(&self, other: &BigUint) -> BigUint {
+        let mut a = self.clone();
+        let mut b = other.clone();
+
+        while !b.is_zero() {
+            let r = &a % &b;
+            a = b;
+            b = r;
+        }
+
+        a
+    }
+
+    // Uniform random value with at most `bits` bits.
+    pub fn random_bits(rng: &mut Rng, bits: usize) -> BigUint {
+        let digit_count = bits.div_ceil(32);
+        let mut digits: Vec<u32> = (0..digit_count).map(|_| rng.next_u32()).collect();
+
+        let excess = digit_count * 32 - bits;
+        if excess > 0 {
+            if let Some(top) = digits.last_mut() {
+                *top >>= excess;
+            }
+        }
+
+        BigUint::from_digits(digits)
+    }
+
+    // Uniform random value in 0..bound.
+    pub fn random_below(rng: &mut Rng, bound: &BigUint) -> BigUint {
+        if bound.is_zero() {
+            panic!("Bound must be greater than zero");
+        }
+
+        let bits = bound.bits();
+        loop {
+            let candidate = BigUint::random_bits(rng, bits);
+            if &candidate < bound {
+                return candidate;
+            }
+        }
+    }
+
+    // Uniform random value in low..high.
+    pub fn random_range(rng: &mut Rng, low: &BigUint, high: &BigUint) -> BigUint {
+        low + &BigUint::random_below(rng, &(high - low))
+    }
+}
+
+fn add_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+
+    for (index, digit) in long.iter().enumerate() {
+        let sum = u64::from(*digit) + u64::from(*short.get(index).unwrap_or(&0)) + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+
+    if carry > 0 {
+        result.push(carry as u32);
+    }
+
+    result
+}
+
+// Requires a >= b.
+fn sub_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+
+    for (index, digit) in a.iter().enumerate() {
+        let mut difference = i64::from(*digit) - i64::from(*b.get(index).unwrap_or(&0)) - borrow;
+        if difference < 0 {
+            difference += 1 << 32;
+            borrow = 1;
+        } else {
+            borrow = 0;
+        }
+        result.push(difference as u32);
+    }
+
+    if borrow != 0 {
+        panic!("Subtraction underflow");
+    }
+
+    result
+}
+
+fn mul_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    let mut result = vec![0u32; a.len() + b.len()];
+
+    for (i, a_digit) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, b_digit) in b.iter().enumerate() {
+            let product =
+                u64::from(*a_digit) * u64::from(*b_digit) + u64::from(result[i + j]) + carry;
+            result[i + j] = product as u32;
+            carry = product >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+
+    result
+}
+
+fn mul_small(a: &BigUint, factor: u32) -> BigUint {
+    mul_digits_small(&a.digits, factor)
+}
+
+fn mul_digits_small(a: &[u32], factor: u32) -> BigUint {
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0u64;
+
+    for digit in a {
+        let product = u64::from(*digit) * u64::from(factor) + carry;
+        result.push(product as u32);
+        carry = product >> 32;
+    }
+    result.push(carry as u32);
+
+    BigUint::from_digits(result)
+}
+
+fn divrem_small(a: &BigUint, divisor: u32) -> (BigUint, u32) {
+    let mut quotient = vec![0u32; a.digits.len()];
+    let mut remainder = 0u64;
+
+    for (index, digit) in a.digits.iter().enumerate().rev() {
+        let current = (remainder << 32) | u64::from(*digit);
+        quotient[index] = (current / u64::from(divisor)) as u32;
+        remainder = current % u64::from(divisor);
+    }
+
+    (BigUint::from_digits(quotient), remainder as u32)
+}
+
+fn shl_digits(digits: &[u32], shift: usize) -> Vec<u32> {
+    if digits.is_empty() {
+        return Vec::new();
+    }
+
+    let digit_shift = shift / 32;
+    let bit_shift = shift % 32;
+
+    let mut result = vec![0u32; digit_shift];
+    if bit_shift == 0 {
+        result.extend_from_slice(digits);
+    } else {
+        let mut carry = 0u32;
+        for digit in digits {
+            result.push((digit << bit_shift) | carry);
+            carry = digit >> (32 - bit_shift);
+        }
+        result.push(carry);
+    }
+
+    result
+}
+
+fn shr_digits(digits: &[u32], shift: usize) -> Vec<u32> {
+    let digit_shift = shift / 32;
+    let bit_shift = shift % 32;
+
+    if digit_shift >= digits.len() {
+        return Vec::new();
+    }
+
+    let digits = &digits[digit_shift..];
+    if bit_shift == 0 {
+        return digits.to_vec();
+    }
+
+    (0..digits.len())
+        .map(|index| {
+            let high = digits
+                .get(index + 1)
+                .map_or(0, |digit| digit << (32 - bit_shift));
+            (digits[index] >> bit_shift) | high
+        })
+        .collect()
+}
+
+// Knuth's Algorithm D (TAOCP vol. 2, 4.3.1). Requires v to have at least two digits with a
+// non-zero top digit, and u >= v.
+fn divrem_digits(u: &[u32], v: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    const BASE: u64 = 1 << 32;
+
+    let shift = v[v.len() - 1].leading_zeros() as usize;
+    let v = shl_digits(v, shift);
+    let v = &v[..v.len() - if shift == 0 { 0 } else { 1 }];
+    let mut u = shl_digits(u, shift);
+    if shift == 0 {
+        u.push(0);
+    }
+
+    let n = v.len();
+    let m = u.len() - n - 1;
+    let mut quotient = vec![0u32; m + 1];
+
+    let v_top = u64::from(v[n - 1]);
+    let v_next = u64::from(v[n - 2]);
+
+    for j in (0..=m).rev() {
+        let numerator = (u64::from(u[j + n]) << 32) | u64::from(u[j + n - 1]);
+        let mut q_hat = numerator / v_top;
+        let mut r_hat = numerator % v_top;
+
+        while q_hat >= BASE || q_hat * v_next > ((r_hat << 32) | u64::from(u[j + n - 2])) {
+            q_hat -= 1;
+            r_hat += v_top;
+            if r_hat >= BASE {
+                break;
+            }
+        }
+
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let product = q_hat * u64::from(v[i]) + carry;
+            carry = product >> 32;
+            let difference = i64::from(u[i + j]) - borrow - (product & 0xffff_ffff) as i64;
+            u[i + j] = difference as u32;
+            borrow = if difference < 0 { 1 } else { 0 };
+        }
+        let difference = i64::from(u[j + n]) - borrow - carry as i64;
+        u[j + n] = difference as u32;
+
+        if difference < 0 {
+            q_hat -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = u64::from(u[i + j]) + u64::from(v[i]) + carry;
+                u[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            u[j + n] = u[j + n].wrapping_add(carry as u32);
+        }
+
+        quotient[j] = q_hat as u32;
+    }
+
+    let remainder = shr_digits(&u[..n], shift);
+    (quotient, remainder)
+}
+
+// Montgomery arithmetic modulo an odd n with R = 2^(32 * digits), so that reduction needs
+// no division. Used to speed up modpow, which dominates every public-key attack.
+struct Montgomery {
+    modulus: Vec<u32>,
+    // -n^-1 mod 2^32
+    n_prime: u32,
+}
+
+impl Montgomery {
+    fn new(modulus: &BigUint) -> Self {
+        let n0 = modulus.digits[0];
+        let mut inverse = 1u32;
+        for _ in 0..5 {
+            inverse = inverse.wrapping_mul(2u32.wrapping_sub(n0.wrapping_mul(inverse)));
+        }
+
+        Montgomery {
+            modulus: modulus.digits.clone(),
+            n_prime: inverse.wrapping_neg(),
+        }
+    }
+
+    fn to_montgomery(&self, value: &BigUint) -> Vec<u32> {
+        let modulus = BigUint {
+            digits: self.modulus.clone(),
+        };
+        let shifted = &(value % &modulus) << (32 * self.modulus.len());
+        let mut digits = (&shifted % &modulus).digits;
+        digits.resize(self.modulus.len(), 0);
+        digits
+    }
+
+    // result = a * b / R mod n, by coarsely integrated operand scanning.
+    fn multiply(&self, a: &[u32], b: &[u32], scratch: &mut [u32], result: &mut [u32]) {
+        let n = &self.modulus;
+        let size = n.len();
+        let t = scratch;
+        t.iter_mut().for_each(|digit| *digit = 0);
+
+        for b_digit in b.iter().take(size) {
+            let b_digit = u64::from(*b_digit);
+
+            let mut carry = 0u64;
+            for j in 0..size {
+                let sum = u64::from(t[j]) + u64::from(a[j]) * b_digit + carry;
+                t[j] = sum as u32;
+                carry = sum >> 32;
+            }
+            let sum = u64::from(t[size]) + carry;
+            t[size] = sum as u32;
+            t[size + 1] = (sum >> 32) as u32;
+
+            let m = u64::from(t[0].wrapping_mul(self.n_prime));
+            let mut carry = (u64::from(t[0]) + m * u64::from(n[0])) >> 32;
+            for j in 1..size {
+                let sum = u64::from(t[j]) + m * u64::from(n[j]) + carry;
+                t[j - 1] = sum as u32;
+                carry = sum >> 32;
+            }
+            let sum = u64::from(t[size]) + carry;
+            t[size - 1] = sum as u32;
+            t[size] = t[size + 1] + (sum >> 32) as u32;
+        }
+
+        let needs_subtraction =
+            t[size] != 0 || t[..size].iter().rev().cmp(n.iter().rev()) != Ordering::Less;
+        if needs_subtraction {
+            let mut borrow = 0i64;
+            for j in 0..size {
+                let difference = i64::from(t[j]) - i64::from(n[j]) - borrow;
+                result[j] = difference as u32;
+                borrow = if difference < 0 { 1 } else { 0 };
+            }
+        } else {
+            result.copy_from_slice(&t[..size]);
+        }
+    }
+
+    // Fixed 4-bit window exponentiation.
+    fn modpow(&self, base: &BigUint, exponent: &BigUint) -> BigUint {
+        const WINDOW: usize = 4;
+
+        let size = self.modulus.len();
+        let mut scratch = vec![0u32; size + 2];
+        let mut temp = vec![0u32; size];
+
+        let mut table = vec![
+            self.to_montgomery(&BigUint::one()),
+            self.to_montgomery(base),
+        ];
+        for index in 2..(1 << WINDOW) {
+            let mut next = vec![0u32; size];
+            self.multiply(&table[index - 1], &table[1], &mut scratch, &mut next);
+            table.push(next);
+        }
+
+        let mut result = table[0].clone();
+        let windows = exponent.bits().div_ceil(WINDOW);
+
+        for window in (0..windows).rev() {
+            for _ in 0..WINDOW {
+                self.multiply(&result, &result, &mut scratch, &mut temp);
+                std::mem::swap(&mut result, &mut temp);
+            }
+
+            let index = (0..WINDOW).fold(0, |index, bit| {
+                index | (exponent.bit(window * WINDOW + bit) as usize) << bit
+            });
+            if index != 0 {
+                self.multiply(&result, &table[index], &mut scratch, &mut temp);
+                std::mem::swap(&mut result, &mut temp);
+            }
+        }
+
+        // Multiplying by 1 converts back out of Montgomery form
+        let mut one = vec![0u32; size];
+        one[0] = 1;
+        self.multiply(&result, &one, &mut scratch, &mut temp);
+
+        BigUint::from_digits(temp)
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.digits
+            .len()
+            .cmp(&other.digits.len())
+            .then_with(|| self.digits.iter().rev().cmp(other.digits.iter().rev()))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_decimal())
+    }
+}
+
+impl fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BigUint(0x{})", self.to_hex())
+    }
+}
+
+impl fmt::LowerHex for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(value: $t) -> Self {
+                    let mut value = value as u128;
+                    let mut digits = Vec::new();
+                    while value > 0 {
+                        digits.push(value as u32);
+                        value >>= 32;
+                    }
+                    BigUint { digits }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $method:ident, $body:expr) => {
+        impl<'a, 'b> $trait<&'b BigUint> for &'a BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: &'b BigUint) -> BigUint {
+                let body: fn(&BigUint, &BigUint) -> BigUint = $body;
+                body(self, other)
+            }
+        }
+
+        impl<'a> $trait<&'a BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: &'a BigUint) -> BigUint {
+                (&self).$method(other)
+            }
+        }
+
+        impl<'a> $trait<BigUint> for &'a BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                self.$method(&other)
+            }
+        }
+
+        impl $trait<BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                (&self).$method(&other)
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, |a, b| BigUint::from_digits(add_digits(
+    &a.digits, &b.digits
+)));
+impl_binary_op!(Sub, sub, |a, b| BigUint::from_digits(sub_digits(
+    &a.digits, &b.digits
+)));
+impl_binary_op!(Mul, mul, |a, b| BigUint::from_digits(mul_digits(
+    &a.digits, &b.digits
+)));
+impl_binary_op!(Div, div, |a, b| a.divrem(b).0);
+impl_binary_op!(Rem, rem, |a, b| a.divrem(b).1);
+
+impl Shl<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        BigUint::from_digits(shl_digits(&self.digits, shift))
+    }
+}
+
+impl Shl<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shl(self, shift: usize) -> BigUint {
+        &self << shift
+    }
+}
+
+impl Shr<usize> for &BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        BigUint::from_digits(shr_digits(&self.digits, shift))
+    }
+}
+
+impl Shr<usize> for BigUint {
+    type Output = BigUint;
+
+    fn shr(self, shift: usize) -> BigUint {
+        &self >> shift
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(value: u128) -> BigUint {
+        BigUint::from(value)
+    }
+
+    fn random_u128(rng: &mut Rng, bits: u32) -> u128 {
+        let value = (u128::from(rng.next_u64()) << 64) | u128::from(rng.next_u64());
+        let width = rng.below(u64::from(bits)) as u32 + 1;
+        value >> (128 - width)
+    }
+
+    #[test]
+    fn test_random_add_sub_against_u128() {
+        let mut rng = Rng::new(26);
+
+        for _ in 0..2000 {
+            let a = random_u128(&mut rng, 127);
+            let b = random_u128(&mut rng, 127);
+
+            assert_eq!((big(a) + big(b)).to_u128(), Some(a + b));
+
+            let (high, low) = if a >= b { (a, b) } else { (b, a) };
+            assert_eq!((big(high) - big(low)).to_u128(), Some(high - low));
+            assert_eq!(big(low).checked_sub(&big(high)).is_none(), low < high);
+        }
+    }
+
+    #[test]
+    fn test_random_mul_against_u128() {
+        let mut rng = Rng::new(27);
+
+        for _ in 0..2000 {
+            let a = random_u128(&mut rng, 64);
+            let b = random_u128(&mut rng, 64);
+
+            assert_eq!((big(a) * big(b)).to_u128(), Some(a * b));
+        }
+    }
+
+    #[test]
+    fn test_random_divrem_against_u128() {
+        let mut rng = Rng::new(28);
+
+        for _ in 0..2000 {
+            let a = random_u128(&mut rng, 128);
+            let b = random_u128(&mut rng, 128).max(1);
+
+            let (quotient, remainder) = big(a).divrem(&big(b));
+            assert_eq!(quotient.to_u128(), Some(a / b));
+            assert_eq!(remainder.to_u128(), Some(a % b));
+
+            let small = (b as u32).max(1);
+            assert_eq!(u128::from(big(a).rem_u32(small)), a % u128::from(small));
+        }
+    }
+
+    #[test]
+    fn test_random_shifts_against_u128() {
+        let mut rng = Rng::new(29);
+
+        for _ in 0..2000 {
+            let a = random_u128(&mut rng, 64);
+            let shift = rng.below(64) as usize;
+
+            assert_eq!((&big(a) << shift).to_u128(), Some(a << shift));
+            assert_eq!((&big(a) >> shift).to_u128(), Some(a >> shift));
+        }
+    }
+
+    #[test]
+    fn test_random_comparison_against_u128() {
+        let mut rng = Rng::new(30);
+
+        for _ in 0..2000 {
+            let a = random_u128(&mut rng, 128);
+            let b = random_u128(&mut rng, 128);
+
+            assert_eq!(big(a).cmp(&big(b)), a.cmp(&b));
+        }
+    }
+
+    #[test]
+    fn test_random_modpow_against_u128() {
+        let mut rng = Rng::new(31);
+
+        for _ in 0..500 {
+            let base = random_u128(&mut rng, 64);
+            let exponent = random_u128(&mut rng, 16);
+            let modulus = random_u128(&mut rng, 64).max(1);
+
+            let mut expected = 1 % modulus;
+            let mut power = base % modulus;
+            let mut e = exponent;
+            while e > 0 {
+                if e & 1 == 1 {
+                    expected = expected * power % modulus;
+                }
+                power = power * power % modulus;
+                e >>= 1;
+            }
+
+            let result = big(base).modpow(&big(exponent), &big(modulus));
+            assert_eq!(result.to_u128(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_random_gcd_and_modinv_against_u128() {
+        let mut rng = Rng::new(32);
+
+        for _ in 0..500 {
+            let a = random_u128(&mut rng, 64);
+            let m = random_u128(&mut rng, 64).max(2);
+
+            let mut x = a;
+            let mut y = m;
+            while y != 0 {
+                let r = x % y;
+                x = y;
+                y = r;
+            }
+            assert_eq!(big(a).gcd(&big(m)).to_u128(), Some(x));
+
+            match big(a).modinv(&big(m)) {
+                Some(inverse) => {
+                    let inverse = inverse.to_u128().unwrap();
+                    assert_eq!(x, 1);
+                    assert_eq!(a % m * inverse % m, 1);
+                }
+                None => assert_ne!(x, 1),
+            }
+        }
+    }
+
+    #[test]
+    fn test_multi_digit_divrem_roundtrip() {
+        let mut rng = Rng::new(33);
+
+        for _ in 0..200 {
+            let a = BigUint::random_bits(&mut rng, 1024);
+            let b = BigUint::random_bits(&mut rng, 300).max(BigUint::one());
+
+            let (quotient, remainder) = a.divrem(&b);
+            assert!(remainder < b);
+            assert_eq!(&(&quotient * &b) + &remainder, a);
+        }
+    }
+
+    #[test]
+    fn test_divrem_extreme_digits() {
+        // Divisors with all-ones and near-zero digit patterns exercise the q_hat correction
+        // and add-back steps of Algorithm D.
+        let patterns = ["ffffffff", "80000000", "00000001", "7fffffff", "00000000"];
+
+        for a_pattern in 0..patterns.len() {
+            for b_pattern in 0..patterns.len() {
+                let a = BigUint::from_hex(&format!("1{}", patterns[a_pattern].repeat(9)));
+                let b = BigUint::from_hex(&format!("8{}", patterns[b_pattern].repeat(3)));
+
+                let (quotient, remainder) = a.divrem(&b);
+                assert!(remainder < b);
+                assert_eq!(&(&quotient * &b) + &remainder, a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hex_roundtrip() {
+        let hex = "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74";
+        let value = BigUint::from_hex(hex);
+
+        assert_eq!(value.to_hex(), hex);
+        assert_eq!(BigUint::from_hex("0").to_hex(), "0");
+        assert_eq!(BigUint::from_hex("00ff").to_hex(), "ff");
+    }
+
+    #[test]
+    fn test_bytes_roundtrip() {
+        let bytes = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let value = BigUint::from_bytes_be(&bytes);
+
+        assert_eq!(value.to_bytes_be(), bytes);
+        assert_eq!(
+            value.to_bytes_be_padded(12),
+            [vec![0, 0, 0], bytes].concat()
+        );
+    }
+
+    #[test]
+    fn test_decimal_roundtrip() {
+        let decimal = "340282366920938463463374607431768211457";
+        let value = BigUint::from_decimal(decimal);
+
+        assert_eq!(value, big(u128::MAX) + big(2));
+        assert_eq!(value.to_decimal(), decimal);
+        assert_eq!(BigUint::zero().to_decimal(), "0");
+    }
+
+    #[test]
+    fn test_nth_root() {
+        let mut rng = Rng::new(34);
+
+        for _ in 0..200 {
+            let root = BigUint::random_bits(&mut rng, 200);
+            let cube = root.pow(3);
+
+            assert_eq!(cube.nth_root(3), root);
+            assert_eq!((&cube + &BigUint::one()).nth_root(3), root);
+            if !root.is_zero() {
+                assert_eq!(
+                    (&cube - &BigUint::one()).nth_root(3),
+                    &root - &BigUint::one()
+                );
+            }
+        }
+
+        assert_eq!(big(1_000_000).nth_root(2), big(1000));
+        assert_eq!(big(7).nth_root(5), big(1));
+    }
+
+    #[test]
+    fn test_fermat_little_theorem() {
+        // 2^127 - 1 is a Mersenne prime
+        let p = (BigUint::one() << 127) - BigUint::one();
+        let a = BigUint::from_hex("123456789abcdef0123456789abcdef");
+
+        assert!(a.modpow(&(&p - &BigUint::one()), &p).is_one());
+    }
+}
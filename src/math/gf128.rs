@@ -0,0 +1,197 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Sub};
+
+use crate::random::Rng;
+
+// An element of GF(2^128) = GF(2)[x] / (x^128 + x^7 + x^2 + x + 1), the field GCM works in.
+// Bit i holds the coefficient of x^i. GCM's byte encoding puts x^0 in the top bit of the
+// first byte, so the bits get reversed on the way in and out.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Gf128(u128);
+
+// The low terms of the modulus: x^128 = x^7 + x^2 + x + 1
+const REDUCTION: u128 = 0x87;
+
+impl Gf128 {
+    pub const ZERO: Gf128 = Gf128(0);
+    pub const ONE: Gf128 = Gf128(1);
+
+    pub fn from_bits(bits: u128) -> Self {
+        Gf128(bits)
+    }
+
+    pub fn bits(self) -> u128 {
+        self.0
+    }
+
+    // x^power, for power below 128.
+    pub fn monomial(power: usize) -> Self {
+        Gf128(1 << power)
+    }
+
+    // A block of up to 16 bytes, zero-padded on the right.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        let mut block = [0; 16];
+        block[..bytes.len()].copy_from_slice(bytes);
+        Gf128(u128::from_be_bytes(block).reverse_bits())
+    }
+
+    pub fn to_bytes(self) -> [u8; 16] {
+        self.0.reverse_bits().to_be_bytes()
+    }
+
+    pub fn random(rng: &mut Rng) -> Self {
+        Gf128(((rng.next_u64() as u128) << 64) | rng.next_u64() as u128)
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn bit(self, index: usize) -> bool {
+        (self.0 >> index) & 1 == 1
+    }
+
+    // Multiplication by x: a shift, folding the bit that falls off back in.
+    pub fn mul_x(self) -> Self {
+        let carry = self.0 >> 127;
+        Gf128((self.0 << 1) ^ (carry * REDUCTION))
+    }
+
+    pub fn square(self) -> Self {
+        self * self
+    }
+
+    pub fn pow(self, exponent: u128) -> Self {
+        let mut result = Gf128::ONE;
+        for index in (0..128 - exponent.leading_zeros()).rev() {
+            result = result.square();
+            if (exponent >> index) & 1 == 1 {
+                result = result * self;
+            }
+        }
+        result
+    }
+
+    // a^(2^128 - 2), since the multiplicative group has order 2^128 - 1.
+    pub fn inverse(self) -> Option<Self> {
+        if self.is_zero() {
+            None
+        } else {
+            Some(self.pow(u128::MAX - 1))
+        }
+    }
+
+    // Squaring is a bijection in characteristic 2, and squaring 128 times is the identity, so
+    // 127 squarings undo one.
+    pub fn sqrt(self) -> Self {
+        (0..127).fold(self, |value, _| value.square())
+    }
+}
+
+// Addition of polynomials over GF(2) is XOR, coefficient by coefficient
+fn add(a: u128, b: u128) -> u128 {
+    a ^ b
+}
+
+fn multiply(a: u128, b: u128) -> u128 {
+    // Carry-less product as a 256-bit (high, low) pair
+    let mut low = 0;
+    let mut high = 0;
+    for index in 0..128 {
+        if (b >> index) & 1 == 1 {
+            low ^= a << index;
+            if index > 0 {
+                high ^= a >> (128 - index);
+            }
+        }
+    }
+
+    // high * x^128 = high * (x^7 + x^2 + x + 1); the few bits that spill past x^127 again
+    // get folded the same way
+    let spill = (high >> 127) ^ (high >> 126) ^ (high >> 121);
+    let folded = high ^ (high << 1) ^ (high << 2) ^ (high << 7);
+    low ^ folded ^ spill ^ (spill << 1) ^ (spill << 2) ^ (spill << 7)
+}
+
+impl Add for Gf128 {
+    type Output = Gf128;
+
+    fn add(self, other: Gf128) -> Gf128 {
+        Gf128(add(self.0, other.0))
+    }
+}
+
+// Addition and subtraction are the same in characteristic 2
+impl Sub for Gf128 {
+    type Output = Gf128;
+
+    fn sub(self, other: Gf128) -> Gf128 {
+        Gf128(add(self.0, other.0))
+    }
+}
+
+impl Mul for Gf128 {
+    type Output = Gf128;
+
+    fn mul(self, other: Gf128) -> Gf128 {
+        Gf128(multiply(self.0, other.0))
+    }
+}
+
+impl Div for Gf128 {
+    type Output = Gf128;
+
+    fn div(self, other: Gf128) -> Gf128 {
+        let inverse = other.inverse().expect("Division by zero");
+        Gf128(multiply(self.0, inverse.0))
+    }
+}
+
+impl fmt::Debug for Gf128 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Gf128({:032x})", u128::from_be_bytes(self.to_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_encoding() {
+        let mut bytes = [0; 16];
+        bytes[0] = 0x80;
+        assert_eq!(Gf128::from_bytes(&bytes), Gf128::ONE);
+        assert_eq!(Gf128::from_bytes(&[0x40]), Gf128::monomial(1));
+        assert_eq!(Gf128::monomial(1).to_bytes()[0], 0x40);
+    }
+
+    #[test]
+    fn test_reduction() {
+        let x127 = Gf128::monomial(127);
+        assert_eq!(x127.mul_x(), Gf128::from_bits(REDUCTION));
+        assert_eq!(x127 * Gf128::monomial(1), x127.mul_x());
+        assert_eq!(
+            x127 * x127,
+            Gf128::from_bits(REDUCTION) * Gf128::monomial(126)
+        );
+    }
+
+    #[test]
+    fn test_field_laws() {
+        let mut rng = Rng::new(63);
+        for _ in 0..20 {
+            let a = Gf128::random(&mut rng);
+            let b = Gf128::random(&mut rng);
+            let c = Gf128::random(&mut rng);
+
+            assert_eq!(a * b, b * a);
+            assert_eq!(a * (b + c), a * b + a * c);
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a * a.inverse().unwrap(), Gf128::ONE);
+            assert_eq!(a * b / b, a);
+            assert_eq!(a.square().sqrt(), a);
+        }
+    }
+}
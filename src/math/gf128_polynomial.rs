@@ -0,0 +1,394 @@
+use std::ops::{Add, Mul, Sub};
+
+use crate::math::bigint::BigUint;
+use crate::math::gf128::Gf128;
+use crate::random::Rng;
+
+// A polynomial over GF(2^128), lowest degree first, with no trailing zero coefficients.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Polynomial {
+    coefficients: Vec<Gf128>,
+}
+
+impl Polynomial {
+    pub fn new(mut coefficients: Vec<Gf128>) -> Self {
+        while coefficients.last() == Some(&Gf128::ZERO) {
+            coefficients.pop();
+        }
+        Polynomial { coefficients }
+    }
+
+    pub fn zero() -> Self {
+        Polynomial::new(Vec::new())
+    }
+
+    pub fn constant(value: Gf128) -> Self {
+        Polynomial::new(vec![value])
+    }
+
+    pub fn one() -> Self {
+        Polynomial::constant(Gf128::ONE)
+    }
+
+    // The polynomial X (not the field element x).
+    pub fn x() -> Self {
+        Polynomial::new(vec![Gf128::ZERO, Gf128::ONE])
+    }
+
+    pub fn random(rng: &mut Rng, degree_bound: usize) -> Self {
+        Polynomial::new((0..degree_bound).map(|_| Gf128::random(rng)).collect())
+    }
+
+    pub fn coefficients(&self) -> &[Gf128] {
+        &self.coefficients
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.coefficients == [Gf128::ONE]
+    }
+
+    // The degree, taking the zero polynomial to have degree 0.
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    pub fn leading_coefficient(&self) -> Gf128 {
+        self.coefficients.last().copied().unwrap_or(Gf128::ZERO)
+    }
+
+    pub fn evaluate(&self, point: Gf128) -> Gf128 {
+        self.coefficients
+            .iter()
+            .rev()
+            .fold(Gf128::ZERO, |result, &coefficient| {
+                result * point + coefficient
+            })
+    }
+
+    pub fn scale(&self, factor: Gf128) -> Polynomial {
+        Polynomial::new(self.coefficients.iter().map(|&c| c * factor).collect())
+    }
+
+    pub fn monic(&self) -> Polynomial {
+        if self.is_zero() {
+            return self.clone();
+        }
+        self.scale(self.leading_coefficient().inverse().unwrap())
+    }
+
+    // In characteristic 2 the even-power terms vanish: the derivative of c X^i is i c X^(i-1).
+    pub fn derivative(&self) -> Polynomial {
+        Polynomial::new(
+            self.coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(power, &c)| if power % 2 == 1 { c } else { Gf128::ZERO })
+                .collect(),
+        )
+    }
+
+    pub fn divrem(&self, divisor: &Polynomial) -> (Polynomial, Polynomial) {
+        if divisor.is_zero() {
+            panic!("Division by zero");
+        }
+        if self.coefficients.len() < divisor.coefficients.len() {
+            return (Polynomial::zero(), self.clone());
+        }
+
+        let lead_inverse = divisor.leading_coefficient().inverse().unwrap();
+        let shift_count = self.coefficients.len() - divisor.coefficients.len() + 1;
+        let mut remainder = self.coefficients.clone();
+        let mut quotient = vec![Gf128::ZERO; shift_count];
+
+        for shift in (0..shift_count).rev() {
+            let factor = remainder[shift + divisor.degree()] * lead_inverse;
+            quotient[shift] = factor;
+            for (index, &c) in divisor.coefficients.iter().enumerate() {
+                remainder[shift + index] = remainder[shift + index] + factor * c;
+            }
+        }
+
+        (Polynomial::new(quotient), Polynomial::new(remainder))
+    }
+
+    // The monic greatest common divisor.
+    pub fn gcd(&self, other: &Polynomial) -> Polynomial {
+        let mut a = self.clone();
+        let mut b = other.clone();
+        while !b.is_zero() {
+            let remainder = a.divrem(&b).1;
+            a = std::mem::replace(&mut b, remainder);
+        }
+        a.monic()
+    }
+
+    pub fn mul_mod(&self, other: &Polynomial, modulus: &Polynomial) -> Polynomial {
+        (self * other).divrem(modulus).1
+    }
+
+    pub fn pow_mod(&self, exponent: &BigUint, modulus: &Polynomial) -> Polynomial {
+        let base = self.divrem(modulus).1;
+        let mut result = Polynomial::one();
+        for index in (0..exponent.bits()).rev() {
+            result = result.mul_mod(&result, modulus);
+            if exponent.bit(index) {
+                result = result.mul_mod(&base, modulus);
+            }
+        }
+        result
+    }
+
+    // self^(2^128) mod modulus, the Frobenius map for the field size.
+    fn frobenius_mod(&self, modulus: &Polynomial) -> Polynomial {
+        (0..128).fold(self.divrem(modulus).1, |power, _| {
+            power.mul_mod(&power, modulus)
+        })
+    }
+
+    // Square-free factorization of a monic polynomial: pairs (g, i) where the product of the
+    // g^i is self and every g is square-free. A zero derivative means every power is even, so
+    // the polynomial is a perfect square and its square root gets factored instead.
+    pub fn square_free_factorization(&self) -> Vec<(Polynomial, usize)> {
+        let mut factors = Vec::new();
+
+        let mut c = self.gcd(&self.derivative());
+        let mut w = self.divrem(&c).0;
+        let mut multiplicity = 1;
+        while !w.is_one() {
+            let y = w.gcd(&c);
+            let factor = w.divrem(&y).0;
+            if !factor.is_one() {
+                factors.push((factor, multiplicity));
+            }
+            c = c.divrem(&y).0;
+            w = y;
+            multiplicity += 1;
+        }
+
+        if !c.is_one() {
+            let root = Polynomial::new(
+                c.coefficients
+                    .iter()
+                    .step_by(2)
+                    .map(|coefficient| coefficient.sqrt())
+                    .collect(),
+            );
+            for (factor, multiplicity) in root.square_free_factorization() {
+                factors.push((factor, 2 * multiplicity));
+            }
+        }
+
+        factors
+    }
+
+    // Distinct-degree factorization of a monic square-free polynomial: pairs (g, d) where g is
+    // the product of all the irreducible factors of degree d. X^(q^d) - X is the product of
+    // every monic irreducible whose degree divides d.
+    pub fn distinct_degree_factorization(&self) -> Vec<(Polynomial, usize)> {
+        let mut factors = Vec::new();
+        let mut remaining = self.clone();
+        let mut power = Polynomial::x();
+        let mut degree = 1;
+
+        while remaining.degree() >= 2 * degree {
+            power = power.frobenius_mod(&remaining);
+            let g = remaining.gcd(&(&power - &Polynomial::x()));
+            if !g.is_one() {
+                remaining = remaining.divrem(&g).0;
+                power = power.divrem(&remaining).1;
+                factors.push((g, degree));
+            }
+            degree += 1;
+        }
+
+        if remaining.degree() > 0 {
+            let degree = remaining.degree();
+            factors.push((remaining, degree));
+        }
+
+        factors
+    }
+
+    // Cantor-Zassenhaus equal-degree splitting of a monic square-free product of irreducibles
+    // of the given degree. q^d - 1 is always divisible by 3 for q = 2^128, and for random a,
+    // a^((q^d - 1) / 3) is a cube root of unity in each factor's field, equal to 1 in roughly a
+    // third of them, so the gcd with it minus 1 splits things apart.
+    pub fn equal_degree_factorization(&self, degree: usize, rng: &mut Rng) -> Vec<Polynomial> {
+        let count = self.degree() / degree;
+        let exponent =
+            &(&(BigUint::one() << (128 * degree)) - &BigUint::one()) / &BigUint::from(3u32);
+
+        let mut factors = vec![self.clone()];
+        while factors.len() < count {
+            let a = Polynomial::random(rng, self.degree());
+            let g = &a.pow_mod(&exponent, self) - &Polynomial::one();
+
+            factors = factors
+                .into_iter()
+                .flat_map(|factor| {
+                    if factor.degree() == degree {
+                        return vec![factor];
+                    }
+                    let split = factor.gcd(&g);
+                    if split.is_one() || split == factor {
+                        vec![factor]
+                    } else {
+                        let other = factor.divrem(&split).0;
+                        vec![split, other]
+                    }
+                })
+                .collect();
+        }
+
+        factors
+    }
+
+    // The monic irreducible factors with their multiplicities.
+    pub fn factor(&self, rng: &mut Rng) -> Vec<(Polynomial, usize)> {
+        let mut factors = Vec::new();
+        for (square_free, multiplicity) in self.monic().square_free_factorization() {
+            for (product, degree) in square_free.distinct_degree_factorization() {
+                for factor in product.equal_degree_factorization(degree, rng) {
+                    factors.push((factor, multiplicity));
+                }
+            }
+        }
+        factors
+    }
+
+    // The distinct roots: X + c is a linear factor exactly when c is a root.
+    pub fn roots(&self, rng: &mut Rng) -> Vec<Gf128> {
+        self.factor(rng)
+            .into_iter()
+            .filter(|(factor, _)| factor.degree() == 1)
+            .map(|(factor, _)| factor.coefficients[0])
+            .collect()
+    }
+}
+
+fn add(a: &Polynomial, b: &Polynomial) -> Polynomial {
+    let (longer, shorter) = if a.coefficients.len() >= b.coefficients.len() {
+        (a, b)
+    } else {
+        (b, a)
+    };
+
+    let mut coefficients = longer.coefficients.clone();
+    for (coefficient, &other) in coefficients.iter_mut().zip(&shorter.coefficients) {
+        *coefficient = *coefficient + other;
+    }
+    Polynomial::new(coefficients)
+}
+
+fn multiply(a: &Polynomial, b: &Polynomial) -> Polynomial {
+    if a.is_zero() || b.is_zero() {
+        return Polynomial::zero();
+    }
+
+    let mut coefficients = vec![Gf128::ZERO; a.coefficients.len() + b.coefficients.len() - 1];
+    for (i, &x) in a.coefficients.iter().enumerate() {
+        for (j, &y) in b.coefficients.iter().enumerate() {
+            coefficients[i + j] = coefficients[i + j] + x * y;
+        }
+    }
+    Polynomial::new(coefficients)
+}
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $method:ident, $body:expr) => {
+        impl<'a, 'b> $trait<&'b Polynomial> for &'a Polynomial {
+            type Output = Polynomial;
+
+            fn $method(self, other: &'b Polynomial) -> Polynomial {
+                let body: fn(&Polynomial, &Polynomial) -> Polynomial = $body;
+                body(self, other)
+            }
+        }
+
+        impl $trait<Polynomial> for Polynomial {
+            type Output = Polynomial;
+
+            fn $method(self, other: Polynomial) -> Polynomial {
+                (&self).$method(&other)
+            }
+        }
+    };
+}
+
+// Subtraction is addition in characteristic 2
+impl_binary_op!(Add, add, add);
+impl_binary_op!(Sub, sub, add);
+impl_binary_op!(Mul, mul, multiply);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear(root: Gf128) -> Polynomial {
+        Polynomial::new(vec![root, Gf128::ONE])
+    }
+
+    #[test]
+    fn test_divrem() {
+        let mut rng = Rng::new(63);
+        let a = Polynomial::random(&mut rng, 7);
+        let b = Polynomial::random(&mut rng, 3);
+
+        let (quotient, remainder) = a.divrem(&b);
+        assert!(remainder.degree() < b.degree());
+        assert_eq!(&(&quotient * &b) + &remainder, a);
+    }
+
+    #[test]
+    fn test_gcd() {
+        let mut rng = Rng::new(63);
+        let common = Polynomial::random(&mut rng, 3).monic();
+        let a = &common * &Polynomial::random(&mut rng, 4);
+        let b = &common * &linear(Gf128::random(&mut rng));
+
+        assert_eq!(a.gcd(&b), common);
+    }
+
+    #[test]
+    fn test_roots_of_product() {
+        let mut rng = Rng::new(63);
+        let roots: Vec<Gf128> = (0..4).map(|_| Gf128::random(&mut rng)).collect();
+
+        // Two distinct linear factors, one squared, and a quadratic that may or may not split
+        let polynomial = [
+            linear(roots[0]),
+            linear(roots[1]),
+            linear(roots[2]),
+            linear(roots[2]),
+            Polynomial::new(vec![Gf128::random(&mut rng), Gf128::ONE, Gf128::ONE]),
+        ]
+        .iter()
+        .fold(Polynomial::one(), |product, factor| &product * factor)
+        .scale(roots[3]);
+
+        let found = polynomial.roots(&mut rng);
+        for root in &roots[..3] {
+            assert!(found.contains(root));
+        }
+        for root in &found {
+            assert_eq!(polynomial.evaluate(*root), Gf128::ZERO);
+        }
+    }
+
+    #[test]
+    fn test_factor_multiplicities() {
+        let mut rng = Rng::new(63);
+        let a = linear(Gf128::random(&mut rng));
+        let b = linear(Gf128::random(&mut rng));
+        let polynomial = &(&(&a * &a) * &(&a * &b)) * &b;
+
+        let mut factors = polynomial.factor(&mut rng);
+        factors.sort_by_key(|(_, multiplicity)| *multiplicity);
+        assert_eq!(factors, vec![(b, 2), (a, 3)]);
+    }
+}
@@ -0,0 +1,103 @@
+// Linear algebra over GF(2). Vectors are bit-packed into u64 words, least significant bit
+// first, so entry i lives in word i / 64.
+
+pub fn words_for(length: usize) -> usize {
+    length.div_ceil(64)
+}
+
+pub fn bit(vector: &[u64], index: usize) -> bool {
+    (vector[index / 64] >> (index % 64)) & 1 == 1
+}
+
+pub fn flip_bit(vector: &mut [u64], index: usize) {
+    vector[index / 64] ^= 1 << (index % 64);
+}
+
+fn xor_into(target: &mut [u64], source: &[u64]) {
+    for (word, other) in target.iter_mut().zip(source) {
+        *word ^= other;
+    }
+}
+
+// A basis for the vectors v with rows * v = 0, for a matrix with the given number of columns.
+// Gauss-Jordan elimination, then one basis vector per free column.
+pub fn kernel(rows: &[Vec<u64>], columns: usize) -> Vec<Vec<u64>> {
+    let mut reduced = rows.to_vec();
+    let mut pivots = Vec::new();
+
+    for column in 0..columns {
+        let pivot_row = pivots.len();
+        let found = match (pivot_row..reduced.len()).find(|&row| bit(&reduced[row], column)) {
+            Some(found) => found,
+            None => continue,
+        };
+        reduced.swap(pivot_row, found);
+
+        let pivot = reduced[pivot_row].clone();
+        for (index, row) in reduced.iter_mut().enumerate() {
+            if index != pivot_row && bit(row, column) {
+                xor_into(row, &pivot);
+            }
+        }
+        pivots.push(column);
+    }
+
+    let mut is_pivot = vec![false; columns];
+    for &column in &pivots {
+        is_pivot[column] = true;
+    }
+
+    (0..columns)
+        .filter(|&free| !is_pivot[free])
+        .map(|free| {
+            let mut vector = vec![0; words_for(columns)];
+            flip_bit(&mut vector, free);
+            for (row, &column) in pivots.iter().enumerate() {
+                if bit(&reduced[row], free) {
+                    flip_bit(&mut vector, column);
+                }
+            }
+            vector
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Rng;
+
+    fn dot(a: &[u64], b: &[u64]) -> bool {
+        a.iter()
+            .zip(b)
+            .fold(0, |parity, (x, y)| parity ^ (x & y).count_ones())
+            % 2
+            == 1
+    }
+
+    #[test]
+    fn test_kernel() {
+        let mut rng = Rng::new(64);
+        let columns = 150;
+
+        // A rank-deficient matrix: the last rows repeat sums of the first ones
+        let mut rows: Vec<Vec<u64>> = (0..100)
+            .map(|_| {
+                let mut row: Vec<u64> = (0..words_for(columns)).map(|_| rng.next_u64()).collect();
+                row[2] &= (1 << (columns - 128)) - 1;
+                row
+            })
+            .collect();
+        for index in 0..20 {
+            let mut sum = rows[index].clone();
+            xor_into(&mut sum, &rows[index + 1]);
+            rows.push(sum);
+        }
+
+        let basis = kernel(&rows, columns);
+        assert_eq!(basis.len(), columns - 100);
+        for vector in &basis {
+            assert!(rows.iter().all(|row| !dot(row, vector)));
+        }
+    }
+}
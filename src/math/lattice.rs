@@ -0,0 +1,266 @@
+use crate::math::bigint::BigUint;
+use crate::math::rational::Rational;
+use crate::math::signed::BigInt;
+
+fn dot(a: &[BigInt], b: &[BigInt]) -> BigInt {
+    a.iter()
+        .zip(b)
+        .fold(BigInt::zero(), |sum, (x, y)| sum + x * y)
+}
+
+fn lcm(a: &BigUint, b: &BigUint) -> BigUint {
+    &(a / &a.gcd(b)) * b
+}
+
+// LLL on integer vectors, kept exact by working with the integral Gram-Schmidt data from
+// Cohen's Algorithm 2.6.7: d[i] is the Gram determinant of the first i vectors, and
+// lambda[k][j] = d[j] * mu(k, j) is always an integer. The rows must be linearly
+// independent. delta is the Lovasz constant, between 1/4 and 1.
+pub fn lll_integer(basis: &[Vec<BigInt>], delta: &Rational) -> Vec<Vec<BigInt>> {
+    let n = basis.len();
+    let mut b = basis.to_vec();
+    if n < 2 {
+        return b;
+    }
+
+    let delta_numerator = delta.numerator().clone();
+    let delta_denominator = BigInt::from(delta.denominator().clone());
+
+    // 1-indexed as in the book, with d[0] = 1
+    let mut d = vec![BigInt::one(); n + 1];
+    let mut lambda = vec![vec![BigInt::zero(); n + 1]; n + 1];
+    d[1] = dot(&b[0], &b[0]);
+
+    let mut k = 2;
+    let mut k_max = 1;
+    while k <= n {
+        // Gram-Schmidt data for a vector seen for the first time
+        if k > k_max {
+            k_max = k;
+            for j in 1..=k {
+                let mut u = dot(&b[k - 1], &b[j - 1]);
+                for i in 1..j {
+                    u = (&d[i] * &u - &lambda[k][i] * &lambda[j][i]).div_exact(&d[i - 1]);
+                }
+                if j < k {
+                    lambda[k][j] = u;
+                } else {
+                    assert!(!u.is_zero(), "Basis vectors must be linearly independent");
+                    d[k] = u;
+                }
+            }
+        }
+
+        reduce(&mut b, &mut lambda, &d, k, k - 1);
+
+        // Lovasz condition, multiplied through by d[k - 1] * d[k - 2] and delta's denominator
+        let lambda_k = &lambda[k][k - 1];
+        let left = &delta_denominator * &(&d[k] * &d[k - 2] + lambda_k * lambda_k);
+        let right = &delta_numerator * &(&d[k - 1] * &d[k - 1]);
+        if left < right {
+            swap(&mut b, &mut lambda, &mut d, k, k_max);
+            k = std::cmp::max(2, k - 1);
+        } else {
+            for l in (1..k - 1).rev() {
+                reduce(&mut b, &mut lambda, &d, k, l);
+            }
+            k += 1;
+        }
+    }
+
+    b
+}
+
+// Size reduction: takes the nearest multiple of vector l off vector k (counting from 1) so
+// that |mu(k, l)| <= 1/2.
+fn reduce(b: &mut [Vec<BigInt>], lambda: &mut [Vec<BigInt>], d: &[BigInt], k: usize, l: usize) {
+    let twice = &lambda[k][l] + &lambda[k][l];
+    if twice.abs() <= d[l] {
+        return;
+    }
+
+    let q = lambda[k][l].div_round(&d[l]);
+    let (head, tail) = b.split_at_mut(k - 1);
+    for (x, y) in tail[0].iter_mut().zip(&head[l - 1]) {
+        *x = &*x - &(&q * y);
+    }
+
+    let (head, tail) = lambda.split_at_mut(k);
+    let row = &mut tail[0];
+    row[l] = &row[l] - &(&q * &d[l]);
+    for (x, y) in row[1..l].iter_mut().zip(&head[l][1..l]) {
+        *x = &*x - &(&q * y);
+    }
+}
+
+// Exchanges vectors k - 1 and k, updating the Gram-Schmidt data in place.
+fn swap(
+    b: &mut [Vec<BigInt>],
+    lambda: &mut [Vec<BigInt>],
+    d: &mut [BigInt],
+    k: usize,
+    k_max: usize,
+) {
+    b.swap(k - 2, k - 1);
+    let (head, tail) = lambda.split_at_mut(k);
+    head[k - 1][1..k - 1].swap_with_slice(&mut tail[0][1..k - 1]);
+
+    let l = lambda[k][k - 1].clone();
+    let new_d = (&d[k - 2] * &d[k] + &l * &l).div_exact(&d[k - 1]);
+    for row in &mut lambda[k + 1..=k_max] {
+        let t = row[k].clone();
+        row[k] = (&d[k] * &row[k - 1] - &l * &t).div_exact(&d[k - 1]);
+        row[k - 1] = (&new_d * &t + &l * &row[k]).div_exact(&d[k]);
+    }
+    d[k - 1] = new_d;
+}
+
+// LLL on rational vectors: scales everything up to integers by the common denominator,
+// reduces, and scales back down.
+pub fn lll(basis: &[Vec<Rational>], delta: &Rational) -> Vec<Vec<Rational>> {
+    let scale = basis
+        .iter()
+        .flatten()
+        .fold(BigUint::one(), |scale, x| lcm(&scale, x.denominator()));
+
+    let integer_basis: Vec<Vec<BigInt>> = basis
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|x| x.numerator() * &BigInt::from(&scale / x.denominator()))
+                .collect()
+        })
+        .collect();
+
+    lll_integer(&integer_basis, delta)
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|x| Rational::new(x, scale.clone()))
+                .collect()
+        })
+        .collect()
+}
+
+// The hidden number problem: find x mod q given pairs (t_i, u_i) for which u_i + t_i x mod q
+// is below q / 2^l. Writing b_i for those small values, the vector
+// (b_1 2^l, ..., b_n 2^l, x, q) is a short vector in the lattice spanned by the rows
+//
+//     q 2^l   0     ...  0      0  0
+//     0       q 2^l ...  0      0  0
+//     ...
+//     t_1 2^l t_2 2^l ... t_n 2^l  1  0
+//     u_1 2^l u_2 2^l ... u_n 2^l  0  q
+//
+// (the rational lattice from the challenge, scaled by 2^l), and LLL tends to turn it up as
+// one of the rows that ends in +-q. Every such row gives a candidate for x; checking them is
+// up to the caller.
+pub fn hidden_number_problem(
+    q: &BigUint,
+    l: usize,
+    samples: &[(BigUint, BigUint)],
+) -> Vec<BigUint> {
+    let n = samples.len();
+    let scale = BigUint::one() << l;
+    let q_signed = BigInt::from(q.clone());
+
+    let mut basis = vec![vec![BigInt::zero(); n + 2]; n + 2];
+    for (i, row) in basis.iter_mut().take(n).enumerate() {
+        row[i] = BigInt::from(q * &scale);
+    }
+    for (i, (t, u)) in samples.iter().enumerate() {
+        basis[n][i] = BigInt::from(t * &scale);
+        basis[n + 1][i] = BigInt::from(u * &scale);
+    }
+    basis[n][n] = BigInt::one();
+    basis[n + 1][n + 1] = q_signed.clone();
+
+    let mut candidates = Vec::new();
+    for row in lll_integer(&basis, &Rational::from_ratio(99, 100)) {
+        if row[n + 1].abs() != q_signed {
+            continue;
+        }
+
+        let x = if row[n + 1].is_negative() {
+            -&row[n]
+        } else {
+            row[n].clone()
+        };
+        let candidate = x.rem_euclid(q);
+        if !candidates.contains(&candidate) {
+            candidates.push(candidate);
+        }
+    }
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Rng;
+
+    fn rationals(rows: &[&[(i64, u64)]]) -> Vec<Vec<Rational>> {
+        rows.iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&(numerator, denominator)| Rational::from_ratio(numerator, denominator))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_lll_small_basis() {
+        let basis = rationals(&[
+            &[(1, 1), (1, 1), (1, 1)],
+            &[(-1, 1), (0, 1), (2, 1)],
+            &[(3, 1), (5, 1), (6, 1)],
+        ]);
+        let expected = rationals(&[
+            &[(0, 1), (1, 1), (0, 1)],
+            &[(1, 1), (0, 1), (1, 1)],
+            &[(-1, 1), (0, 1), (2, 1)],
+        ]);
+
+        assert_eq!(lll(&basis, &Rational::from_ratio(3, 4)), expected);
+    }
+
+    #[test]
+    fn test_lll_challenge_example() {
+        let basis = rationals(&[
+            &[(-2, 1), (0, 1), (2, 1), (0, 1)],
+            &[(1, 2), (-1, 1), (0, 1), (0, 1)],
+            &[(-1, 1), (0, 1), (-2, 1), (1, 2)],
+            &[(-1, 1), (1, 1), (1, 1), (2, 1)],
+        ]);
+        let expected = rationals(&[
+            &[(1, 2), (-1, 1), (0, 1), (0, 1)],
+            &[(-1, 1), (0, 1), (-2, 1), (1, 2)],
+            &[(-1, 2), (0, 1), (1, 1), (2, 1)],
+            &[(-3, 2), (-1, 1), (2, 1), (0, 1)],
+        ]);
+
+        assert_eq!(lll(&basis, &Rational::from_ratio(99, 100)), expected);
+    }
+
+    #[test]
+    fn test_hidden_number_problem() {
+        let mut rng = Rng::new(62);
+        let q = BigUint::from_decimal("29246302889428143187362802287225875743");
+        let l = 16;
+        let x = BigUint::random_below(&mut rng, &q);
+
+        // u_i = b_i - t_i x for random t_i and b_i below q / 2^l
+        let samples: Vec<(BigUint, BigUint)> = (0..12)
+            .map(|_| {
+                let t = BigUint::random_below(&mut rng, &q);
+                let b = BigUint::random_below(&mut rng, &(&q >> l));
+                let u = &(&(&b + &q) - &(&(&t * &x) % &q)) % &q;
+                (t, u)
+            })
+            .collect();
+
+        assert!(hidden_number_problem(&q, l, &samples).contains(&x));
+    }
+}
@@ -0,0 +1,276 @@
+use crate::math::bigint::BigUint;
+use crate::random::Rng;
+
+const MILLER_RABIN_ROUNDS: usize = 40;
+
+pub fn small_primes(limit: u32) -> Vec<u32> {
+    let limit = limit as usize;
+    let mut is_prime = vec![true; limit + 1];
+    let mut primes = Vec::new();
+
+    for candidate in 2..=limit {
+        if is_prime[candidate] {
+            primes.push(candidate as u32);
+            for multiple in (candidate * candidate..=limit).step_by(candidate) {
+                is_prime[multiple] = false;
+            }
+        }
+    }
+
+    primes
+}
+
+pub fn is_probable_prime(n: &BigUint, rng: &mut Rng) -> bool {
+    let two = BigUint::from(2u32);
+    if n < &two {
+        return false;
+    }
+
+    for prime in small_primes(1000) {
+        let prime = BigUint::from(prime);
+        if n == &prime {
+            return true;
+        }
+        if (n % &prime).is_zero() {
+            return false;
+        }
+    }
+
+    miller_rabin(n, MILLER_RABIN_ROUNDS, rng)
+}
+
+// Requires n odd and greater than 3.
+fn miller_rabin(n: &BigUint, rounds: usize, rng: &mut Rng) -> bool {
+    let one = BigUint::one();
+    let n_minus_one = n - &one;
+    let s = n_minus_one.trailing_zeros();
+    let d = &n_minus_one >> s;
+
+    'witness: for _ in 0..rounds {
+        let a = BigUint::random_range(rng, &BigUint::from(2u32), &n_minus_one);
+        let mut x = a.modpow(&d, n);
+
+        if x == one || x == n_minus_one {
+            continue;
+        }
+
+        for _ in 1..s {
+            x = &(&x * &x) % n;
+            if x == n_minus_one {
+                continue 'witness;
+            }
+        }
+
+        return false;
+    }
+
+    true
+}
+
+// A random prime with exactly `bits` bits; the top two bits are set so that the product
+// of two such primes has exactly twice as many bits.
+pub fn random_prime(rng: &mut Rng, bits: usize) -> BigUint {
+    if bits < 3 {
+        panic!("Primes must have at least 3 bits");
+    }
+
+    loop {
+        let mut candidate = BigUint::random_bits(rng, bits);
+        candidate.set_bit(bits - 1);
+        candidate.set_bit(bits - 2);
+        candidate.set_bit(0);
+
+        if is_probable_prime(&candidate, rng) {
+            return candidate;
+        }
+    }
+}
+
+// Chinese remainder theorem for pairwise coprime moduli: the unique x below the product of
+// the moduli with x = residues[i] (mod moduli[i]).
+pub fn crt(residues: &[BigUint], moduli: &[BigUint]) -> BigUint {
+    let product = moduli
+        .iter()
+        .fold(BigUint::one(), |product, modulus| &product * modulus);
+
+    let sum =
+        residues
+            .iter()
+            .zip(moduli.iter())
+            .fold(BigUint::zero(), |sum, (residue, modulus)| {
+                let others = &product / modulus;
+                let inverse = others
+                    .modinv(modulus)
+                    .expect("Moduli must be pairwise coprime");
+                &sum + &(&(residue * &others) * &inverse)
+            });
+
+    &sum % &product
+}
+
+// Trial division by every prime up to `bound`. Returns the prime factors found with their
+// exponents, and the cofactor left over once they're divided out.
+pub fn trial_division(n: &BigUint, bound: u32) -> (Vec<(u32, u32)>, BigUint) {
+    let mut remaining = n.clone();
+    let mut factors = Vec::new();
+
+    for prime in small_primes(bound) {
+        if remaining.is_one() {
+            break;
+        }
+
+        let mut exponent = 0;
+        while !remaining.is_zero() && remaining.rem_u32(prime) == 0 {
+            remaining = &remaining / &BigUint::from(prime);
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((prime, exponent));
+        }
+    }
+
+    (factors, remaining)
+}
+
+// Euler's criterion: a is a square mod the odd prime p iff a^((p - 1) / 2) isn't -1.
+pub fn is_quadratic_residue(a: &BigUint, p: &BigUint) -> bool {
+    let exponent = &(p - &BigUint::one()) >> 1;
+    let a = a % p;
+    a.is_zero() || a.modpow(&exponent, p).is_one()
+}
+
+// Tonelli-Shanks: a square root of a mod the odd prime p, if there is one.
+pub fn sqrt_mod(a: &BigUint, p: &BigUint) -> Option<BigUint> {
+    let a = a % p;
+    if a.is_zero() {
+        return Some(a);
+    }
+    if !is_quadratic_residue(&a, p) {
+        return None;
+    }
+
+    // p - 1 = q * 2^s with q odd
+    let p_minus_one = p - &BigUint::one();
+    let s = p_minus_one.trailing_zeros();
+    let q = &p_minus_one >> s;
+
+    let mut z = BigUint::from(2u32);
+    while is_quadratic_residue(&z, p) {
+        z = &z + &BigUint::one();
+    }
+
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = a.modpow(&q, p);
+    let mut root = a.modpow(&(&(&q + &BigUint::one()) >> 1), p);
+
+    // Invariant: root^2 = a * t, with t of order dividing 2^(m - 1)
+    while !t.is_one() {
+        let mut i = 0;
+        let mut power = t.clone();
+        while !power.is_one() {
+            power = &(&power * &power) % p;
+            i += 1;
+        }
+
+        let mut b = c;
+        for _ in 0..m - i - 1 {
+            b = &(&b * &b) % p;
+        }
+
+        m = i;
+        c = &(&b * &b) % p;
+        t = &(&t * &c) % p;
+        root = &(&root * &b) % p;
+    }
+
+    Some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trial_division() {
+        let n = BigUint::from(2u64 * 2 * 2 * 3 * 9973 * 1_000_003);
+        let (factors, cofactor) = trial_division(&n, 10_000);
+
+        assert_eq!(factors, vec![(2, 3), (3, 1), (9973, 1)]);
+        assert_eq!(cofactor, BigUint::from(1_000_003u32));
+    }
+
+    #[test]
+    fn test_small_primes() {
+        assert_eq!(small_primes(30), vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+    }
+
+    #[test]
+    fn test_miller_rabin_agrees_with_sieve() {
+        let mut rng = Rng::new(39);
+        let primes = small_primes(5000);
+
+        for n in 0..5000u32 {
+            assert_eq!(
+                is_probable_prime(&BigUint::from(n), &mut rng),
+                primes.contains(&n),
+                "{}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_carmichael_numbers_are_composite() {
+        let mut rng = Rng::new(40);
+
+        for n in [561u64, 41041, 825265, 321197185, 5394826801] {
+            assert!(!is_probable_prime(&BigUint::from(n), &mut rng));
+        }
+    }
+
+    #[test]
+    fn test_random_prime_bits() {
+        let mut rng = Rng::new(41);
+        let prime = random_prime(&mut rng, 128);
+
+        assert_eq!(prime.bits(), 128);
+        assert!(BigUint::from(2u32)
+            .modpow(&(&prime - &BigUint::one()), &prime)
+            .is_one());
+    }
+
+    #[test]
+    fn test_crt() {
+        let residues = [2u32, 3, 2].map(BigUint::from);
+        let moduli = [3u32, 5, 7].map(BigUint::from);
+
+        assert_eq!(crt(&residues, &moduli), BigUint::from(23u32));
+    }
+
+    #[test]
+    fn test_sqrt_mod() {
+        // 233970423115425145524320034830162017933 = 5 (mod 8), so this needs the full
+        // Tonelli-Shanks loop rather than a single exponentiation
+        let primes = [
+            BigUint::from(1_000_003u32),
+            BigUint::from(40_961u32),
+            BigUint::from_decimal("233970423115425145524320034830162017933"),
+        ];
+
+        for p in primes {
+            let mut residues = 0;
+            for a in 1..200u32 {
+                let a = BigUint::from(a);
+                match sqrt_mod(&a, &p) {
+                    Some(root) => {
+                        assert_eq!(&(&root * &root) % &p, a);
+                        residues += 1;
+                    }
+                    None => assert!(!is_quadratic_residue(&a, &p)),
+                }
+            }
+            assert!(residues > 50 && residues < 150);
+        }
+    }
+}
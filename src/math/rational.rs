@@ -0,0 +1,183 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::math::bigint::BigUint;
+use crate::math::signed::BigInt;
+
+// An exact fraction in lowest terms, with the sign on the numerator and a positive denominator.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: BigInt,
+    denominator: BigUint,
+}
+
+impl Rational {
+    pub fn new(numerator: BigInt, denominator: BigUint) -> Self {
+        if denominator.is_zero() {
+            panic!("Denominator must be non-zero");
+        }
+
+        if numerator.is_zero() {
+            return Rational::from_integer(numerator);
+        }
+
+        let divisor = numerator.magnitude().gcd(&denominator);
+        Rational {
+            numerator: BigInt::new(numerator.is_negative(), numerator.magnitude() / &divisor),
+            denominator: &denominator / &divisor,
+        }
+    }
+
+    pub fn from_integer(value: BigInt) -> Self {
+        Rational {
+            numerator: value,
+            denominator: BigUint::one(),
+        }
+    }
+
+    // A small fraction, mostly for constants and tests.
+    pub fn from_ratio(numerator: i64, denominator: u64) -> Self {
+        Rational::new(BigInt::from(numerator), BigUint::from(denominator))
+    }
+
+    pub fn numerator(&self) -> &BigInt {
+        &self.numerator
+    }
+
+    pub fn denominator(&self) -> &BigUint {
+        &self.denominator
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator.is_zero()
+    }
+}
+
+impl From<BigInt> for Rational {
+    fn from(value: BigInt) -> Self {
+        Rational::from_integer(value)
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let left = &self.numerator * &BigInt::from(other.denominator.clone());
+        let right = &other.numerator * &BigInt::from(self.denominator.clone());
+        left.cmp(&right)
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.denominator.is_one() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+impl fmt::Debug for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Rational({})", self)
+    }
+}
+
+impl Neg for &Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Rational {
+        Rational {
+            numerator: -&self.numerator,
+            denominator: self.denominator.clone(),
+        }
+    }
+}
+
+fn add(a: &Rational, b: &Rational) -> Rational {
+    let numerator = &(&a.numerator * &BigInt::from(b.denominator.clone()))
+        + &(&b.numerator * &BigInt::from(a.denominator.clone()));
+    Rational::new(numerator, &a.denominator * &b.denominator)
+}
+
+fn multiply(a: &Rational, b: &Rational) -> Rational {
+    Rational::new(&a.numerator * &b.numerator, &a.denominator * &b.denominator)
+}
+
+fn divide(a: &Rational, b: &Rational) -> Rational {
+    if b.is_zero() {
+        panic!("Division by zero");
+    }
+
+    let numerator = &a.numerator * &BigInt::from(b.denominator.clone());
+    let numerator = if b.numerator.is_negative() {
+        -numerator
+    } else {
+        numerator
+    };
+    Rational::new(numerator, &a.denominator * b.numerator.magnitude())
+}
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $method:ident, $body:expr) => {
+        impl<'a, 'b> $trait<&'b Rational> for &'a Rational {
+            type Output = Rational;
+
+            fn $method(self, other: &'b Rational) -> Rational {
+                let body: fn(&Rational, &Rational) -> Rational = $body;
+                body(self, other)
+            }
+        }
+
+        impl $trait<Rational> for Rational {
+            type Output = Rational;
+
+            fn $method(self, other: Rational) -> Rational {
+                (&self).$method(&other)
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, add);
+impl_binary_op!(Sub, sub, |a, b| add(a, &-b));
+impl_binary_op!(Mul, mul, multiply);
+impl_binary_op!(Div, div, divide);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ratio(numerator: i64, denominator: u64) -> Rational {
+        Rational::from_ratio(numerator, denominator)
+    }
+
+    #[test]
+    fn test_lowest_terms() {
+        assert_eq!(ratio(6, 8), ratio(3, 4));
+        assert_eq!(ratio(-6, 8).denominator(), &BigUint::from(4u32));
+        assert_eq!(ratio(0, 5), ratio(0, 1));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(&ratio(1, 2) + &ratio(1, 3), ratio(5, 6));
+        assert_eq!(&ratio(1, 2) - &ratio(3, 4), ratio(-1, 4));
+        assert_eq!(&ratio(-2, 3) * &ratio(3, 4), ratio(-1, 2));
+        assert_eq!(&ratio(1, 2) / &ratio(-3, 4), ratio(-2, 3));
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(ratio(1, 3) < ratio(1, 2));
+        assert!(ratio(-1, 2) < ratio(-1, 3));
+        assert!(ratio(99, 100) > ratio(3, 4));
+    }
+}
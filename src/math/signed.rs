@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+
+use crate::math::bigint::BigUint;
+
+// Sign and magnitude on top of BigUint, for the places that need negative numbers. Zero is
+// never negative.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: BigUint,
+}
+
+impl BigInt {
+    pub fn new(negative: bool, magnitude: BigUint) -> Self {
+        BigInt {
+            negative: negative && !magnitude.is_zero(),
+            magnitude,
+        }
+    }
+
+    pub fn zero() -> Self {
+        BigInt::from(BigUint::zero())
+    }
+
+    pub fn one() -> Self {
+        BigInt::from(BigUint::one())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt::from(self.magnitude.clone())
+    }
+
+    // Division rounding towards negative infinity.
+    pub fn div_floor(&self, divisor: &BigInt) -> BigInt {
+        if divisor.is_zero() {
+            panic!("Division by zero");
+        }
+
+        let (quotient, remainder) = self.magnitude.divrem(&divisor.magnitude);
+        if self.negative == divisor.negative {
+            BigInt::from(quotient)
+        } else if remainder.is_zero() {
+            BigInt::new(true, quotient)
+        } else {
+            BigInt::new(true, &quotient + &BigUint::one())
+        }
+    }
+
+    // self / divisor rounded to the nearest integer, halves rounding up.
+    pub fn div_round(&self, divisor: &BigInt) -> BigInt {
+        if divisor.negative {
+            return (-self).div_round(&-divisor);
+        }
+
+        (&(self + self) + divisor).div_floor(&(divisor + divisor))
+    }
+
+    // Division that's known to leave no remainder.
+    pub fn div_exact(&self, divisor: &BigInt) -> BigInt {
+        BigInt::new(
+            self.negative != divisor.negative,
+            &self.magnitude / &divisor.magnitude,
+        )
+    }
+
+    // The representative of self in [0, modulus).
+    pub fn rem_euclid(&self, modulus: &BigUint) -> BigUint {
+        let remainder = &self.magnitude % modulus;
+        if self.negative && !remainder.is_zero() {
+            modulus - &remainder
+        } else {
+            remainder
+        }
+    }
+}
+
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        BigInt {
+            negative: false,
+            magnitude,
+        }
+    }
+}
+
+impl From<i64> for BigInt {
+    fn from(value: i64) -> Self {
+        BigInt::new(value < 0, BigUint::from(value.unsigned_abs()))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.negative { "-" } else { "" };
+        write!(f, "{}{}", sign, self.magnitude)
+    }
+}
+
+impl fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BigInt({})", self)
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        BigInt::new(!self.negative, self.magnitude.clone())
+    }
+}
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -&self
+    }
+}
+
+fn add(a: &BigInt, b: &BigInt) -> BigInt {
+    if a.negative == b.negative {
+        return BigInt::new(a.negative, &a.magnitude + &b.magnitude);
+    }
+
+    // Opposite signs: the result takes the sign of the larger magnitude
+    if a.magnitude >= b.magnitude {
+        BigInt::new(a.negative, &a.magnitude - &b.magnitude)
+    } else {
+        BigInt::new(b.negative, &b.magnitude - &a.magnitude)
+    }
+}
+
+macro_rules! impl_binary_op {
+    ($trait:ident, $method:ident, $body:expr) => {
+        impl<'a, 'b> $trait<&'b BigInt> for &'a BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: &'b BigInt) -> BigInt {
+                let body: fn(&BigInt, &BigInt) -> BigInt = $body;
+                body(self, other)
+            }
+        }
+
+        impl<'a> $trait<&'a BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: &'a BigInt) -> BigInt {
+                (&self).$method(other)
+            }
+        }
+
+        impl<'a> $trait<BigInt> for &'a BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                self.$method(&other)
+            }
+        }
+
+        impl $trait<BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                (&self).$method(&other)
+            }
+        }
+    };
+}
+
+impl_binary_op!(Add, add, add);
+impl_binary_op!(Sub, sub, |a, b| add(a, &-b));
+impl_binary_op!(Mul, mul, |a, b| BigInt::new(
+    a.negative != b.negative,
+    &a.magnitude * &b.magnitude
+));
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn int(value: i64) -> BigInt {
+        BigInt::from(value)
+    }
+
+    #[test]
+    fn test_arithmetic_matches_i64() {
+        let values = [-7i64, -3, -1, 0, 1, 2, 5, 12];
+
+        for &a in &values {
+            for &b in &values {
+                assert_eq!(&int(a) + &int(b), int(a + b));
+                assert_eq!(&int(a) - &int(b), int(a - b));
+                assert_eq!(&int(a) * &int(b), int(a * b));
+                assert_eq!(int(a).cmp(&int(b)), a.cmp(&b));
+                if b != 0 {
+                    let floor = (a as f64 / b as f64).floor() as i64;
+                    assert_eq!(int(a).div_floor(&int(b)), int(floor));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_div_round() {
+        assert_eq!(int(7).div_round(&int(2)), int(4));
+        assert_eq!(int(-7).div_round(&int(2)), int(-3));
+        assert_eq!(int(5).div_round(&int(3)), int(2));
+        assert_eq!(int(-5).div_round(&int(3)), int(-2));
+        assert_eq!(int(5).div_round(&int(-3)), int(-2));
+    }
+
+    #[test]
+    fn test_rem_euclid() {
+        let modulus = BigUint::from(7u32);
+
+        assert_eq!(int(-1).rem_euclid(&modulus), BigUint::from(6u32));
+        assert_eq!(int(-14).rem_euclid(&modulus), BigUint::zero());
+        assert_eq!(int(10).rem_euclid(&modulus), BigUint::from(3u32));
+    }
+
+    #[test]
+    fn test_zero_is_not_negative() {
+        assert_eq!(&int(3) - &int(3), BigInt::zero());
+        assert!(!(-BigInt::zero()).is_negative());
+    }
+}
@@ -0,0 +1,7 @@
+pub mod bank;
+pub mod compression;
+pub mod dh;
+pub mod ec;
+pub mod gcm;
+pub mod rc4;
+pub mod rsa;
@@ -0,0 +1,134 @@
+use crate::ciphers::aes::BLOCK_SIZE;
+use crate::mac::cbc_mac;
+use crate::random::Rng;
+
+const ZERO_IV: [u8; BLOCK_SIZE] = [0; BLOCK_SIZE];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transfer {
+    pub from: u32,
+    pub to: u32,
+    pub amount: u64,
+}
+
+// Challenge 49's API server. It shares its key with the web client, which signs requests on
+// behalf of whichever account is logged in.
+pub struct BankServer {
+    key: Vec<u8>,
+}
+
+// The web client for a single account. It will only ever sign requests from that account.
+pub struct BankClient {
+    key: Vec<u8>,
+    account: u32,
+}
+
+impl BankServer {
+    pub fn new(rng: &mut Rng) -> Self {
+        BankServer {
+            key: rng.bytes(BLOCK_SIZE),
+        }
+    }
+
+    pub fn client(&self, account: u32) -> BankClient {
+        BankClient {
+            key: self.key.clone(),
+            account,
+        }
+    }
+
+    // message || IV || MAC, with the message "from=#{from}&to=#{to}&amount=#{amount}".
+    pub fn process_transfer(&self, request: &[u8]) -> Option<Transfer> {
+        if request.len() < 2 * BLOCK_SIZE {
+            return None;
+        }
+
+        let (message, rest) = request.split_at(request.len() - 2 * BLOCK_SIZE);
+        let (iv, mac) = rest.split_at(BLOCK_SIZE);
+        if cbc_mac(&self.key, iv, message) != mac {
+            return None;
+        }
+
+        let mut from = None;
+        let mut to = None;
+        let mut amount = None;
+        for pair in message.split(|&byte| byte == b'&') {
+            let (key, value) = split_once(pair, b'=')?;
+            match key {
+                b"from" => from = Some(parse_number(value)?),
+                b"to" => to = Some(parse_number(value)?),
+                b"amount" => amount = Some(parse_number(value)?),
+                _ => return None,
+            }
+        }
+
+        Some(Transfer {
+            from: from?,
+            to: to?,
+            amount: amount?,
+        })
+    }
+
+    // message || MAC under a fixed zero IV, with the message
+    // "from=#{from}&tx_list=#{to}:#{amount}(;#{to}:#{amount})*". Like the challenge's server,
+    // transactions that don't parse are skipped rather than failing the whole batch.
+    pub fn process_transactions(&self, request: &[u8]) -> Option<Vec<Transfer>> {
+        if request.len() < BLOCK_SIZE {
+            return None;
+        }
+
+        let (message, mac) = request.split_at(request.len() - BLOCK_SIZE);
+        if cbc_mac(&self.key, &ZERO_IV, message) != mac {
+            return None;
+        }
+
+        let (from, transactions) = split_once(message.strip_prefix(b"from=")?, b'&')?;
+        let from = parse_number(from)?;
+        let transactions = transactions.strip_prefix(b"tx_list=")?;
+
+        let transfers = transactions
+            .split(|&byte| byte == b';')
+            .filter_map(|transaction| {
+                let (to, amount) = split_once(transaction, b':')?;
+                Some(Transfer {
+                    from,
+                    to: parse_number(to)?,
+                    amount: parse_number(amount)?,
+                })
+            })
+            .collect();
+
+        Some(transfers)
+    }
+}
+
+impl BankClient {
+    pub fn transfer(&self, to: u32, amount: u64, rng: &mut Rng) -> Vec<u8> {
+        let message = format!("from={}&to={}&amount={}", self.account, to, amount).into_bytes();
+        let iv = rng.bytes(BLOCK_SIZE);
+        let mac = cbc_mac(&self.key, &iv, &message);
+
+        [message, iv, mac.to_vec()].concat()
+    }
+
+    pub fn transactions(&self, transactions: &[(u32, u64)]) -> Vec<u8> {
+        let transactions: Vec<String> = transactions
+            .iter()
+            .map(|(to, amount)| format!("{}:{}", to, amount))
+            .collect();
+        let message =
+            format!("from={}&tx_list={}", self.account, transactions.join(";")).into_bytes();
+        let mac = cbc_mac(&self.key, &ZERO_IV, &message);
+
+        [message, mac.to_vec()].concat()
+    }
+}
+
+fn split_once(bytes: &[u8], separator: u8) -> Option<(&[u8], &[u8])> {
+    let index = bytes.iter().position(|&byte| byte == separator)?;
+    Some((&bytes[..index], &bytes[index + 1..]))
+}
+
+fn parse_number<T: std::str::FromStr>(bytes: &[u8]) -> Option<T> {
+    std::str::from_utf8(bytes).ok()?.parse().ok()
+}
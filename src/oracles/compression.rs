@@ -0,0 +1,57 @@
+use crate::ciphers::aes::BLOCK_SIZE;
+use crate::ciphers::cbc::cbc_encrypt;
+use crate::ciphers::ctr::ctr;
+use crate::compression::deflate::compress;
+use crate::random::Rng;
+
+// Something that reveals only the length of an encrypted request carrying our plaintext.
+pub trait CompressionLengthOracle {
+    fn encrypted_length(&mut self, plaintext: &[u8]) -> usize;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompressionCipher {
+    Ctr,
+    Cbc,
+}
+
+// Challenge 51: formats a request around the plaintext, compresses it, then encrypts it
+// under a fresh key and nonce or IV every time.
+pub struct CompressionServer {
+    session_id: String,
+    cipher: CompressionCipher,
+    rng: Rng,
+}
+
+impl CompressionServer {
+    pub fn new(session_id: &str, cipher: CompressionCipher, rng: Rng) -> Self {
+        CompressionServer {
+            session_id: session_id.to_string(),
+            cipher,
+            rng,
+        }
+    }
+
+    pub fn format_request(&self, plaintext: &[u8]) -> Vec<u8> {
+        let headers = format!(
+            "POST / HTTP/1.1\nHost: hapless.com\nCookie: sessionid={}\nContent-Length: {}\n",
+            self.session_id,
+            plaintext.len()
+        );
+
+        [headers.as_bytes(), plaintext].concat()
+    }
+}
+
+impl CompressionLengthOracle for CompressionServer {
+    fn encrypted_length(&mut self, plaintext: &[u8]) -> usize {
+        let compressed = compress(&self.format_request(plaintext));
+        let key = self.rng.bytes(BLOCK_SIZE);
+
+        let encrypted = match self.cipher {
+            CompressionCipher::Ctr => ctr(&key, self.rng.next_u64(), &compressed),
+            CompressionCipher::Cbc => cbc_encrypt(&key, &self.rng.bytes(BLOCK_SIZE), &compressed),
+        };
+        encrypted.len()
+    }
+}
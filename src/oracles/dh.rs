@@ -0,0 +1,36 @@
+use crate::mac::hmac_sha256;
+use crate::math::bigint::BigUint;
+use crate::protocols::dh::{DhGroup, DhKeypair};
+use crate::random::Rng;
+
+pub const MESSAGE: &[u8] = b"crazy flamboyant for the rap enjoyment";
+
+// The MAC Bob sends back, keyed by the shared secret.
+pub fn shared_secret_mac(shared_secret: &BigUint, message: &[u8]) -> [u8; 32] {
+    hmac_sha256(&shared_secret.to_bytes_be(), message)
+}
+
+// Challenge 57's Bob: answers any public key with a fixed message and its MAC under the shared
+// secret, without checking that the key lies in the subgroup generated by g.
+pub struct DhMacServer {
+    group: DhGroup,
+    keypair: DhKeypair,
+}
+
+impl DhMacServer {
+    // The private key is drawn from below the order of g.
+    pub fn new(group: DhGroup, order: &BigUint, rng: &mut Rng) -> Self {
+        let keypair =
+            group.keypair_from_private(BigUint::random_range(rng, &BigUint::one(), order));
+        DhMacServer { group, keypair }
+    }
+
+    pub fn public_key(&self) -> &BigUint {
+        &self.keypair.public_key
+    }
+
+    pub fn respond(&self, public_key: &BigUint) -> (Vec<u8>, [u8; 32]) {
+        let shared_secret = self.group.shared_secret(&self.keypair, public_key);
+        (MESSAGE.to_vec(), shared_secret_mac(&shared_secret, MESSAGE))
+    }
+}
@@ -0,0 +1,109 @@
+use crate::ec::montgomery::{MontgomeryGroup, MontgomeryKeypair};
+use crate::ec::weierstrass::{EcKeypair, Point, WeierstrassGroup};
+use crate::mac::hmac_sha256;
+use crate::math::bigint::BigUint;
+use crate::oracles::dh::{shared_secret_mac, MESSAGE};
+use crate::pubkey::ecdsa::{self, EcdsaSignature};
+use crate::random::Rng;
+
+// Bob's MAC for a shared point, keyed by its full encoding: both coordinates, so k and -k
+// give different MACs.
+pub fn shared_point_mac(group: &WeierstrassGroup, point: &Point, message: &[u8]) -> [u8; 32] {
+    hmac_sha256(&group.curve.encode(point), message)
+}
+
+// Challenge 59's Bob: ECDH on the full point, without checking that the point he's sent is
+// on his curve.
+pub struct EcdhMacServer {
+    group: WeierstrassGroup,
+    keypair: EcKeypair,
+}
+
+impl EcdhMacServer {
+    pub fn new(group: WeierstrassGroup, rng: &mut Rng) -> Self {
+        let keypair = group.generate_keypair(rng);
+        EcdhMacServer { group, keypair }
+    }
+
+    pub fn public_key(&self) -> &Point {
+        &self.keypair.public_key
+    }
+
+    pub fn respond(&self, public_key: &Point) -> (Vec<u8>, [u8; 32]) {
+        let shared_secret = self.group.shared_secret(&self.keypair, public_key);
+        (
+            MESSAGE.to_vec(),
+            shared_point_mac(&self.group, &shared_secret, MESSAGE),
+        )
+    }
+}
+
+// Challenge 60's Bob: the Montgomery ladder on a bare u coordinate, which he can't check
+// without computing a Legendre symbol, and doesn't. The MAC is keyed by u, as in the
+// finite-field version.
+pub struct LadderMacServer {
+    group: MontgomeryGroup,
+    keypair: MontgomeryKeypair,
+}
+
+impl LadderMacServer {
+    pub fn new(group: MontgomeryGroup, rng: &mut Rng) -> Self {
+        let keypair = group.generate_keypair(rng);
+        LadderMacServer { group, keypair }
+    }
+
+    pub fn with_private_key(group: MontgomeryGroup, private_key: BigUint) -> Self {
+        let keypair = group.keypair_from_private(private_key);
+        LadderMacServer { group, keypair }
+    }
+
+    pub fn public_key(&self) -> &BigUint {
+        &self.keypair.public_key
+    }
+
+    pub fn respond(&self, public_key: &BigUint) -> (Vec<u8>, [u8; 32]) {
+        let shared_secret = self.group.shared_secret(&self.keypair, public_key);
+        (MESSAGE.to_vec(), shared_secret_mac(&shared_secret, MESSAGE))
+    }
+}
+
+// Challenge 62's signer: ordinary ECDSA, except that every nonce has its low `zero_bits` bits
+// cleared.
+pub struct BiasedEcdsaSigner {
+    group: WeierstrassGroup,
+    keypair: EcKeypair,
+    zero_bits: usize,
+}
+
+impl BiasedEcdsaSigner {
+    pub fn new(group: WeierstrassGroup, zero_bits: usize, rng: &mut Rng) -> Self {
+        let keypair = group.generate_keypair(rng);
+        BiasedEcdsaSigner {
+            group,
+            keypair,
+            zero_bits,
+        }
+    }
+
+    pub fn public_key(&self) -> &Point {
+        &self.keypair.public_key
+    }
+
+    pub fn sign(&self, message: &[u8], rng: &mut Rng) -> EcdsaSignature {
+        let hash = ecdsa::message_hash(message, &self.group.order);
+
+        loop {
+            let k = BigUint::random_below(rng, &self.group.order);
+            let k = (k >> self.zero_bits) << self.zero_bits;
+            if k.is_zero() {
+                continue;
+            }
+
+            let signature =
+                ecdsa::sign_with_nonce(&self.group, &self.keypair.private_key, &hash, &k);
+            if !signature.r.is_zero() && !signature.s.is_zero() {
+                return signature;
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::ciphers::gcm::{gcm_decrypt, gcm_encrypt, NONCE_SIZE};
+use crate::random::Rng;
+
+// Challenge 64: AES-GCM with the tag cut down to its first `tag_length` bytes. Encrypts
+// whatever it's given and tells you whether a (nonce, ciphertext, tag) triple verifies, but
+// never hands back a decryption.
+pub struct TruncatedGcmOracle {
+    key: Vec<u8>,
+    tag_length: usize,
+}
+
+impl TruncatedGcmOracle {
+    pub fn new(tag_length: usize, rng: &mut Rng) -> Self {
+        TruncatedGcmOracle::with_key(&rng.bytes(16), tag_length)
+    }
+
+    pub fn with_key(key: &[u8], tag_length: usize) -> Self {
+        TruncatedGcmOracle {
+            key: key.to_vec(),
+            tag_length,
+        }
+    }
+
+    pub fn tag_length(&self) -> usize {
+        self.tag_length
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8], rng: &mut Rng) -> ([u8; NONCE_SIZE], Vec<u8>, Vec<u8>) {
+        let mut nonce = [0; NONCE_SIZE];
+        rng.fill_bytes(&mut nonce);
+
+        let (ciphertext, tag) = gcm_encrypt(&self.key, &nonce, &[], plaintext);
+        (nonce, ciphertext, tag[..self.tag_length].to_vec())
+    }
+
+    pub fn is_valid(&self, nonce: &[u8; NONCE_SIZE], ciphertext: &[u8], tag: &[u8]) -> bool {
+        tag.len() == self.tag_length
+            && gcm_decrypt(&self.key, nonce, &[], ciphertext, tag).is_some()
+    }
+}
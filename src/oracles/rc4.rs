@@ -0,0 +1,25 @@
+use crate::ciphers::rc4::Rc4;
+use crate::random::Rng;
+
+// Challenge 56: encrypts request || cookie under a fresh random 128-bit RC4 key every time.
+// Holds no mutable state, so it can be shared between threads that each bring their own Rng.
+pub struct Rc4CookieServer {
+    cookie: Vec<u8>,
+}
+
+impl Rc4CookieServer {
+    pub fn new(cookie: &[u8]) -> Self {
+        Rc4CookieServer {
+            cookie: cookie.to_vec(),
+        }
+    }
+
+    pub fn encrypt(&self, request: &[u8], rng: &mut Rng) -> Vec<u8> {
+        let mut key = [0; 16];
+        rng.fill_bytes(&mut key);
+
+        let mut ciphertext = [request, &self.cookie].concat();
+        Rc4::new(&key).apply_keystream(&mut ciphertext);
+        ciphertext
+    }
+}
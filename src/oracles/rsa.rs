@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+
+use crate::hash::sha256;
+use crate::math::bigint::BigUint;
+use crate::pubkey::rsa::{RsaPrivateKey, RsaPublicKey};
+
+// Something that will decrypt RSA ciphertexts under a known public key, but may refuse.
+pub trait RsaDecryptionOracle {
+    fn public_key(&self) -> &RsaPublicKey;
+    fn decrypt(&mut self, ciphertext: &BigUint) -> Option<BigUint>;
+}
+
+// Something that leaks whether the plaintext of an RSA ciphertext is even.
+pub trait RsaParityOracle {
+    fn public_key(&self) -> &RsaPublicKey;
+    fn is_plaintext_even(&mut self, ciphertext: &BigUint) -> bool;
+}
+
+// Something that leaks whether an RSA ciphertext decrypts to PKCS#1 v1.5 conforming
+// plaintext, i.e. one starting with 00 02.
+pub trait RsaPaddingOracle {
+    fn public_key(&self) -> &RsaPublicKey;
+    fn is_padding_conforming(&mut self, ciphertext: &BigUint) -> bool;
+}
+
+// Challenge 41: decrypts any ciphertext, but only once, remembering the hash of each one.
+pub struct UnpaddedDecryptionServer {
+    public_key: RsaPublicKey,
+    private_key: RsaPrivateKey,
+    seen: HashSet<[u8; 32]>,
+}
+
+impl UnpaddedDecryptionServer {
+    pub fn new(public_key: RsaPublicKey, private_key: RsaPrivateKey) -> Self {
+        UnpaddedDecryptionServer {
+            public_key,
+            private_key,
+            seen: HashSet::new(),
+        }
+    }
+}
+
+impl RsaDecryptionOracle for UnpaddedDecryptionServer {
+    fn public_key(&self) -> &RsaPublicKey {
+        &self.public_key
+    }
+
+    fn decrypt(&mut self, ciphertext: &BigUint) -> Option<BigUint> {
+        if self.seen.insert(sha256(&ciphertext.to_bytes_be())) {
+            Some(self.private_key.decrypt(ciphertext))
+        } else {
+            None
+        }
+    }
+}
+
+// Challenge 46: decrypts and reports only the lowest bit of the plaintext.
+pub struct ParityServer {
+    public_key: RsaPublicKey,
+    private_key: RsaPrivateKey,
+}
+
+impl ParityServer {
+    pub fn new(public_key: RsaPublicKey, private_key: RsaPrivateKey) -> Self {
+        ParityServer {
+            public_key,
+            private_key,
+        }
+    }
+}
+
+impl RsaParityOracle for ParityServer {
+    fn public_key(&self) -> &RsaPublicKey {
+        &self.public_key
+    }
+
+    fn is_plaintext_even(&mut self, ciphertext: &BigUint) -> bool {
+        self.private_key.decrypt(ciphertext).is_even()
+    }
+}
+
+// Challenges 47 and 48: decrypts and checks only the leading 00 02 bytes.
+pub struct Pkcs1PaddingServer {
+    public_key: RsaPublicKey,
+    private_key: RsaPrivateKey,
+}
+
+impl Pkcs1PaddingServer {
+    pub fn new(public_key: RsaPublicKey, private_key: RsaPrivateKey) -> Self {
+        Pkcs1PaddingServer {
+            public_key,
+            private_key,
+        }
+    }
+}
+
+impl RsaPaddingOracle for Pkcs1PaddingServer {
+    fn public_key(&self) -> &RsaPublicKey {
+        &self.public_key
+    }
+
+    fn is_padding_conforming(&mut self, ciphertext: &BigUint) -> bool {
+        let block = self
+            .private_key
+            .decrypt(ciphertext)
+            .to_bytes_be_padded(self.public_key.size());
+        block[0] == 0x00 && block[1] == 0x02
+    }
+}
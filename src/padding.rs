@@ -0,0 +1,50 @@
+pub fn pkcs7_pad(bytes: &[u8], block_size: usize) -> Vec<u8> {
+    let padding_length = block_size - bytes.len() % block_size;
+
+    let mut result = bytes.to_vec();
+    result.extend(std::iter::repeat_n(padding_length as u8, padding_length));
+    result
+}
+
+// Returns None unless the input ends in well-formed PKCS#7 padding.
+pub fn pkcs7_unpad(bytes: &[u8], block_size: usize) -> Option<Vec<u8>> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(block_size) {
+        return None;
+    }
+
+    let padding_length = *bytes.last()? as usize;
+    if padding_length == 0 || padding_length > block_size {
+        return None;
+    }
+
+    let (message, padding) = bytes.split_at(bytes.len() - padding_length);
+    if padding.iter().all(|byte| *byte as usize == padding_length) {
+        Some(message.to_vec())
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkcs7_pad() {
+        assert_eq!(
+            pkcs7_pad(b"YELLOW SUBMARINE", 20),
+            b"YELLOW SUBMARINE\x04\x04\x04\x04".to_vec()
+        );
+        assert_eq!(pkcs7_pad(b"", 4), vec![4, 4, 4, 4]);
+    }
+
+    #[test]
+    fn test_pkcs7_unpad() {
+        assert_eq!(
+            pkcs7_unpad(b"ICE ICE BABY\x04\x04\x04\x04", 16),
+            Some(b"ICE ICE BABY".to_vec())
+        );
+        assert_eq!(pkcs7_unpad(b"ICE ICE BABY\x05\x05\x05\x05", 16), None);
+        assert_eq!(pkcs7_unpad(b"ICE ICE BABY\x01\x02\x03\x04", 16), None);
+    }
+}
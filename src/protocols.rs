@@ -0,0 +1,3 @@
+pub mod channel;
+pub mod dh;
+pub mod srp;
@@ -0,0 +1,60 @@
+use std::sync::mpsc::{self, Receiver, Sender};
+
+// One side of a bidirectional in-process connection. Parties only ever talk through an
+// endpoint, so anything holding a matching pair of endpoints can sit between them.
+pub struct Endpoint<M> {
+    sender: Sender<M>,
+    receiver: Receiver<M>,
+}
+
+pub fn connect<M>() -> (Endpoint<M>, Endpoint<M>) {
+    let (sender_1, receiver_1) = mpsc::channel();
+    let (sender_2, receiver_2) = mpsc::channel();
+
+    let endpoint_1 = Endpoint {
+        sender: sender_1,
+        receiver: receiver_2,
+    };
+    let endpoint_2 = Endpoint {
+        sender: sender_2,
+        receiver: receiver_1,
+    };
+
+    (endpoint_1, endpoint_2)
+}
+
+impl<M> Endpoint<M> {
+    pub fn send(&self, message: M) {
+        self.sender.send(message).expect("Peer hung up");
+    }
+
+    // Returns None once the other side has hung up.
+    pub fn receive(&self) -> Option<M> {
+        self.receiver.recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_messages_cross_threads() {
+        let (local, remote) = connect();
+
+        let echo = thread::spawn(move || {
+            while let Some(message) = remote.receive() {
+                remote.send(message * 2);
+            }
+        });
+
+        for value in 0..10 {
+            local.send(value);
+            assert_eq!(local.receive(), Some(value * 2));
+        }
+
+        drop(local);
+        echo.join().unwrap();
+    }
+}
@@ -0,0 +1,243 @@
+use crate::ciphers::aes::BLOCK_SIZE;
+use crate::ciphers::cbc::{cbc_decrypt, cbc_encrypt};
+use crate::hash::sha1;
+use crate::math::bigint::BigUint;
+use crate::protocols::channel::Endpoint;
+use crate::random::Rng;
+
+// The 1536-bit MODP group from RFC 3526, as given in Challenge 33.
+const NIST_PRIME: &str = "ffffffffffffffffc90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b139b22514a08798e3404ddef9519b3cd3a431b302b0a6df25f14374fe1356d6d51c245e485b576625e7ec6f44c42e9a637ed6b0bff5cb6f406b7edee386bfb5a899fa5ae9f24117c4b1fe649286651ece45b3dc2007cb8a163bf0598da48361c55d39a69163fa8fd24cf5f83655d23dca3ad961c62f356208552bb9ed529077096966d670c354e4abc9804f1746c08ca237327ffffffffffffffff";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DhGroup {
+    pub p: BigUint,
+    pub g: BigUint,
+}
+
+#[derive(Clone, Debug)]
+pub struct DhKeypair {
+    pub private_key: BigUint,
+    pub public_key: BigUint,
+}
+
+impl DhGroup {
+    pub fn new(p: BigUint, g: BigUint) -> Self {
+        DhGroup { p, g }
+    }
+
+    pub fn nist() -> Self {
+        DhGroup::new(BigUint::from_hex(NIST_PRIME), BigUint::from(2u32))
+    }
+
+    pub fn generate_keypair(&self, rng: &mut Rng) -> DhKeypair {
+        let private_key = BigUint::random_range(rng, &BigUint::one(), &self.p);
+        self.keypair_from_private(private_key)
+    }
+
+    pub fn keypair_from_private(&self, private_key: BigUint) -> DhKeypair {
+        let public_key = self.g.modpow(&private_key, &self.p);
+        DhKeypair {
+            private_key,
+            public_key,
+        }
+    }
+
+    pub fn shared_secret(&self, keypair: &DhKeypair, other_public_key: &BigUint) -> BigUint {
+        other_public_key.modpow(&keypair.private_key, &self.p)
+    }
+}
+
+// AES-128 key derived from a shared secret: the first 16 bytes of SHA1(s).
+pub fn session_key(shared_secret: &BigUint) -> [u8; 16] {
+    let digest = sha1(&shared_secret.to_bytes_be());
+
+    let mut key = [0; 16];
+    key.copy_from_slice(&digest[..16]);
+    key
+}
+
+// AES-CBC under a session key, with the random IV appended to the ciphertext.
+pub fn encrypt_message(key: &[u8; 16], plaintext: &[u8], rng: &mut Rng) -> Vec<u8> {
+    let iv = rng.bytes(BLOCK_SIZE);
+    let mut result = cbc_encrypt(key, &iv, plaintext);
+    result.extend(iv);
+    result
+}
+
+pub fn decrypt_message(key: &[u8; 16], message: &[u8]) -> Option<Vec<u8>> {
+    if message.len() < BLOCK_SIZE {
+        return None;
+    }
+
+    let (ciphertext, iv) = message.split_at(message.len() - BLOCK_SIZE);
+    cbc_decrypt(key, iv, ciphertext)
+}
+
+#[derive(Clone, Debug)]
+pub enum DhMessage {
+    Negotiate { group: DhGroup, public_key: BigUint },
+    Propose(DhGroup),
+    Accept(DhGroup),
+    PublicKey(BigUint),
+    Encrypted(Vec<u8>),
+}
+
+// Initiates the exchange: A->B sends (p, g, A), B->A sends B. Returns the derived session key.
+pub fn alice(endpoint: &Endpoint<DhMessage>, group: &DhGroup, rng: &mut Rng) -> [u8; 16] {
+    let keypair = group.generate_keypair(rng);
+
+    endpoint.send(DhMessage::Negotiate {
+        group: group.clone(),
+        public_key: keypair.public_key.clone(),
+    });
+
+    match endpoint.receive() {
+        Some(DhMessage::PublicKey(other_public_key)) => {
+            session_key(&group.shared_secret(&keypair, &other_public_key))
+        }
+        message => panic!("Alice expected a public key, got {:?}", message),
+    }
+}
+
+// Responds to an exchange started by `alice`. Returns the derived session key.
+pub fn bob(endpoint: &Endpoint<DhMessage>, rng: &mut Rng) -> [u8; 16] {
+    match endpoint.receive() {
+        Some(DhMessage::Negotiate { group, public_key }) => {
+            let keypair = group.generate_keypair(rng);
+            endpoint.send(DhMessage::PublicKey(keypair.public_key.clone()));
+            session_key(&group.shared_secret(&keypair, &public_key))
+        }
+        message => panic!("Bob expected negotiation, got {:?}", message),
+    }
+}
+
+// Negotiated-group variant: A->B sends (p, g), B->A ACKs the group it will use, then the
+// public keys are swapped. Returns the derived session key.
+pub fn alice_negotiated(
+    endpoint: &Endpoint<DhMessage>,
+    group: &DhGroup,
+    rng: &mut Rng,
+) -> [u8; 16] {
+    endpoint.send(DhMessage::Propose(group.clone()));
+
+    let group = match endpoint.receive() {
+        Some(DhMessage::Accept(group)) => group,
+        message => panic!("Alice expected an ACK, got {:?}", message),
+    };
+
+    let keypair = group.generate_keypair(rng);
+    endpoint.send(DhMessage::PublicKey(keypair.public_key.clone()));
+
+    match endpoint.receive() {
+        Some(DhMessage::PublicKey(other_public_key)) => {
+            session_key(&group.shared_secret(&keypair, &other_public_key))
+        }
+        message => panic!("Alice expected a public key, got {:?}", message),
+    }
+}
+
+pub fn bob_negotiated(endpoint: &Endpoint<DhMessage>, rng: &mut Rng) -> [u8; 16] {
+    let group = match endpoint.receive() {
+        Some(DhMessage::Propose(group)) => group,
+        message => panic!("Bob expected a group proposal, got {:?}", message),
+    };
+    endpoint.send(DhMessage::Accept(group.clone()));
+
+    let other_public_key = match endpoint.receive() {
+        Some(DhMessage::PublicKey(public_key)) => public_key,
+        message => panic!("Bob expected a public key, got {:?}", message),
+    };
+
+    let keypair = group.generate_keypair(rng);
+    endpoint.send(DhMessage::PublicKey(keypair.public_key.clone()));
+
+    session_key(&group.shared_secret(&keypair, &other_public_key))
+}
+
+pub fn send_encrypted(
+    endpoint: &Endpoint<DhMessage>,
+    key: &[u8; 16],
+    plaintext: &[u8],
+    rng: &mut Rng,
+) {
+    endpoint.send(DhMessage::Encrypted(encrypt_message(key, plaintext, rng)));
+}
+
+pub fn receive_encrypted(endpoint: &Endpoint<DhMessage>, key: &[u8; 16]) -> Vec<u8> {
+    match endpoint.receive() {
+        Some(DhMessage::Encrypted(message)) => {
+            decrypt_message(key, &message).expect("Message did not decrypt")
+        }
+        message => panic!("Expected an encrypted message, got {:?}", message),
+    }
+}
+
+// Alice sends a message and returns Bob's echo of it.
+pub fn alice_echo(
+    endpoint: &Endpoint<DhMessage>,
+    key: &[u8; 16],
+    plaintext: &[u8],
+    rng: &mut Rng,
+) -> Vec<u8> {
+    send_encrypted(endpoint, key, plaintext, rng);
+    receive_encrypted(endpoint, key)
+}
+
+// Bob echoes one message back under a fresh IV and returns what he received.
+pub fn bob_echo(endpoint: &Endpoint<DhMessage>, key: &[u8; 16], rng: &mut Rng) -> Vec<u8> {
+    let plaintext = receive_encrypted(endpoint, key);
+    send_encrypted(endpoint, key, &plaintext, rng);
+    plaintext
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocols::channel::connect;
+    use std::thread;
+
+    #[test]
+    fn test_nist_prime_passes_fermat_test() {
+        let group = DhGroup::nist();
+        let exponent = &group.p - &BigUint::one();
+
+        assert_eq!(group.p.bits(), 1536);
+        assert!(BigUint::from(3u32).modpow(&exponent, &group.p).is_one());
+    }
+
+    #[test]
+    fn test_exchange_through_passive_relay() {
+        let (alice_endpoint, relay_alice) = connect();
+        let (relay_bob, bob_endpoint) = connect();
+
+        let alice_thread =
+            thread::spawn(move || alice(&alice_endpoint, &DhGroup::nist(), &mut Rng::new(1)));
+        let bob_thread = thread::spawn(move || bob(&bob_endpoint, &mut Rng::new(2)));
+
+        let negotiation = relay_alice.receive().unwrap();
+        relay_bob.send(negotiation);
+        let reply = relay_bob.receive().unwrap();
+        relay_alice.send(reply);
+
+        assert_eq!(alice_thread.join().unwrap(), bob_thread.join().unwrap());
+    }
+
+    #[test]
+    fn test_negotiated_echo() {
+        let (alice_endpoint, bob_endpoint) = connect();
+
+        let alice_thread = thread::spawn(move || {
+            let mut rng = Rng::new(3);
+            let key = alice_negotiated(&alice_endpoint, &DhGroup::nist(), &mut rng);
+            alice_echo(&alice_endpoint, &key, b"Hello, Bob", &mut rng)
+        });
+        let bob_thread = thread::spawn(move || {
+            let mut rng = Rng::new(4);
+            let key = bob_negotiated(&bob_endpoint, &mut rng);
+            bob_echo(&bob_endpoint, &key, &mut rng)
+        });
+
+        assert_eq!(alice_thread.join().unwrap(), b"Hello, Bob");
+        assert_eq!(bob_thread.join().unwrap(), b"Hello, Bob");
+    }
+}
@@ -0,0 +1,273 @@
+use crate::hash::sha256;
+use crate::mac::hmac_sha256;
+use crate::math::bigint::BigUint;
+use crate::protocols::channel::Endpoint;
+use crate::protocols::dh::DhGroup;
+use crate::random::Rng;
+
+#[derive(Clone, Debug)]
+pub struct SrpParameters {
+    pub n: BigUint,
+    pub g: BigUint,
+    pub k: BigUint,
+}
+
+impl SrpParameters {
+    // The NIST prime from Challenge 33 with g = 2 and k = 3.
+    pub fn nist() -> Self {
+        let group = DhGroup::nist();
+        SrpParameters {
+            n: group.p,
+            g: group.g,
+            k: BigUint::from(3u32),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum SrpMessage {
+    Hello {
+        email: String,
+        public_key: BigUint,
+    },
+    Challenge {
+        salt: Vec<u8>,
+        public_key: BigUint,
+    },
+    SimplifiedChallenge {
+        salt: Vec<u8>,
+        public_key: BigUint,
+        u: BigUint,
+    },
+    Proof(Vec<u8>),
+    Verdict(bool),
+}
+
+// x = SHA256(salt|password) as an integer.
+pub fn password_exponent(salt: &[u8], password: &str) -> BigUint {
+    BigUint::from_bytes_be(&sha256(&[salt, password.as_bytes()].concat()))
+}
+
+// u = SHA256(A|B) as an integer.
+pub fn scrambler(client_public_key: &BigUint, server_public_key: &BigUint) -> BigUint {
+    let bytes = [
+        client_public_key.to_bytes_be(),
+        server_public_key.to_bytes_be(),
+    ]
+    .concat();
+    BigUint::from_bytes_be(&sha256(&bytes))
+}
+
+// K = SHA256(S), and the client proves knowledge of K with HMAC-SHA256(K, salt).
+pub fn session_proof(shared_secret: &BigUint, salt: &[u8]) -> Vec<u8> {
+    let key = sha256(&shared_secret.to_bytes_be());
+    hmac_sha256(&key, salt).to_vec()
+}
+
+// Server side of SRP, holding only the salted verifier for a single user. The simplified
+// variant from Challenge 38 sends a random u and B = g^b instead of deriving u from A and B.
+pub struct SrpServer {
+    parameters: SrpParameters,
+    email: String,
+    salt: Vec<u8>,
+    verifier: BigUint,
+    simplified: bool,
+}
+
+impl SrpServer {
+    pub fn new(parameters: SrpParameters, email: &str, password: &str, rng: &mut Rng) -> Self {
+        let salt = rng.bytes(16);
+        let x = password_exponent(&salt, password);
+        let verifier = parameters.g.modpow(&x, &parameters.n);
+
+        SrpServer {
+            parameters,
+            email: email.to_string(),
+            salt,
+            verifier,
+            simplified: false,
+        }
+    }
+
+    pub fn simplified(
+        parameters: SrpParameters,
+        email: &str,
+        password: &str,
+        rng: &mut Rng,
+    ) -> Self {
+        SrpServer {
+            simplified: true,
+            ..SrpServer::new(parameters, email, password, rng)
+        }
+    }
+
+    // Handles login attempts until the client hangs up.
+    pub fn serve(&self, endpoint: &Endpoint<SrpMessage>, rng: &mut Rng) {
+        while let Some(message) = endpoint.receive() {
+            let (email, client_public_key) = match message {
+                SrpMessage::Hello { email, public_key } => (email, public_key),
+                _ => {
+                    endpoint.send(SrpMessage::Verdict(false));
+                    continue;
+                }
+            };
+
+            let shared_secret = self.respond(endpoint, &client_public_key, rng);
+
+            let accepted = match endpoint.receive() {
+                Some(SrpMessage::Proof(proof)) => {
+                    email == self.email && proof == session_proof(&shared_secret, &self.salt)
+                }
+                _ => false,
+            };
+            endpoint.send(SrpMessage::Verdict(accepted));
+        }
+    }
+
+    // Sends the server challenge and returns S.
+    fn respond(
+        &self,
+        endpoint: &Endpoint<SrpMessage>,
+        client_public_key: &BigUint,
+        rng: &mut Rng,
+    ) -> BigUint {
+        let SrpParameters { n, g, k } = &self.parameters;
+        let b = BigUint::random_below(rng, n);
+        let g_b = g.modpow(&b, n);
+
+        let u = if self.simplified {
+            let u = BigUint::random_bits(rng, 128);
+            endpoint.send(SrpMessage::SimplifiedChallenge {
+                salt: self.salt.clone(),
+                public_key: g_b,
+                u: u.clone(),
+            });
+            u
+        } else {
+            let server_public_key = &(&(k * &self.verifier) + &g_b) % n;
+            endpoint.send(SrpMessage::Challenge {
+                salt: self.salt.clone(),
+                public_key: server_public_key.clone(),
+            });
+            scrambler(client_public_key, &server_public_key)
+        };
+
+        let base = &(client_public_key * &self.verifier.modpow(&u, n)) % n;
+        base.modpow(&b, n)
+    }
+}
+
+fn finish_login(endpoint: &Endpoint<SrpMessage>, shared_secret: &BigUint, salt: &[u8]) -> bool {
+    endpoint.send(SrpMessage::Proof(session_proof(shared_secret, salt)));
+
+    match endpoint.receive() {
+        Some(SrpMessage::Verdict(accepted)) => accepted,
+        message => panic!("Expected a verdict, got {:?}", message),
+    }
+}
+
+pub fn login(
+    endpoint: &Endpoint<SrpMessage>,
+    parameters: &SrpParameters,
+    email: &str,
+    password: &str,
+    rng: &mut Rng,
+) -> bool {
+    let SrpParameters { n, g, k } = parameters;
+    let a = BigUint::random_below(rng, n);
+    let client_public_key = g.modpow(&a, n);
+
+    endpoint.send(SrpMessage::Hello {
+        email: email.to_string(),
+        public_key: client_public_key.clone(),
+    });
+
+    let (salt, server_public_key) = match endpoint.receive() {
+        Some(SrpMessage::Challenge { salt, public_key }) => (salt, public_key),
+        message => panic!("Expected a challenge, got {:?}", message),
+    };
+
+    let u = scrambler(&client_public_key, &server_public_key);
+    let x = password_exponent(&salt, password);
+
+    // S = (B - k * g^x)^(a + u * x) mod N, kept non-negative by adding N before subtracting
+    let k_g_x = &(k * &g.modpow(&x, n)) % n;
+    let base = &(&(&server_public_key % n) + n) - &k_g_x;
+    let shared_secret = base.modpow(&(&a + &(&u * &x)), n);
+
+    finish_login(endpoint, &shared_secret, &salt)
+}
+
+pub fn simplified_login(
+    endpoint: &Endpoint<SrpMessage>,
+    parameters: &SrpParameters,
+    email: &str,
+    password: &str,
+    rng: &mut Rng,
+) -> bool {
+    let SrpParameters { n, g, .. } = parameters;
+    let a = BigUint::random_below(rng, n);
+
+    endpoint.send(SrpMessage::Hello {
+        email: email.to_string(),
+        public_key: g.modpow(&a, n),
+    });
+
+    let (salt, server_public_key, u) = match endpoint.receive() {
+        Some(SrpMessage::SimplifiedChallenge {
+            salt,
+            public_key,
+            u,
+        }) => (salt, public_key, u),
+        message => panic!("Expected a simplified challenge, got {:?}", message),
+    };
+
+    // S = B^(a + u * x) mod N
+    let x = password_exponent(&salt, password);
+    let shared_secret = server_public_key.modpow(&(&a + &(&u * &x)), n);
+
+    finish_login(endpoint, &shared_secret, &salt)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocols::channel::connect;
+    use std::thread;
+
+    #[test]
+    fn test_wrong_email_is_rejected() {
+        let (client, server_endpoint) = connect();
+
+        let server = thread::spawn(move || {
+            let mut rng = Rng::new(1);
+            let server = SrpServer::new(
+                SrpParameters::nist(),
+                "alice@example.com",
+                "hunter2",
+                &mut rng,
+            );
+            server.serve(&server_endpoint, &mut rng);
+        });
+
+        let mut rng = Rng::new(2);
+        let parameters = SrpParameters::nist();
+        assert!(!login(
+            &client,
+            &parameters,
+            "mallory@example.com",
+            "hunter2",
+            &mut rng
+        ));
+        assert!(login(
+            &client,
+            &parameters,
+            "alice@example.com",
+            "hunter2",
+            &mut rng
+        ));
+
+        drop(client);
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,4 @@
+pub mod dsa;
+pub mod ecdsa;
+pub mod pkcs1;
+pub mod rsa;
@@ -0,0 +1,128 @@
+use crate::hash::sha1;
+use crate::math::bigint::BigUint;
+use crate::random::Rng;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DsaParameters {
+    pub p: BigUint,
+    pub q: BigUint,
+    pub g: BigUint,
+}
+
+#[derive(Clone, Debug)]
+pub struct DsaKeypair {
+    pub private_key: BigUint,
+    pub public_key: BigUint,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DsaSignature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+impl DsaParameters {
+    // The parameters given in Challenge 43.
+    pub fn challenge_43() -> Self {
+        DsaParameters {
+            p: BigUint::from_hex("800000000000000089e1855218a0e7dac38136ffafa72eda7859f2171e25e65eac698c1702578b07dc2a1076da241c76c62d374d8389ea5aeffd3226a0530cc565f3bf6b50929139ebeac04f48c3c84afb796d61e5a4f9a8fda812ab59494232c7d2b4deb50aa18ee9e132bfa85ac4374d7f9091abc3d015efc871a584471bb1"),
+            q: BigUint::from_hex("f4f47f05794b256174bba6e9b396a7707e563c5b"),
+            g: BigUint::from_hex("5958c9d3898b224b12672c0b98e06c60df923cb8bc999d119458fef538b8fa4046c8db53039db620c094c9fa077ef389b5322a559946a71903f990f1f7e0e025e2d7f7cf494aff1a0470f5b64c36b625a097f1651fe775323556fe00b3608c887892878480e99041be601a62166ca6894bdd41a7054ec89f756ba9fc95302291"),
+        }
+    }
+
+    pub fn with_generator(&self, g: BigUint) -> Self {
+        DsaParameters { g, ..self.clone() }
+    }
+
+    pub fn generate_keypair(&self, rng: &mut Rng) -> DsaKeypair {
+        self.keypair_from_private(BigUint::random_range(rng, &BigUint::one(), &self.q))
+    }
+
+    pub fn keypair_from_private(&self, private_key: BigUint) -> DsaKeypair {
+        let public_key = self.g.modpow(&private_key, &self.p);
+        DsaKeypair {
+            private_key,
+            public_key,
+        }
+    }
+
+    // Textbook signing with an explicit nonce. Deliberately no retry when r or s is zero,
+    // which the tampered generators of Challenge 45 rely on.
+    pub fn sign_with_nonce(
+        &self,
+        private_key: &BigUint,
+        hash: &BigUint,
+        k: &BigUint,
+    ) -> DsaSignature {
+        let r = &self.g.modpow(k, &self.p) % &self.q;
+        let k_inverse = k.modinv(&self.q).expect("Nonce must be invertible mod q");
+        let s = &(&k_inverse * &(hash + &(private_key * &r))) % &self.q;
+
+        DsaSignature { r, s }
+    }
+
+    pub fn sign(&self, keypair: &DsaKeypair, message: &[u8], rng: &mut Rng) -> DsaSignature {
+        let k = BigUint::random_range(rng, &BigUint::one(), &self.q);
+        self.sign_with_nonce(&keypair.private_key, &message_hash(message), &k)
+    }
+
+    pub fn verify(&self, public_key: &BigUint, message: &[u8], signature: &DsaSignature) -> bool {
+        let DsaSignature { r, s } = signature;
+        if r.is_zero() || r >= &self.q || s.is_zero() || s >= &self.q {
+            return false;
+        }
+
+        self.verify_unchecked(public_key, message, signature)
+    }
+
+    // Verification without the 0 < r, s < q range checks, as Challenge 45 asks for.
+    pub fn verify_unchecked(
+        &self,
+        public_key: &BigUint,
+        message: &[u8],
+        signature: &DsaSignature,
+    ) -> bool {
+        let DsaParameters { p, q, g } = self;
+        let w = match signature.s.modinv(q) {
+            Some(w) => w,
+            None => return false,
+        };
+
+        let u1 = &(&message_hash(message) * &w) % q;
+        let u2 = &(&signature.r * &w) % q;
+        let v = &(&(&g.modpow(&u1, p) * &public_key.modpow(&u2, p)) % p) % q;
+
+        v == signature.r
+    }
+}
+
+// H(m) as an integer; SHA-1 output already fits below the 160-bit q.
+pub fn message_hash(message: &[u8]) -> BigUint {
+    BigUint::from_bytes_be(&sha1(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parameters_are_consistent() {
+        let parameters = DsaParameters::challenge_43();
+        let p_minus_one = &parameters.p - &BigUint::one();
+
+        assert!((&p_minus_one % &parameters.q).is_zero());
+        assert!(parameters.g.modpow(&parameters.q, &parameters.p).is_one());
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let mut rng = Rng::new(43);
+        let parameters = DsaParameters::challenge_43();
+        let keypair = parameters.generate_keypair(&mut rng);
+
+        let signature = parameters.sign(&keypair, b"hi mom", &mut rng);
+        assert!(parameters.verify(&keypair.public_key, b"hi mom", &signature));
+        assert!(!parameters.verify(&keypair.public_key, b"hi dad", &signature));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::ec::weierstrass::{EcKeypair, Point, WeierstrassGroup};
+use crate::hash::sha256;
+use crate::math::bigint::BigUint;
+use crate::random::Rng;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EcdsaSignature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+// H(m) as an integer: SHA-256 cut down to its leftmost bits, as many as the group order has.
+pub fn message_hash(message: &[u8], order: &BigUint) -> BigUint {
+    let hash = BigUint::from_bytes_be(&sha256(message));
+    hash >> 256usize.saturating_sub(order.bits())
+}
+
+// r = x(kG) mod n, s = (H(m) + dr) / k mod n. No retry when r or s is zero.
+pub fn sign_with_nonce(
+    group: &WeierstrassGroup,
+    private_key: &BigUint,
+    hash: &BigUint,
+    k: &BigUint,
+) -> EcdsaSignature {
+    let n = &group.order;
+    let r = match group.curve.scalar_multiply(&group.generator, k) {
+        Point::Infinity => BigUint::zero(),
+        Point::Affine { x, .. } => &x % n,
+    };
+    let k_inverse = k.modinv(n).expect("Nonce must be invertible mod n");
+    let s = &(&k_inverse * &(hash + &(private_key * &r))) % n;
+
+    EcdsaSignature { r, s }
+}
+
+pub fn sign(
+    group: &WeierstrassGroup,
+    keypair: &EcKeypair,
+    message: &[u8],
+    rng: &mut Rng,
+) -> EcdsaSignature {
+    let hash = message_hash(message, &group.order);
+
+    loop {
+        let k = BigUint::random_range(rng, &BigUint::one(), &group.order);
+        let signature = sign_with_nonce(group, &keypair.private_key, &hash, &k);
+        if !signature.r.is_zero() && !signature.s.is_zero() {
+            return signature;
+        }
+    }
+}
+
+// Checks that x(H(m)/s G + r/s Q) = r. The generator comes from the group, and nothing ties it
+// to the public key, which is what Challenge 61 takes advantage of.
+pub fn verify(
+    group: &WeierstrassGroup,
+    public_key: &Point,
+    message: &[u8],
+    signature: &EcdsaSignature,
+) -> bool {
+    let n = &group.order;
+    let EcdsaSignature { r, s } = signature;
+    if r.is_zero() || r >= n || s.is_zero() || s >= n {
+        return false;
+    }
+
+    let w = match s.modinv(n) {
+        Some(w) => w,
+        None => return false,
+    };
+    let u1 = &(&message_hash(message, n) * &w) % n;
+    let u2 = &(r * &w) % n;
+
+    let curve = &group.curve;
+    let point = curve.add(
+        &curve.scalar_multiply(&group.generator, &u1),
+        &curve.scalar_multiply(public_key, &u2),
+    );
+    match point {
+        Point::Infinity => false,
+        Point::Affine { x, .. } => &(&x % n) == r,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::curves;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let mut rng = Rng::new(61);
+        let group = WeierstrassGroup::from_spec(&curves::CHALLENGE_59);
+        let keypair = group.generate_keypair(&mut rng);
+        let other = group.generate_keypair(&mut rng);
+
+        let signature = sign(&group, &keypair, b"hi mom", &mut rng);
+        assert!(verify(&group, &keypair.public_key, b"hi mom", &signature));
+        assert!(!verify(&group, &keypair.public_key, b"hi dad", &signature));
+        assert!(!verify(&group, &other.public_key, b"hi mom", &signature));
+    }
+
+    #[test]
+    fn test_message_hash_fits_order() {
+        let group = WeierstrassGroup::from_spec(&curves::CHALLENGE_59);
+        let hash = message_hash(b"hi mom", &group.order);
+
+        assert!(hash.bits() <= group.order.bits());
+        assert!(hash.bits() > group.order.bits() - 8);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::hash::sha1;
+use crate::math::bigint::BigUint;
+use crate::pubkey::rsa::{RsaPrivateKey, RsaPublicKey};
+use crate::random::Rng;
+
+// DER encoding of the DigestInfo header for SHA-1, which precedes the 20-byte hash.
+pub const SHA1_DIGEST_INFO: [u8; 15] = [
+    0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04, 0x14,
+];
+
+// 00 01 FF .. FF 00 DigestInfo HASH, filling `length` bytes.
+pub fn encode_signature(message: &[u8], length: usize) -> Vec<u8> {
+    let digest_info = [&SHA1_DIGEST_INFO[..], &sha1(message)].concat();
+    if length < digest_info.len() + 11 {
+        panic!("Key too small for a PKCS#1 v1.5 signature");
+    }
+
+    let mut block = vec![0x00, 0x01];
+    block.resize(length - digest_info.len() - 1, 0xff);
+    block.push(0x00);
+    block.extend(digest_info);
+    block
+}
+
+// 00 02 PS 00 M, where PS is at least 8 random non-zero bytes, filling `length` bytes.
+pub fn encode_encryption(message: &[u8], length: usize, rng: &mut Rng) -> Vec<u8> {
+    if message.len() + 11 > length {
+        panic!("Message too long for a PKCS#1 v1.5 block");
+    }
+
+    let mut block = vec![0x00, 0x02];
+    while block.len() < length - message.len() - 1 {
+        let byte = rng.next_u8();
+        if byte != 0 {
+            block.push(byte);
+        }
+    }
+    block.push(0x00);
+    block.extend_from_slice(message);
+    block
+}
+
+pub fn decode_encryption(block: &[u8]) -> Option<Vec<u8>> {
+    if block.len() < 11 || block[0] != 0x00 || block[1] != 0x02 {
+        return None;
+    }
+
+    let separator = block[2..].iter().position(|byte| *byte == 0)? + 2;
+    if separator < 10 {
+        return None;
+    }
+
+    Some(block[separator + 1..].to_vec())
+}
+
+pub fn encrypt(public_key: &RsaPublicKey, message: &[u8], rng: &mut Rng) -> BigUint {
+    public_key.encrypt_bytes(&encode_encryption(message, public_key.size(), rng))
+}
+
+pub fn decrypt(private_key: &RsaPrivateKey, ciphertext: &BigUint) -> Option<Vec<u8>> {
+    let block = private_key
+        .decrypt(ciphertext)
+        .to_bytes_be_padded(private_key.size());
+    decode_encryption(&block)
+}
+
+pub fn sign(private_key: &RsaPrivateKey, message: &[u8]) -> BigUint {
+    let block = encode_signature(message, private_key.size());
+    private_key.decrypt(&BigUint::from_bytes_be(&block))
+}
+
+// Rebuilds the whole block and compares, so nothing after the hash can hide.
+pub fn verify(public_key: &RsaPublicKey, message: &[u8], signature: &BigUint) -> bool {
+    if signature >= &public_key.n {
+        return false;
+    }
+
+    let block = public_key
+        .encrypt(signature)
+        .to_bytes_be_padded(public_key.size());
+    block == encode_signature(message, public_key.size())
+}
+
+// Deliberately broken in the way Challenge 42 describes: it walks the padding from the
+// left, finds the DigestInfo and hash, and never checks that they end the block, so any
+// trailing bytes are accepted.
+pub fn verify_sloppy(public_key: &RsaPublicKey, message: &[u8], signature: &BigUint) -> bool {
+    let block = public_key
+        .encrypt(signature)
+        .to_bytes_be_padded(public_key.size());
+
+    if block.len() < 3 || block[0] != 0x00 || block[1] != 0x01 {
+        return false;
+    }
+
+    let padding_length = block[2..].iter().take_while(|byte| **byte == 0xff).count();
+    if padding_length == 0 {
+        return false;
+    }
+
+    let rest = &block[2 + padding_length..];
+    let expected = [&[0x00], &SHA1_DIGEST_INFO[..], &sha1(message)].concat();
+
+    rest.starts_with(&expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pubkey::rsa::generate_keypair;
+
+    #[test]
+    fn test_encryption_roundtrip() {
+        let mut rng = Rng::new(47);
+        let (public_key, private_key) = generate_keypair(&mut rng, 512, &BigUint::from(65537u32));
+
+        let ciphertext = encrypt(&public_key, b"kick it, CC", &mut rng);
+        assert_eq!(
+            decrypt(&private_key, &ciphertext),
+            Some(b"kick it, CC".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_signature_roundtrip() {
+        let mut rng = Rng::new(42);
+        let (public_key, private_key) = generate_keypair(&mut rng, 1024, &BigUint::from(3u32));
+
+        let signature = sign(&private_key, b"hi mom");
+        assert!(verify_sloppy(&public_key, b"hi mom", &signature));
+        assert!(!verify_sloppy(&public_key, b"hi dad", &signature));
+        assert!(verify(&public_key, b"hi mom", &signature));
+        assert!(!verify(&public_key, b"hi dad", &signature));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::math::bigint::BigUint;
+use crate::math::number_theory::random_prime;
+use crate::random::Rng;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RsaPublicKey {
+    pub e: BigUint,
+    pub n: BigUint,
+}
+
+#[derive(Clone, Debug)]
+pub struct RsaPrivateKey {
+    pub d: BigUint,
+    pub n: BigUint,
+    crt: Option<CrtParameters>,
+}
+
+// Precomputed values for decrypting modulo p and q separately, which is roughly four times
+// faster than a single exponentiation modulo n.
+#[derive(Clone, Debug)]
+struct CrtParameters {
+    p: BigUint,
+    q: BigUint,
+    d_p: BigUint,
+    d_q: BigUint,
+    q_inverse: BigUint,
+}
+
+impl RsaPublicKey {
+    pub fn encrypt(&self, message: &BigUint) -> BigUint {
+        message.modpow(&self.e, &self.n)
+    }
+
+    // Size of the modulus in bytes, i.e. the length of an encoded block.
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8)
+    }
+
+    pub fn encrypt_bytes(&self, message: &[u8]) -> BigUint {
+        self.encrypt(&BigUint::from_bytes_be(message))
+    }
+}
+
+impl RsaPrivateKey {
+    pub fn new(d: BigUint, n: BigUint) -> Self {
+        RsaPrivateKey { d, n, crt: None }
+    }
+
+    pub fn from_primes(p: BigUint, q: BigUint, d: BigUint) -> Self {
+        let one = BigUint::one();
+        let crt = CrtParameters {
+            d_p: &d % &(&p - &one),
+            d_q: &d % &(&q - &one),
+            q_inverse: q.modinv(&p).expect("Primes must be distinct"),
+            p,
+            q,
+        };
+
+        RsaPrivateKey {
+            n: &crt.p * &crt.q,
+            d,
+            crt: Some(crt),
+        }
+    }
+
+    pub fn decrypt(&self, ciphertext: &BigUint) -> BigUint {
+        let crt = match &self.crt {
+            Some(crt) => crt,
+            None => return ciphertext.modpow(&self.d, &self.n),
+        };
+
+        // Garner's recombination: m = m_q + q * ((m_p - m_q) * q^-1 mod p)
+        let m_p = ciphertext.modpow(&crt.d_p, &crt.p);
+        let m_q = ciphertext.modpow(&crt.d_q, &crt.q);
+        let difference = &(&(&m_p + &crt.p) - &(&m_q % &crt.p)) % &crt.p;
+        let h = &(&difference * &crt.q_inverse) % &crt.p;
+
+        &m_q + &(&h * &crt.q)
+    }
+
+    pub fn size(&self) -> usize {
+        self.n.bits().div_ceil(8)
+    }
+
+    pub fn decrypt_bytes(&self, ciphertext: &BigUint) -> Vec<u8> {
+        self.decrypt(ciphertext).to_bytes_be()
+    }
+}
+
+// Textbook RSA key pair with a `bits`-bit modulus. Primes are redrawn until e is invertible
+// mod (p - 1)(q - 1), which matters for small exponents like e = 3.
+pub fn generate_keypair(rng: &mut Rng, bits: usize, e: &BigUint) -> (RsaPublicKey, RsaPrivateKey) {
+    let one = BigUint::one();
+
+    loop {
+        let p = random_prime(rng, bits / 2);
+        let q = random_prime(rng, bits - bits / 2);
+        if p == q {
+            continue;
+        }
+
+        let totient = &(&p - &one) * &(&q - &one);
+        if let Some(d) = e.modinv(&totient) {
+            let private_key = RsaPrivateKey::from_primes(p, q, d);
+            let public_key = RsaPublicKey {
+                e: e.clone(),
+                n: private_key.n.clone(),
+            };
+            return (public_key, private_key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keypair_roundtrip() {
+        let mut rng = Rng::new(39);
+        let (public_key, private_key) = generate_keypair(&mut rng, 512, &BigUint::from(65537u32));
+
+        assert_eq!(public_key.n.bits(), 512);
+        assert_eq!(public_key.size(), 64);
+
+        let message = b"Textbook RSA, no padding";
+        let ciphertext = public_key.encrypt_bytes(message);
+        assert_eq!(private_key.decrypt_bytes(&ciphertext), message);
+    }
+
+    #[test]
+    fn test_crt_matches_plain_decryption() {
+        let mut rng = Rng::new(40);
+        let (public_key, private_key) = generate_keypair(&mut rng, 512, &BigUint::from(3u32));
+        let plain_key = RsaPrivateKey::new(private_key.d.clone(), private_key.n.clone());
+
+        for _ in 0..20 {
+            let ciphertext = BigUint::random_below(&mut rng, &public_key.n);
+            assert_eq!(
+                private_key.decrypt(&ciphertext),
+                plain_key.decrypt(&ciphertext)
+            );
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// xoshiro256** seeded through splitmix64. Not cryptographically secure, but the attacks
+// only need something fast, uniform and reproducible from a seed.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: [u64; 4],
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        let mut seed = seed;
+        let mut next_seed = || {
+            seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            z ^ (z >> 31)
+        };
+
+        Rng {
+            state: [next_seed(), next_seed(), next_seed(), next_seed()],
+        }
+    }
+
+    pub fn from_entropy() -> Self {
+        let mut hasher = RandomState::new().build_hasher();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        hasher.write_u128(nanos);
+
+        Rng::new(hasher.finish())
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+
+        result
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    pub fn next_u8(&mut self) -> u8 {
+        (self.next_u64() >> 56) as u8
+    }
+
+    // Uniform in 0..bound, using rejection sampling to avoid modulo bias.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        if bound == 0 {
+            panic!("Bound must be greater than zero");
+        }
+
+        let zone = u64::MAX - (u64::MAX % bound);
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    pub fn range(&mut self, low: u64, high: u64) -> u64 {
+        low + self.below(high - low)
+    }
+
+    pub fn fill_bytes(&mut self, buffer: &mut [u8]) {
+        for chunk in buffer.chunks_mut(8) {
+            let bytes = self.next_u64().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    pub fn bytes(&mut self, count: usize) -> Vec<u8> {
+        let mut result = vec![0; count];
+        self.fill_bytes(&mut result);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut rng1 = Rng::new(1234);
+        let mut rng2 = Rng::new(1234);
+
+        for _ in 0..100 {
+            assert_eq!(rng1.next_u64(), rng2.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_below_stays_in_bounds() {
+        let mut rng = Rng::new(42);
+
+        for bound in 1..200 {
+            assert!(rng.below(bound) < bound);
+        }
+    }
+}
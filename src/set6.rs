@@ -0,0 +1,391 @@
+use std::fs;
+
+use crate::attacks::bleichenbacher::bleichenbacher_attack;
+use crate::attacks::dsa::{self as dsa_attacks, SignedMessage};
+use crate::attacks::pkcs1_forgery::forge_pkcs1_signature;
+use crate::attacks::rsa_parity::parity_oracle_attack;
+use crate::attacks::rsa_unpadded::recover_unpadded_message;
+use crate::conversion::base64::base64_to_bytes;
+use crate::conversion::hex::bytes_to_hex_string;
+use crate::hash::sha1;
+use crate::math::bigint::BigUint;
+use crate::oracles::rsa::{
+    ParityServer, Pkcs1PaddingServer, RsaDecryptionOracle, RsaPaddingOracle,
+    UnpaddedDecryptionServer,
+};
+use crate::pubkey::dsa::{self, DsaParameters, DsaSignature};
+use crate::pubkey::{pkcs1, rsa};
+use crate::random::Rng;
+
+// Implement unpadded message recovery oracle
+// Nate Lawson says we should stop calling it "RSA padding" and start calling it "RSA armoring". Here's why.
+// Imagine a web application, again with the Javascript encryption, taking RSA-encrypted messages which (again: Javascript)
+// aren't padded before encryption at all.
+// You can submit an arbitrary RSA blob and the server will return plaintext. But you can't submit the same message
+// twice: let's say the server keeps hashes of previous messages for some liveness interval, and that the message has an
+// embedded timestamp:
+// {
+//   time: 1356304276,
+//   social: '555-55-5555',
+// }
+// You'd like to capture other people's messages and use the server to decrypt them. But when you try, the server takes
+// the hash of the ciphertext and uses it to reject the request. Any bit you flip in the ciphertext irrevocably scrambles
+// the decryption.
+// This turns out to be trivially breakable:
+//     Capture the ciphertext C
+//     Let N and E be the public modulus and exponent respectively
+//     Let S be a random number > 1 mod N. Doesn't matter what.
+//     Now:
+//     C' = ((S**E mod N) C) mod N
+//     Submit C', which appears totally different from C, to the server, recovering P', which appears totally different
+//     from P
+//     Now:
+//               P'
+//         P = -----  mod N
+//               S
+// Oops!
+// Implement that attack.
+#[test]
+pub fn challenge_41() {
+    let mut rng = Rng::new(41);
+    let (public_key, private_key) = rsa::generate_keypair(&mut rng, 1024, &BigUint::from(65537u32));
+    let mut server = UnpaddedDecryptionServer::new(public_key.clone(), private_key);
+
+    let message = b"{ time: 1356304276, social: '555-55-5555' }";
+    let ciphertext = public_key.encrypt_bytes(message);
+
+    // The victim's own request goes through; replaying it does not
+    assert!(server.decrypt(&ciphertext).is_some());
+    assert!(server.decrypt(&ciphertext).is_none());
+
+    let recovered = recover_unpadded_message(&mut server, &ciphertext, &mut rng).unwrap();
+
+    assert_eq!(recovered.to_bytes_be(), message);
+}
+
+// Bleichenbacher's e=3 RSA Attack
+// RSA with an encrypting exponent of 3 is popular, because it makes the RSA math faster.
+// With e=3 RSA, encryption is just cubing a number mod the public encryption modulus:
+//  c = m ** 3 % n
+// e=3 is secure as long as we can make assumptions about the message blocks we're encrypting. The worry with low-exponent
+// RSA is that the message blocks we process won't be large enough to wrap the modulus after being cubed. The block 00:02
+// (imagine sufficient zero-padding) can be "encrypted" in e=3 RSA; it is simply 00:08.
+// When RSA is used to sign, rather than encrypt, the operations are reversed; the verifier "decrypts" the message by
+// cubing it. This produces a "plaintext" which the verifier checks for validity.
+// When you use RSA to sign a message, you supply it a block input that contains a message digest. The PKCS1.5 standard
+// formats that block as:
+// 00h 01h ffh ffh ... ffh ffh 00h ASN.1 GOOP HASH
+// As intended, the ffh bytes in that block expand to fill the whole block, producing a "right-justified" hash (the last
+// byte of the hash is the last byte of the message).
+// There was, 7 years ago, a common implementation flaw with RSA verifiers: they'd verify signatures by "decrypting" them
+// (cubing them modulo the public exponent) and then "parsing" them by looking for 00h 01h ... ffh 00h ASN.1 HASH.
+// This is a bug because it implies the verifier isn't checking all the padding. If you don't check the padding, you
+// leave open the possibility that instead of hundreds of ffh bytes, you have only a few, which if you think about it
+// means there could be squizzilions of possible numbers that could produce a valid-looking signature.
+// How to find such a block? Find a number that when cubed (a) doesn't wrap the modulus (thus bypassing the key
+// entirely) and (b) produces a block that starts "00h 01h ffh ... 00h ASN.1 HASH".
+// There are two ways to approach this problem:
+//     You can work from Hal Finney's writeup, available on Google, of how Bleichenbacher explained the math "so that you
+//     can do it by hand with a pencil".
+//     You can implement an integer cube root in your language, format the message block you want to forge, leaving
+//     sufficient trailing zeros at the end to fill with garbage, then take the cube-root of that block.
+// Forge a 1024-bit RSA signature for the string "hi mom". Make sure your implementation actually accepts the signature!
+#[test]
+pub fn challenge_42() {
+    let mut rng = Rng::new(42);
+    let (public_key, private_key) = rsa::generate_keypair(&mut rng, 1024, &BigUint::from(3u32));
+
+    let genuine = pkcs1::sign(&private_key, b"hi mom");
+    assert!(pkcs1::verify_sloppy(&public_key, b"hi mom", &genuine));
+
+    let forged = forge_pkcs1_signature(&public_key, b"hi mom");
+
+    assert_ne!(forged, genuine);
+    assert!(pkcs1::verify_sloppy(&public_key, b"hi mom", &forged));
+    assert!(!pkcs1::verify_sloppy(&public_key, b"hi dad", &forged));
+}
+
+// DSA key recovery from nonce
+// Step 1: Relocate so that you are out of easy travel distance of us.
+// Step 2: Implement DSA, up to signing and verifying, including parameter generation.
+// Hah-hah you're too far away to come punch us.
+// Just kidding you can skip the parameter generation part if you want; if you do, use these params:
+//  p = 800000000000000089e1855218a0e7dac38136ffafa72eda7
+//      859f2171e25e65eac698c1702578b07dc2a1076da241c76c6
+//      2d374d8389ea5aeffd3226a0530cc565f3bf6b50929139ebe
+//      ac04f48c3c84afb796d61e5a4f9a8fda812ab59494232c7d2
+//      b4deb50aa18ee9e132bfa85ac4374d7f9091abc3d015efc87
+//      1a584471bb1
+//  q = f4f47f05794b256174bba6e9b396a7707e563c5b
+//  g = 5958c9d3898b224b12672c0b98e06c60df923cb8bc999d119
+//      458fef538b8fa4046c8db53039db620c094c9fa077ef389b5
+//      322a559946a71903f990f1f7e0e025e2d7f7cf494aff1a047
+//      0f5b64c36b625a097f1651fe775323556fe00b3608c887892
+//      878480e99041be601a62166ca6894bdd41a7054ec89f756ba
+//      9fc95302291
+// Given a signing key and the k used to generate the signature, the private key x is:
+//           (s * k) - H(msg)
+//       x = ----------------  mod q
+//                   r
+// Do this a couple times to prove to yourself that you grok it. Capture it in a function of some sort.
+// Now then. I used the parameters above. I generated a keypair. My pubkey is:
+//   y = 84ad4719d044495496a3201c8ff484feb45b962e7302e56a392aee4
+//       abab3e4bdebf2955b4736012f21a08084056b19bcd7fee56048e004
+//       e44984e2f411788efdc837a0d2e5abb7b555039fd243ac01f0fb2ed
+//       1dec568280ce678e931868d23eb095fde9d3779191b8c0299d6e07b
+//       bb283e6633451e535c45513b2d33c99ea17
+// I signed
+// For those that envy a MC it can be hazardous to your health
+// So be friendly, a matter of life and death, just like a etch-a-sketch
+// (My SHA1 for this string was d2d0714f014a9784047eaeccf956520045c45265; I don't know what NIST wants you to do, but
+// when I convert that hash to an integer I get: 0xd2d0714f014a9784047eaeccf956520045c45265).
+// I get:
+// r = 548099063082341131477253921760299949438196259240
+// s = 857042759984254168557880549501802188789837994940
+// I signed this string with a broken implemention of DSA that generated "k" values between 0 and 2^16. What's my
+// private key?
+// Its SHA-1 fingerprint (after being converted to hex) is:
+// 0954edd5e0afe5542a4adf012611a91912a3ec16
+// Obviously, it also generates the same signature for that string.
+#[test]
+pub fn challenge_43() {
+    let parameters = DsaParameters::challenge_43();
+    let message = b"For those that envy a MC it can be hazardous to your health\n\
+                    So be friendly, a matter of life and death, just like a etch-a-sketch\n";
+    let hash = dsa::message_hash(message);
+    assert_eq!(hash.to_hex(), "d2d0714f014a9784047eaeccf956520045c45265");
+
+    let public_key = BigUint::from_hex("84ad4719d044495496a3201c8ff484feb45b962e7302e56a392aee4abab3e4bdebf2955b4736012f21a08084056b19bcd7fee56048e004e44984e2f411788efdc837a0d2e5abb7b555039fd243ac01f0fb2ed1dec568280ce678e931868d23eb095fde9d3779191b8c0299d6e07bbb283e6633451e535c45513b2d33c99ea17");
+    let signature = DsaSignature {
+        r: BigUint::from_decimal("548099063082341131477253921760299949438196259240"),
+        s: BigUint::from_decimal("857042759984254168557880549501802188789837994940"),
+    };
+    assert!(parameters.verify(&public_key, message, &signature));
+
+    let private_key =
+        dsa_attacks::brute_force_nonce(&parameters, &public_key, &hash, &signature, 1 << 16)
+            .unwrap();
+
+    assert_eq!(
+        bytes_to_hex_string(&sha1(private_key.to_hex().as_bytes())),
+        "0954edd5e0afe5542a4adf012611a91912a3ec16"
+    );
+}
+
+// DSA nonce recovery from repeated nonce
+// In this file find a collection of DSA-signed messages. (NB: each msg has a trailing space.)
+// These were signed under the following pubkey:
+// y = 2d026f4bf30195ede3a088da85e398ef869611d0f68f07
+//     13d51c9c1a3a26c95105d915e2d8cdf26d056b86b8a7b8
+//     5519b1c23cc3ecdc6062650462e3063bd179c2a6581519
+//     f674a61f1d89a1fff27171ebc1b93d4dc57bceb7ae2430
+//     f98a6a4d83d8279ee65d71c1203d2c96d65ebbf7cce9d3
+//     2971c3de5084cce04a2e147821
+// (using the same domain parameters as the previous exercise)
+// It should not be hard to find the messages for which we have accidentally used a repeated "k". Given a pair of
+// such messages, you can discover the "k" we used with the following formula:
+//          (m1 - m2)
+//      k = --------- mod q
+//          (s1 - s2)
+// What's my private key? Its SHA-1 (from hex) is:
+// ca8f6f7c66fa362d40760d135b763eb8527d3d52
+#[test]
+#[ignore = "files/44.txt holds 6 of the published entries, none of which share a nonce"]
+pub fn challenge_44() {
+    let parameters = DsaParameters::challenge_43();
+    let contents = fs::read_to_string("files/44.txt").expect("the published Challenge 44 data");
+    let lines: Vec<&str> = contents.lines().collect();
+
+    let field = |line: &str, name: &str| line.strip_prefix(name).unwrap().to_string();
+    let messages: Vec<SignedMessage> = lines
+        .chunks(4)
+        .map(|entry| {
+            let message = field(entry[0], "msg: ").into_bytes();
+            let hash = BigUint::from_hex(&field(entry[3], "m: "));
+            assert_eq!(hash, dsa::message_hash(&message));
+
+            SignedMessage {
+                message,
+                hash,
+                signature: DsaSignature {
+                    s: BigUint::from_decimal(&field(entry[1], "s: ")),
+                    r: BigUint::from_decimal(&field(entry[2], "r: ")),
+                },
+            }
+        })
+        .collect();
+
+    let public_key = BigUint::from_hex("2d026f4bf30195ede3a088da85e398ef869611d0f68f0713d51c9c1a3a26c95105d915e2d8cdf26d056b86b8a7b85519b1c23cc3ecdc6062650462e3063bd179c2a6581519f674a61f1d89a1fff27171ebc1b93d4dc57bceb7ae2430f98a6a4d83d8279ee65d71c1203d2c96d65ebbf7cce9d32971c3de5084cce04a2e147821");
+    for signed in &messages {
+        assert!(parameters.verify(&public_key, &signed.message, &signed.signature));
+    }
+
+    let private_key =
+        dsa_attacks::repeated_nonce_key_recovery(&parameters, &public_key, &messages).unwrap();
+
+    assert_eq!(
+        bytes_to_hex_string(&sha1(private_key.to_hex().as_bytes())),
+        "ca8f6f7c66fa362d40760d135b763eb8527d3d52"
+    );
+}
+
+// DSA parameter tampering
+// Take your DSA code from the previous exercise. Imagine it as part of an algorithm in which the client was allowed
+// to propose domain parameters (the p and q moduli, and the g generator).
+// This would be bad, because attackers could trick victims into accepting bad parameters. Vaudenay gave two examples
+// of bad generator parameters: generators that were 0 mod p, and generators that were 1 mod p.
+// Use the parameters from the previous exercise, but substitute 0 for "g". Generate a signature. You will notice
+// something bad. Verify the signature. Now verify any other signature, for any other string.
+// Now, try (p+1) as "g". With this "g", you can generate a magic signature s, r for any DSA public key that will
+// validate against any string. For arbitrary z:
+//   r = ((y**z) % p) % q
+//         r
+//   s =  --- % q
+//         z
+// Sign "Hello, world". And "Goodbye, world".
+#[test]
+pub fn challenge_45() {
+    let mut rng = Rng::new(45);
+    let original = DsaParameters::challenge_43();
+    let keypair = original.generate_keypair(&mut rng);
+
+    // g = 0 makes r = 0 for every signature, so an unchecked verifier accepts it for anything
+    let zero = original.with_generator(BigUint::zero());
+    let signature = zero.sign(&keypair, b"Hello, world", &mut rng);
+    assert!(signature.r.is_zero());
+    assert!(zero.verify_unchecked(&keypair.public_key, b"Hello, world", &signature));
+    assert!(zero.verify_unchecked(&keypair.public_key, b"Goodbye, world", &signature));
+    assert!(!zero.verify(&keypair.public_key, b"Hello, world", &signature));
+
+    // g = p + 1 lets us forge without the private key, and the range checks don't help
+    let p_plus_one = original.with_generator(&original.p + &BigUint::one());
+    let magic =
+        dsa_attacks::magic_signature(&p_plus_one, &keypair.public_key, &BigUint::from(45u32));
+    assert!(p_plus_one.verify(&keypair.public_key, b"Hello, world", &magic));
+    assert!(p_plus_one.verify(&keypair.public_key, b"Goodbye, world", &magic));
+}
+
+// RSA parity oracle
+// Generate a 1024 bit RSA key pair.
+// Write an oracle function that uses the private key to answer the question "is the plaintext of this message even or
+// odd" (is the last bit of the message 0 or 1). Imagine for instance a server that accepted RSA-encrypted messages and
+// checked the parity of their decryption to validate them, and spat out an error if they were of the wrong parity.
+// Anyways: function returning true or false based on whether the decrypted plaintext was even or odd, and nothing else.
+// Take the following string and un-Base64 it in your code (without looking at it!) and encrypt it to the public key,
+// creating a ciphertext:
+// VGhhdCdzIHdoeSBJIGZvdW5kIHlvdSBkb24ndCBwbGF5IGFyb3VuZCB3aXRoIHRoZSBGdW5reSBDb2xkIE1lZGluYQ==
+// With your oracle function, you can trivially decrypt the message.
+// Here's why:
+//     RSA ciphertexts are just numbers. You can do trivial math on them. You can for instance multiply a ciphertext by
+//     the RSA-encryption of another number; the corresponding plaintext will be the product of those two numbers.
+//     If you double a ciphertext (multiply it by (2**e)%n), the resulting plaintext will (obviously) be either even or
+//     odd.
+//     If the plaintext after doubling is even, doubling the plaintext didn't wrap the modulus --- the modulus is a prime
+//     number. That means the plaintext is less than half the modulus.
+// You can repeatedly apply this heuristic, once per bit of the message, checking your oracle function each time.
+// Your decryption function starts with bounds for the plaintext of [0,n].
+// Each iteration of the decryption cuts the bounds in half; either the upper bound is reduced by half, or the lower
+// bound is.
+// After log2(n) iterations, you have the decryption of the message.
+// Print the upper bound of the message as a string at each iteration; you'll see the message decrypt "hollywood style".
+// Decrypt the string (after encrypting it to a hidden private key) above.
+#[test]
+pub fn challenge_46() {
+    let mut rng = Rng::new(46);
+    let (public_key, private_key) = rsa::generate_keypair(&mut rng, 1024, &BigUint::from(65537u32));
+    let mut server = ParityServer::new(public_key.clone(), private_key);
+
+    let message = base64_to_bytes(
+        "VGhhdCdzIHdoeSBJIGZvdW5kIHlvdSBkb24ndCBwbGF5IGFyb3VuZCB3aXRoIHRoZSBGdW5reSBDb2xkIE1lZGluYQ==",
+    );
+    let ciphertext = public_key.encrypt_bytes(&message);
+
+    let mut iterations = 0;
+    let recovered = parity_oracle_attack(&mut server, &ciphertext, |upper_bound| {
+        iterations += 1;
+        println!("{}", String::from_utf8_lossy(&upper_bound.to_bytes_be()));
+    });
+
+    assert_eq!(iterations, 1024);
+    assert_eq!(recovered.to_bytes_be(), message);
+}
+
+fn padding_oracle_roundtrip(bits: usize, seed: u64) -> usize {
+    let mut rng = Rng::new(seed);
+    let (public_key, private_key) = rsa::generate_keypair(&mut rng, bits, &BigUint::from(3u32));
+    let mut server = Pkcs1PaddingServer::new(public_key.clone(), private_key);
+
+    let ciphertext = pkcs1::encrypt(&public_key, b"kick it, CC", &mut rng);
+    assert!(server.is_padding_conforming(&ciphertext));
+
+    let result = bleichenbacher_attack(&mut server, &ciphertext);
+    let block = result.plaintext.to_bytes_be_padded(public_key.size());
+    println!("{}-bit key: {} oracle calls", bits, result.oracle_calls);
+
+    assert_eq!(
+        pkcs1::decode_encryption(&block),
+        Some(b"kick it, CC".to_vec())
+    );
+    result.oracle_calls
+}
+
+// Bleichenbacher's PKCS 1.5 Padding Oracle (Simple Case)
+// Degree of difficulty: 10
+// This is the hardest challenge in this set; it's really easy to get a single comparison off by one, and very hard to
+// debug.
+// Let us now go break cryptography.
+// Build an oracle function, just like you did in the last exercise, but have it check for plaintext[0] == 0 and
+// plaintext[1] == 2.
+// Generate a 256 bit keypair (that is, p and q will each be 128 bit primes), [n, e, d].
+// Plug d and n into your oracle function.
+// PKCS1.5-pad a short message, like "kick it, CC", and call it "m". Encrypt to to get "c".
+// Decrypt "c" using your padding oracle.
+// For this challenge, we've used an untenably small RSA modulus (you could factor this keypair instantly). That's
+// because this exercise targets a specific step in the Bleichenbacher paper --- Step 2c, which implements a fast,
+// nearly O(log n) search for the plaintext.
+// Things you want to keep in mind as you read the paper:
+//     RSA ciphertexts are just numbers.
+//     RSA is "homomorphic" with respect to multiplication, which means you can multiply c * RSA(2) to get a c' that will
+//     decrypt to plaintext * 2. This is mindbending but easy to see if you play with it in code --- try multiplying
+//     ciphertexts with the RSA encryptions of numbers so you know you grok it.
+//     What you need to grok for this challenge is that Bleichenbacher uses multiplication on ciphertexts the way the
+//     CBC oracle uses XORs of random blocks.
+//     A PKCS#1v1.5 conformant plaintext, one that starts with 00:02, must be a number between 02:00:00...00 and
+//     02:FF:FF..FF --- in other words, 2B and 3B-1, where B is the bit size of the modulus minus the first 16 bits.
+//     When you see 2B and 3B, that's the idea the paper is playing with.
+// To decrypt "c", you'll need Step 2a from the paper (the search for the first "s" that, when encrypted and
+// multiplied with the ciphertext, produces a conformant plaintext), Step 2c, the fast O(log n) search, and Step 3.
+// Your Step 3 code is probably not going to need to handle multiple ranges.
+// We recommend you just use the raw math from paper (check, check, double check your translation to code) and not
+// spend too much time trying to grok how the math works.
+#[test]
+pub fn challenge_47() {
+    let oracle_calls = padding_oracle_roundtrip(256, 47);
+    assert!(oracle_calls > 0);
+}
+
+// Bleichenbacher's PKCS 1.5 Padding Oracle (Complete Case)
+// This is a continuation of challenge #47; it implements the complete BB'98 attack.
+// Set yourself up the way you did in #47, but this time generate a 768 bit modulus.
+// To make the attack work with a realistic RSA keypair, you need to reproduce step 2b from the paper, and your
+// implementation of Step 3 needs to handle multiple ranges.
+// The full Bleichenbacher attack works basically like this:
+//     Starting from the smallest 's' that could possibly produce a plaintext bigger than 2B, iteratively search for an
+//     's' that produces a conformant plaintext.
+//     For our known 's1' and 'n', solve m1=m0s1-rn (again: just a definition of modular multiplication) for 'r', the
+//     number of times we've wrapped the modulus.
+//     'm0' and 'm1' are unknowns, but we know both are conformant PKCS#1v1.5 plaintexts, and so are between [2B,3B].
+//     We substitute the known bounds for both, leaving only 'r' free, and solve for a range of possible 'r' values.
+//     This range should be small!
+//     Solve m1=m0s1-rn again but this time for 'm0', plugging in each value of 'r' we generated in the last step. This
+//     gives us new intervals to work with. Rule out any interval that is outside 2B,3B.
+//     Repeat the process for successively higher values of 's'. Eventually, this process will get us down to just one
+//     interval, whereupon we're back to exercise #47.
+// What happens when we get down to one interval is, we stop blindly incrementing 's'; instead, we start rapidly
+// growing 'r' and backing it out to 's' values by solving m1=m0s1-rn for 's' instead of 'r' or 'm0'. So much algebra!
+// Make your teenage son do it for you! *Note: does not work well in practice*
+#[test]
+pub fn challenge_48() {
+    let oracle_calls = padding_oracle_roundtrip(768, 48);
+    assert!(oracle_calls > 0);
+}
@@ -0,0 +1,374 @@
+use crate::attacks::cbc_mac::{cbc_mac_collision, extend_transactions, forge_transfer_source};
+use crate::attacks::compression::recover_secret;
+use crate::attacks::iterated_hash::{
+    concatenated_collision, second_preimage, NostradamusPrediction,
+};
+use crate::attacks::md4_collision::find_collision;
+use crate::attacks::rc4_bias::{recover_cookie, recover_cookie_bytes, BiasAttackConfig};
+use crate::conversion::base64::base64_to_bytes;
+use crate::hash::md4;
+use crate::hash::weak_md::WeakMdHash;
+use crate::mac::cbc_mac;
+use crate::oracles::bank::{BankServer, Transfer};
+use crate::oracles::compression::{CompressionCipher, CompressionServer};
+use crate::oracles::rc4::Rc4CookieServer;
+use crate::random::Rng;
+
+// CBC-MAC Message Forgery
+// Let's talk about CBC-MAC.
+// CBC-MAC is like this:
+//     Take the plaintext P.
+//     Encrypt P under CBC with key K, yielding ciphertext C.
+//     Chuck all of C but the last block C[n].
+//     C[n] is the MAC.
+// Suppose there's an online banking application, and it carries out user requests by talking to an API server over
+// the network. Each request looks like this:
+// message || IV || MAC
+// The message looks like this:
+// from=#{from_id}&to=#{to_id}&amount=#{amount}
+// Now, write an API server and a web frontend for it. (NOTE: No need to get ambitious and write actual servers and
+// web apps. Totally fine to go lo-fi on this one.) The client and server should share a secret key K to sign and
+// verify messages.
+// The API server should accept messages, verify signatures, and carry out each transaction if the MAC is valid. It's
+// also publicly exposed - the attacker can submit messages freely assuming he can forge the right MAC.
+// The web client should allow the attacker to generate valid messages for accounts he controls. (Feel free to sanity
+// check the transactions with the API server, such as hitting the "from" account only.) Assume the attacker is in a
+// position to capture and replay messages from other users.
+// Your goal: capture a valid message from a victim user, use length extension to add a transaction paying the
+// attacker's account 1M spacebucks.
+// This is a known-IV attack in disguise. First forge a transfer request from the victim to the attacker by controlling
+// the IV. Then, for a revised protocol with a fixed IV of 0 and multiple transactions per message
+// (from=#{from_id}&tx_list=#{transactions}, transactions being to:amount(;to:amount)*), capture the victim's message
+// and extend it with a transaction of your own.
+const ATTACKER: u32 = 1001;
+const VICTIM: u32 = 2002;
+
+#[test]
+pub fn challenge_49_iv() {
+    let mut rng = Rng::new(49);
+    let bank = BankServer::new(&mut rng);
+    let attacker = bank.client(ATTACKER);
+
+    let request = attacker.transfer(ATTACKER, 1_000_000, &mut rng);
+    let forged = forge_transfer_source(&request, VICTIM).unwrap();
+
+    assert_eq!(
+        bank.process_transfer(&forged),
+        Some(Transfer {
+            from: VICTIM,
+            to: ATTACKER,
+            amount: 1_000_000,
+        })
+    );
+}
+
+#[test]
+pub fn challenge_49_length_extension() {
+    let mut rng = Rng::new(49);
+    let bank = BankServer::new(&mut rng);
+
+    let victim_request = bank
+        .client(VICTIM)
+        .transactions(&[(3003, 100), (4004, 250)]);
+    assert!(bank.process_transactions(&victim_request).is_some());
+
+    // Our first block is sacrificed to the glue, so the transaction we want comes second
+    let attacker_request = bank
+        .client(ATTACKER)
+        .transactions(&[(ATTACKER, 1), (ATTACKER, 1_000_000)]);
+    let forged = extend_transactions(&victim_request, &attacker_request).unwrap();
+
+    let transfers = bank.process_transactions(&forged).unwrap();
+    assert!(transfers.contains(&Transfer {
+        from: VICTIM,
+        to: ATTACKER,
+        amount: 1_000_000,
+    }));
+}
+
+// Hashing with CBC-MAC
+// Sometimes people try to use CBC-MAC as a hash function.
+// This is a bad idea. Matt Green explains:
+//     To make a long story short: cryptographic hash functions are public functions (i.e., no secret key) that have
+//     the property of collision-resistance (it's hard to find two messages with the same hash). MACs are keyed
+//     functions that (typically) provide message unforgeability -- a very different property. Moreover, they
+//     guarantee this only when the key is secret.
+// Let's try a simple exercise.
+// Hash functions are often used for code verification. This snippet of JavaScript (with newline):
+// alert('MZA who was that?');
+// Hashes to 296b8d7cb78a243dda4d0a61d33bbdd1 under CBC-MAC with a key of "YELLOW SUBMARINE" and a 0 IV.
+// Forge a valid snippet of JavaScript that alerts "Ayo, the Wu is back!" and hashes to the same value. Ensure that it
+// runs in a browser.
+#[test]
+pub fn challenge_50() {
+    let key = b"YELLOW SUBMARINE";
+    let iv = [0; 16];
+    let target = b"alert('MZA who was that?');\n";
+    let prefix = b"alert('Ayo, the Wu is back!');//";
+
+    let forged = cbc_mac_collision(key, &iv, target, prefix);
+
+    assert!(forged.starts_with(prefix));
+    assert!(forged.ends_with(b"');\n"));
+    assert_eq!(forged.iter().filter(|&&byte| byte == b'\n').count(), 1);
+    assert_eq!(cbc_mac(key, &iv, &forged), cbc_mac(key, &iv, target));
+}
+
+// Compression Ratio Side-Channel Attacks
+// Internet traffic is often compressed to save bandwidth. Until recently, this included HTTPS headers, and it still
+// includes the contents of responses.
+// Why does that matter?
+// Well, if you're an attacker with:
+//     Partial plaintext knowledge and
+//     Partial plaintext control and
+//     Access to a compression oracle
+// You've got a pretty good chance to recover any additional unknown plaintext.
+// What's a compression oracle? You give it some input and it tells you how well the full message compresses, i.e.
+// the length of the resultant output.
+// This is somewhat similar to the timing attacks we did way back in set 4 in that we're taking advantage of
+// incidental side channels rather than attacking the cryptographic mechanisms themselves.
+// Scenario: you are running a MITM attack with an eye towards stealing secure session cookies. You've injected
+// malicious content allowing you to spawn arbitrary requests and observe them in flight. (The particulars aren't
+// terribly important, just roll with it.)
+// So! Write this oracle:
+// oracle(P) -> length(encrypt(compress(format_request(P))))
+// Format the request like this:
+// POST / HTTP/1.1
+// Host: hapless.com
+// Cookie: sessionid=TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=
+// Content-Length: ((len(P)))
+// ((P))
+// (Pretend you can't see that session id. You're the attacker.)
+// Compress using zlib or whatever.
+// Encryption... is actually kind of irrelevant for our purposes, but be a sport. Just use some stream cipher. Dealer's
+// choice. Random key/IV on every call to the oracle.
+// And then just return the length in bytes.
+// Now, the idea here is to leak information using the compression library. A payload of "sessionid=T" should compress
+// just a little bit better than, say, "sessionid=S".
+// There is one complicating factor. The DEFLATE algorithm operates in terms of individual bits, but the final
+// message length will be in bytes. Even if you do find a better compression, the difference may not cross a byte
+// boundary. So that's a problem.
+// You may also get some incorrect guesses. You'll need to hack around them and recover.
+// When you have the secret, use a block cipher (e.g. CBC) and repeat the attack. Now the length is rounded to the
+// block size, so you'll need to pad your guesses out to land near a block boundary.
+const SESSION_ID: &str = "TmV2ZXIgcmV2ZWFsIHRoZSBXdS1UYW5nIFNlY3JldCE=";
+const SESSION_ID_ALPHABET: &[u8] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=\n";
+
+fn compression_roundtrip(cipher: CompressionCipher, seed: u64) {
+    let mut server = CompressionServer::new(SESSION_ID, cipher, Rng::new(seed));
+
+    let recovered = recover_secret(&mut server, b"sessionid=", SESSION_ID_ALPHABET, b'\n').unwrap();
+    assert_eq!(recovered, SESSION_ID.as_bytes());
+}
+
+#[test]
+pub fn challenge_51_ctr() {
+    compression_roundtrip(CompressionCipher::Ctr, 51);
+}
+
+#[test]
+pub fn challenge_51_cbc() {
+    compression_roundtrip(CompressionCipher::Cbc, 51);
+}
+
+// Iterated Hash Function Multicollisions
+// While we're on the topic of hash functions...
+// The major feature you want in your hash function is collision-resistance. That is, it should be hard to generate
+// collisions, and it should be really hard to generate a collision for a given hash (aka preimage).
+// Iterated hash functions have a problem: the effort to generate lots of collisions scales sublinearly.
+// What's an iterated hash function? For all intents and purposes, we're talking about the Merkle-Damgard
+// construction. It looks like this:
+// function MD(M, H, C):
+//   for M[i] in pad(M):
+//     H := C(M[i], H)
+//   return H
+// For message M, initial state H, and compression function C.
+// This should look really familiar, because SHA-1 and MD4 are both in this category. What's cool is you can use this
+// formula to build a makeshift hash function out of some spare crypto primitives you have lying around (e.g. C =
+// AES-128).
+// Back on task: the cost of collisions scales sublinearly. What does that mean? If it's feasible to find one
+// collision, it's probably feasible to find a lot.
+// How? For a given state H, find two blocks that collide. Now take the resulting hash from this collision as your new
+// H and repeat. Recognize that with each iteration you can actually double your collisions by subbing in either of
+// the two blocks for that slot.
+// This means that if finding two colliding messages takes 2^(b/2) work (where b is the bit-size of the hash
+// function), then finding 2^n colliding messages only takes n*2^(b/2) work.
+// Let's test it. First, build your own MD hash function. We're going to be generating a LOT of collisions, so don't
+// knock yourself out. In fact, go out of your way to make it bad. Here's one way:
+//     Take a fast block cipher and use it as C.
+//     Make H pretty small. I won't look down on you if it's only 16 bits. Pick some initial H.
+//     H is going to be the input key and M[i] the plaintext block. Take the first n bits of the output as the new H.
+// Now write the function f(n) that will generate 2^n collisions in this hash function.
+// Why does this matter? Well, one reason is that people have tried to strengthen hash functions by cascading them
+// together. Here's what I mean:
+//     Take hash functions f and g.
+//     Build a function h such that h(x) = f(x) || g(x).
+// The idea is that if collisions in f cost 2^(b1/2) and collisions in g cost 2^(b2/2), collisions in h should come
+// to the princely sum of 2^((b1+b2)/2).
+// But now we know that's not true!
+// Here's the idea:
+//     Pick the "cheaper" hash function. Suppose it's f.
+//     Generate 2^(b2/2) colliding messages in f.
+//     There's a good chance your message pool has a collision in g.
+//     Find it.
+// And if it doesn't, keep generating cheap collisions until you find it.
+// Prove this out by building a more expensive (but not too expensive) hash function to pair with the one you just
+// used. Find a pair of messages that collide under both functions. Measure the total number of calls to the
+// collision function.
+#[test]
+pub fn challenge_52() {
+    let mut rng = Rng::new(52);
+    let cheap = WeakMdHash::new(2, 0x5252);
+    let expensive = WeakMdHash::new(3, 0x525252);
+
+    let (first, second) = concatenated_collision(&cheap, &expensive, &mut rng);
+
+    assert_ne!(first, second);
+    assert_eq!(cheap.hash(&first), cheap.hash(&second));
+    assert_eq!(expensive.hash(&first), expensive.hash(&second));
+}
+
+// Kelsey and Schneier's Expandable Messages
+// One of the basic yardsticks we use to judge a cryptographic hash function is its resistance to second preimage
+// attacks. That means that if I give you x and y such that H(x) = y, you should have a tough time finding x' such
+// that H(x') = H(x).
+// How tough? Brute-force tough. For a 2^b hash function, we want second preimage attacks to cost 2^b operations.
+// This turns out not to be the case for very long messages.
+// Consider the problem we're trying to solve: we want to find a message that will collide with H(x) in the very last
+// iteration of the hash function. But there are a ton of intermediate hash states we have access to... Still, it's
+// no use to collide with one of those states if our forgery doesn't have the same length, since the padding is
+// length-dependent.
+// Expandable messages are the answer. For a given k, build a set of k collisions, each between a single block and a
+// message of 2^(k-1)+1, 2^(k-2)+1, ... 2 blocks, so that any length between k and k + 2^k - 1 blocks can be
+// produced. Then:
+//     Generate an expandable message of length (k, k + 2^k - 1) using the strategy outlined above.
+//     Hash M and generate a map of intermediate hash states to the block indices that they correspond to.
+//     From your expandable message's final state, find a single-block "bridge" to intermediate state in your map.
+//     Note the index i it maps to.
+//     Use your expandable message to generate a prefix of the right length such that len(prefix || bridge ||
+//     M[i..]) = len(M).
+// The padding in the final block should now be correct, and your forgery should hash to the same value as M.
+#[test]
+pub fn challenge_53() {
+    let mut rng = Rng::new(53);
+    let hash = WeakMdHash::new(2, 0x5353);
+    let message = rng.bytes(1024 * 16 + 7);
+
+    let forged = second_preimage(&hash, &message, &mut rng).unwrap();
+
+    assert_ne!(forged, message);
+    assert_eq!(forged.len(), message.len());
+    assert_eq!(hash.hash(&forged), hash.hash(&message));
+}
+
+// Kelsey and Kohno's Nostradamus Attack
+// Hash functions are sometimes used as proof of a secret prediction.
+// For example, suppose you wanted to predict the score of every Major League Baseball game in a season. (2,430 in all.)
+// You might be concerned that publishing your predictions would affect the outcomes.
+// So instead you write down all the scores, hash the document, and publish the hash. Once the season is over, you
+// publish the document. Everyone can then hash the document to verify your soothsaying prowess.
+// But what if you can't accurately predict the scores of 2.4k baseball games? Have no fear - forging a prediction
+// under this scheme reduces to another second preimage attack.
+// We could apply the long message attack from the previous problem, but it would look pretty shady. Would you trust
+// someone whose predicted message turned out to be 2^50 bytes long?
+// It turns out we can run a successful attack with a much shorter suffix. Check the method:
+//     Generate a large number of initial hash states. Say, 2^k.
+//     Pair them up and generate single-block collisions. Now you have 2^k hash states that collide into 2^(k-1)
+//     states.
+//     Repeat the process. Pair up the 2^(k-1) states and generate collisions. Now you have 2^(k-2) states.
+//     Keep doing this until you have one state. This is your prediction.
+//     Well, sort of. You need to commit to some length to encode in the padding. Make sure it's long enough to
+//     accommodate your actual message, this suffix, and a little bit of glue to join them up. Hash this padding block
+//     using the state from step 4 - THIS is your prediction.
+// What did you just build? It's basically a funnel mapping many initial states into a common final state. What's
+// critical is we now have a big field of 2^k states we can try to collide into, but the actual suffix will only be
+// k+1 blocks long.
+// The rest is trivial:
+//     Wait for the end of the baseball season. (This may take some time.)
+//     Write down the game results. Or, you know, anything else. I'm not too particular.
+//     Generate random padding bytes and glue blocks until one of the resulting hashes collides with a leaf.
+//     Walk the tree back to the root.
+#[test]
+pub fn challenge_54() {
+    let mut rng = Rng::new(54);
+    let hash = WeakMdHash::new(2, 0x5454);
+    let prediction = NostradamusPrediction::new(&hash, 8, 8, &mut rng);
+
+    let results =
+        b"Red Sox 4, Yankees 2\nCubs 7, Cardinals 3\nGiants 1, Dodgers 0\nMets 6, Braves 5\n";
+    let forged = prediction.forge(&hash, results, &mut rng).unwrap();
+
+    assert!(forged.starts_with(results));
+    assert_eq!(hash.hash(&forged), prediction.prediction);
+}
+
+// MD4 Collisions
+// MD4 is a 128-bit cryptographic hash function, meaning it should take a work factor of roughly 2^64 to find
+// collisions.
+// It turns out we can do much better.
+// The paper "Cryptanalysis of the Hash Functions MD4 and RIPEMD" by Wang et al details a cryptanalytic attack that
+// lets us find collisions in 2^8 or less.
+// Given a message block M, Wang outlines a strategy for finding a sister message block M', differing only in a few
+// bits, that will collide with it. Just so long as a short set of conditions holds true for M.
+// What sort of conditions? Simple bitwise equalities within the intermediate hash function state, e.g. a[1][6] =
+// b[0][6]. This should be read as: "the sixth-from-last bit (a.k.a. bit 6, indexed from 1) in variable a after one
+// round should be equal to the same bit in variable b after zero rounds". These conditions are listed in Table 6 of
+// the paper.
+// The real trick is in how we assure all these conditions are met. Single-message modification fixes each round 1
+// condition by setting the bit and solving for the message word that produces it. Multi-message modification does the
+// same for round 2, then rewrites the words of round 1 that it disturbed so the earlier conditions hold again.
+// When every condition we can enforce holds, compute M' = M + (0, 2^31, 2^31 - 2^28, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+// -2^16, 0, 0, 0) and see whether MD4(M) = MD4(M'). If not, try another M.
+#[test]
+pub fn challenge_55() {
+    let mut rng = Rng::new(55);
+    let collision = find_collision(&mut rng);
+
+    assert_ne!(collision.first, collision.second);
+    assert_eq!(md4(&collision.first), md4(&collision.second));
+}
+
+// RC4 Single-Byte Biases
+// RC4 is popular stream cipher notable for its usage in protocols like TLS, WPA, RDP, &c.
+// It's also susceptible to significant single-byte biases, especially early in the keystream. What does this mean?
+// Simply: for a given position in the keystream, certain bytes are more (or less) likely to pop up than others.
+// Given enough encryptions of a given plaintext, an attacker can use these biases to recover the entire plaintext.
+// Now, search online for "On the Security of RC4 in TLS and WPA". This site is your one-stop shop for RC4
+// information.
+// Click through to "RC4 biases" on the right.
+// These are graphs of each single-byte bias (one per page). Notice in particular the monster spikes on z16, z32, z48,
+// etc. (Note: these are one-indexed, so z16 = keystream[15].)
+// How useful are these biases?
+// Click through to the research paper and scroll down to the simulation results. (Incidentally, the whole paper is
+// worth your time.) We start out clueless, but quickly recover the plaintext byte using the bias.
+// Here's the attack:
+//     Build an encryption oracle: RC4(your-request || cookie, random-key). Use a random 128-bit key on every
+//     invocation.
+//     The cookie is "QkUgU1VSRSBUTyBEUklOSyBZT1VSIE9WQUxUSU5F", base64 decoded.
+//     By adjusting the length of your request, you can bump the cookie bytes onto z16 and z32, then count the
+//     ciphertext bytes you see there. The most common one, XORed with the biased keystream value (240 for z16, 224
+//     for z32), is the plaintext byte.
+// A hint on the number of trials: 2^24 per byte position works. It's a lot, so use threads or expect to wait.
+fn rc4_cookie_server() -> Rc4CookieServer {
+    Rc4CookieServer::new(&base64_to_bytes("QkUgU1VSRSBUTyBEUklOSyBZT1VSIE9WQUxUSU5F"))
+}
+
+// One request alignment covers the first cookie byte through z16 and the seventeenth through z32
+#[test]
+pub fn challenge_56_reduced() {
+    let config = BiasAttackConfig {
+        trials: 1 << 23,
+        ..BiasAttackConfig::default()
+    };
+
+    let recovered = recover_cookie_bytes(&rc4_cookie_server(), &[0, 16], &config).unwrap();
+    assert_eq!(recovered, b"B ");
+}
+
+#[test]
+#[ignore = "2^24 encryptions for each of 16 request alignments"]
+pub fn challenge_56() {
+    let recovered = recover_cookie(&rc4_cookie_server(), &BiasAttackConfig::default()).unwrap();
+    assert_eq!(recovered, b"BE SURE TO DRINK YOUR OVALTINE");
+}
@@ -0,0 +1,461 @@
+use crate::attacks::dh_subgroup::{
+    kangaroo, subgroup_confinement, subgroup_confinement_with_kangaroo,
+};
+use crate::attacks::ec_invalid_curve::{invalid_curve_attack, InvalidCurve};
+use crate::attacks::ec_twist::twist_attack;
+use crate::attacks::ecdsa_biased_nonce::biased_nonce_attack;
+use crate::attacks::gcm::{
+    forge_tag, recover_authentication_key, truncated_mac_attack, GcmMessage,
+};
+use crate::attacks::key_selection::{ecdsa_key_selection, rsa_key_selection};
+use crate::byte_operations::bytes_xor;
+use crate::ciphers::aes::Aes;
+use crate::ciphers::gcm;
+use crate::ec::curves;
+use crate::ec::montgomery::MontgomeryGroup;
+use crate::ec::weierstrass::WeierstrassGroup;
+use crate::math::bigint::BigUint;
+use crate::oracles::dh::{shared_secret_mac, DhMacServer, MESSAGE};
+use crate::oracles::ec::{BiasedEcdsaSigner, EcdhMacServer, LadderMacServer};
+use crate::oracles::gcm::TruncatedGcmOracle;
+use crate::protocols::dh::DhGroup;
+use crate::pubkey::ecdsa::{self, EcdsaSignature};
+use crate::pubkey::{pkcs1, rsa};
+use crate::random::Rng;
+
+const FACTOR_BOUND: u32 = 1 << 16;
+
+// Diffie-Hellman Revisited: Small Subgroup Confinement
+// This set is going to focus on elliptic curves. But before we get to that, we're going to kick things off with some
+// classic Diffie-Hellman.
+// Trust me, it's gonna make sense later.
+// Let's get right into it. First, build your typical Diffie-Hellman key agreement: Alice and Bob exchange public keys
+// and derive the same shared secret. Then Bob sends Alice some message with a MAC over it. Easy peasy.
+// Use these parameters:
+// p = 7199773997391911030609999317773941274322764333428698921736339643928346453700085358802973900485592910475480089726140708102474957429903531369589969318716771
+// g = 4565356397095740655436854503483826832136106141639563487732438195343690437606117828318042418238184896212352329118608100083187535033402010599512641674644143
+// The generator g has order q:
+// q = 236234353446506858198510045061214171961
+// "Order" is a new word, but it just means g^q = 1 mod p. You might notice that q is a prime, just like p. This isn't
+// mere chance: in fact, we chose q and p together such that q divides p-1 (the order or size of the group itself)
+// evenly. This guarantees that an element g of order q will exist. (In fact, there will be q-1 such elements.)
+// Back to the protocol. Alice and Bob should choose their secret keys as random integers mod q. There's no point in
+// choosing them mod p; since g has order q, the numbers will just start repeating after that. You can prove this to
+// yourself by verifying g^x mod p = g^(x + k*q) mod p for any x and k.
+// The rest is the same as before.
+// How can we attack this protocol? Remember what we said before about order: the fact that q divides p-1 guarantees
+// the existence of elements of order q. What if there are smaller divisors of p-1?
+// Spoiler alert: there are. I chose j = (p-1) / q to have many small factors because I want you to be happy. Find
+// them by factoring j, which is:
+// j = 30477252323177606811760882179058908038824640750610513771646768011063128035873508507547741559514324673960576895059570
+// You don't need to factor it all the way. Just find a bunch of factors smaller than, say, 2^16. There should be
+// plenty. (Friendly tip: maybe avoid any repeated factors. They only complicate things.)
+// Got 'em? Good. Now, we can use these to recover Bob's secret key using the Pohlig-Hellman algorithm for discrete
+// logarithms. Here's how:
+//     Take one of the small factors j. Call it r. We want to find an element h of order r. To find it, do:
+//     h := rand(1, p)^((p-1)/r) mod p
+//     If h = 1, try again.
+//     You're Eve. Send Bob h as your public key. Note that h is not a valid public key! There is no x such that h = g^x
+//     mod p. But Bob doesn't know that.
+//     Bob will compute:
+//     K := h^x mod p
+//     Where x is his secret key and K is the output shared secret. Bob then sends back (m, t), with:
+//     m := "crazy flamboyant for the rap enjoyment"
+//     t := MAC(K, m)
+//     We (Eve) can't compute K, because h isn't actually a valid public key. But we're not licked yet.
+//     Remember how we saw that g^x starts repeating when x > q? h has the same property with r. This means there are
+//     only r possible values of K that Bob could have generated. We can recover K by doing a brute-force search over
+//     these values until t = MAC(K, m).
+//     Now we know Bob's secret key x mod r.
+//     Repeat steps 1 through 4 many times. Eventually you will know:
+//     x = b1 mod r1
+//     x = b2 mod r2
+//     x = b3 mod r3
+//     ...
+//     Once (r1*r2*...*rn) > q, you'll have enough information to reassemble Bob's secret key using the Chinese
+//     Remainder Theorem.
+#[test]
+pub fn challenge_57() {
+    let mut rng = Rng::new(57);
+    let group = DhGroup::new(
+        BigUint::from_decimal("7199773997391911030609999317773941274322764333428698921736339643928346453700085358802973900485592910475480089726140708102474957429903531369589969318716771"),
+        BigUint::from_decimal("4565356397095740655436854503483826832136106141639563487732438195343690437606117828318042418238184896212352329118608100083187535033402010599512641674644143"),
+    );
+    let order = BigUint::from_decimal("236234353446506858198510045061214171961");
+    let server = DhMacServer::new(group.clone(), &order, &mut rng);
+
+    let recovered = subgroup_confinement(&server, &group, &order, FACTOR_BOUND, &mut rng);
+
+    assert!(recovered.modulus > order);
+    assert_eq!(
+        &group.g.modpow(&recovered.residue, &group.p),
+        server.public_key()
+    );
+}
+
+// Pollard's Method for Catching Kangaroos
+// The last problem was a little contrived. It only worked because I helpfully foisted those broken group parameters
+// on Alice and Bob. While real-world groups may include small subgroups, it's unlikely to find this many in a group
+// chosen for DH.
+// But p-1 will always have at least one subgroup. Most of the time, it will have a few. But most of the time, they
+// won't be small enough to be useful.
+// This means it's time to look at a different algorithm: Pollard's kangaroo, for catching a discrete log in a known
+// range [a, b]:
+//     Pick a pseudorandom mapping function f that maps group elements onto a set of jump sizes, e.g.
+//     f(y) = 2^(y mod k). Let N be four times the mean of the jumps.
+//     The tame kangaroo starts at g^b and jumps N times, remembering its total distance xT and where it stopped, yT.
+//     The wild kangaroo starts at y and jumps until either it lands on yT, in which case
+//     x = b + xT - xW, or it has gone further than b - a + xT, in which case it has jumped over the trap.
+// Use these parameters:
+// p = 11470374874925275658116663507232161402086650258453896274534991676898999262641581519101074740642369848233294239851519212341844337347119899874391456329785623
+// q = 335062023296420808191071248367701059461
+// j = 34233586850807404623475048381328686211071196701374230492615844865929237417097514638999377942356150481334217896204702
+// g = 622952335333961296978159266084741085889881358738459939978290179936063635566740258555167783009058567397963466103140082647486611657350811560630587013183357
+// Find the index of:
+// y = 7760073848032689505395005705677365876654629189298052775754597607446617558600394076764814236081991643094239886772481052254010323780165093955236429914607119
+// in the range [0, 2^20], and the index of:
+// y = 9388897478013399550694114614498790691034187453089355259602614074132918843899833277397448144245883225611726912025846772975325932794909655215329941809013733
+// in the range [0, 2^40].
+// Now combine the two attacks: j has only a few small factors, so the subgroup confinement attack recovers n = x mod r
+// for their product r. Then y' = y * g^-n = (g^r)^m for some m in [0, (q-1)/r], which the kangaroo can find, giving
+// x = n + m*r.
+fn challenge_58_group() -> (DhGroup, BigUint) {
+    let group = DhGroup::new(
+        BigUint::from_decimal("11470374874925275658116663507232161402086650258453896274534991676898999262641581519101074740642369848233294239851519212341844337347119899874391456329785623"),
+        BigUint::from_decimal("622952335333961296978159266084741085889881358738459939978290179936063635566740258555167783009058567397963466103140082647486611657350811560630587013183357"),
+    );
+    let order = BigUint::from_decimal("335062023296420808191071248367701059461");
+
+    (group, order)
+}
+
+#[test]
+pub fn challenge_58_kangaroo() {
+    let (group, _) = challenge_58_group();
+    let cases = [
+        ("7760073848032689505395005705677365876654629189298052775754597607446617558600394076764814236081991643094239886772481052254010323780165093955236429914607119", 20, 705485u64),
+        ("9388897478013399550694114614498790691034187453089355259602614074132918843899833277397448144245883225611726912025846772975325932794909655215329941809013733", 40, 359579674340),
+    ];
+
+    for (y, bits, expected) in cases {
+        let high = BigUint::from(1u64 << bits);
+        let index = kangaroo(
+            &group.p,
+            &group.g,
+            &BigUint::from_decimal(y),
+            &BigUint::zero(),
+            &high,
+        );
+        assert_eq!(index, Some(BigUint::from(expected)));
+    }
+}
+
+#[test]
+pub fn challenge_58() {
+    let mut rng = Rng::new(58);
+    let (group, order) = challenge_58_group();
+    let server = DhMacServer::new(group.clone(), &order, &mut rng);
+
+    let private_key =
+        subgroup_confinement_with_kangaroo(&server, &group, &order, FACTOR_BOUND, &mut rng)
+            .unwrap();
+
+    assert_eq!(&group.g.modpow(&private_key, &group.p), server.public_key());
+}
+
+// Elliptic Curve Diffie-Hellman and Invalid-Curve Attacks
+// Elliptic curves are groups too: points (x, y) satisfying y^2 = x^3 + a*x + b over GF(p), plus a point at infinity
+// that acts as the identity. Addition is defined geometrically, and scalar multiplication is repeated addition, so
+// ECDH is DH with points: Bob's public key is d*G and the shared secret is d*Q.
+// Use this curve:
+// y^2 = x^3 - 95051*x + 11279326
+// p = 233970423115425145524320034830162017933
+// G = (182, 85518893674295321206118380980485522083)
+// The order of G is
+// n = 29246302889428143187362802287225875743
+// and the curve has 8n points.
+// Now the attack. The addition formulas never use b. So if Bob doesn't check that Alice's point is on his curve, we
+// can send him points from other curves y^2 = x^3 - 95051*x + b' whose orders have small factors:
+// y^2 = x^3 - 95051*x + 210 has 233970423115425145550826547352470124412 points
+// y^2 = x^3 - 95051*x + 504 has 233970423115425145544350131142039591210 points
+// y^2 = x^3 - 95051*x + 727 has 233970423115425145545378039958152057148 points
+// Find points of small order r on them by multiplying random points by (order / r), send them to Bob, and brute
+// force his MAC as in challenge 57. Combine the residues with CRT once their moduli multiply past n.
+#[test]
+pub fn challenge_59() {
+    let mut rng = Rng::new(59);
+    let group = WeierstrassGroup::from_spec(&curves::CHALLENGE_59);
+    let server = EcdhMacServer::new(group.clone(), &mut rng);
+    let invalid_curves = [
+        InvalidCurve::from_decimal("210", "233970423115425145550826547352470124412"),
+        InvalidCurve::from_decimal("504", "233970423115425145544350131142039591210"),
+        InvalidCurve::from_decimal("727", "233970423115425145545378039958152057148"),
+    ];
+
+    let recovered = invalid_curve_attack(&server, &group, &invalid_curves, FACTOR_BOUND, &mut rng);
+
+    assert!(recovered.modulus > group.order);
+    assert_eq!(
+        &group
+            .curve
+            .scalar_multiply(&group.generator, &recovered.residue),
+        server.public_key()
+    );
+}
+
+// Single-Coordinate Ladders and Insecure Twists
+// The Montgomery curve v^2 = u^3 + 534*u^2 + u over the same p is isomorphic to the curve from challenge 59, with
+// u = x - 178 and base point u = 4. The Montgomery ladder computes k*P from the u coordinate alone, which is fast and
+// means public keys are a single coordinate. Bob can't send invalid-curve points any more: every u is either on the
+// curve or on its quadratic twist. But the ladder works just as well on the twist, whose order is
+// 2*p + 2 - 8*n = 233970423115425145549737651362517029924
+// and has small factors. Send Bob twist points of small order r and brute force his MAC again; since the MAC only
+// covers u, each residue is +/- some value. Resolve the signs, then catch the rest of the key with the kangaroo from
+// challenge 58, run on the Weierstrass form of the curve.
+fn challenge_60_shared_secrets_match(
+    server: &LadderMacServer,
+    group: &MontgomeryGroup,
+    key: &BigUint,
+) {
+    let u = group.curve.ladder(&group.base, &BigUint::from(12345u32));
+    let (message, mac) = server.respond(&u);
+
+    assert_eq!(&group.curve.ladder(&group.base, key), server.public_key());
+    assert_eq!(
+        shared_secret_mac(&group.curve.ladder(&u, key), MESSAGE),
+        mac
+    );
+    assert_eq!(message, MESSAGE);
+}
+
+// Factors below 2^20 leave about 64 bits of the key, so Bob's key is kept below 2^88 to
+// leave the kangaroo a short walk
+#[test]
+pub fn challenge_60_reduced() {
+    let mut rng = Rng::new(60);
+    let group = MontgomeryGroup::from_spec(&curves::CHALLENGE_60);
+    let key_bound = BigUint::one() << 88;
+    let private_key = BigUint::random_range(&mut rng, &BigUint::one(), &key_bound);
+    let server = LadderMacServer::with_private_key(group.clone(), private_key);
+
+    let key = twist_attack(&server, &group, 1 << 20, &key_bound, &mut rng).unwrap();
+
+    challenge_60_shared_secrets_match(&server, &group, &key);
+}
+
+#[test]
+#[ignore = "a 2^40-wide kangaroo with an inversion per jump"]
+pub fn challenge_60() {
+    let mut rng = Rng::new(60);
+    let group = MontgomeryGroup::from_spec(&curves::CHALLENGE_60);
+    let server = LadderMacServer::new(group.clone(), &mut rng);
+
+    let key = twist_attack(&server, &group, 1 << 22, &group.order, &mut rng).unwrap();
+
+    challenge_60_shared_secrets_match(&server, &group, &key);
+}
+
+// Duplicate-Signature Key Selection in ECDSA (and RSA)
+// Suppose you have a message-signature pair. If I give you a public key that verifies the signature, can you trust
+// that I'm the author?
+// You shouldn't. It turns out to be pretty easy to solve this problem to cook up a key that verifies a given
+// signature, a property known as duplicate-signature key selection (DSKS).
+// ECDSA verification computes R = u1*G + u2*Q with u1 = H(m)/s and u2 = r/s, and checks R.x = r. Eve can pick any
+// private key d', compute t = u1 + u2*d', and publish the generator G' = t^-1 * R with Q' = d'*G'. Then
+// u1*G' + u2*Q' = t*G' = R, and the signature verifies under her key.
+// RSA verification checks s^e = pad(m) mod N. Eve picks primes p and q where p-1 and q-1 are smooth and s generates
+// both groups, so the discrete logs e_p = log_s(pad(m)) mod p and e_q mod q are easy with Pohlig-Hellman. Then
+// e' = crt([e_p, e_q], [p-1, q-1]) and N' = p*q verify the signature. Make sure p-1 and q-1 don't share factors
+// beyond 2, and that N' is bigger than s.
+#[test]
+pub fn challenge_61_ecdsa() {
+    let mut rng = Rng::new(61);
+    let group = WeierstrassGroup::from_spec(&curves::CHALLENGE_59);
+    let alice = group.generate_keypair(&mut rng);
+    let message = b"I'll pay Bob 10 dollars";
+    let signature = ecdsa::sign(&group, &alice, message, &mut rng);
+
+    let (forged_group, eve) =
+        ecdsa_key_selection(&group, &alice.public_key, message, &signature, &mut rng).unwrap();
+
+    assert_ne!(eve.public_key, alice.public_key);
+    assert!(ecdsa::verify(
+        &forged_group,
+        &eve.public_key,
+        message,
+        &signature
+    ));
+    assert!(!ecdsa::verify(
+        &forged_group,
+        &eve.public_key,
+        b"I'll pay Bob 11 dollars",
+        &signature
+    ));
+
+    let eve_signature = ecdsa::sign(&forged_group, &eve, b"Eve's own message", &mut rng);
+    assert!(ecdsa::verify(
+        &forged_group,
+        &eve.public_key,
+        b"Eve's own message",
+        &eve_signature
+    ));
+}
+
+#[test]
+pub fn challenge_61_rsa() {
+    let mut rng = Rng::new(61);
+    let (public_key, private_key) = rsa::generate_keypair(&mut rng, 1024, &BigUint::from(65537u32));
+    let message = b"I'll pay Bob 10 dollars";
+    let signature = pkcs1::sign(&private_key, message);
+    assert!(pkcs1::verify(&public_key, message, &signature));
+
+    let forged_key = rsa_key_selection(&public_key, message, &signature, &mut rng);
+
+    assert_ne!(forged_key, public_key);
+    assert!(pkcs1::verify(&forged_key, message, &signature));
+    assert!(!pkcs1::verify(
+        &forged_key,
+        b"I'll pay Bob 11 dollars",
+        &signature
+    ));
+}
+
+// Key-Recovery Attacks on ECDSA with Biased Nonces
+// ECDSA falls apart if the nonce k is reused, or even if it's just a little predictable. Here the signer's nonces all
+// have their low 8 bits zeroed, so k = b*2^l with l = 8 and b < q/2^l.
+// Start from the signing equation s = (H(m) + d*r) / k mod q and rearrange it:
+//     b = H(m)/(s*2^l) + d * r/(s*2^l) mod q
+// Call t = r/(s*2^l) and u = H(m)/(s*2^l). Then u + d*t = b is small, which is an instance of the hidden number
+// problem. Collect a bunch of signatures and build the lattice with rows
+//     [  q    0   ...  0   0   0 ]
+//     [  0    q   ...  0   0   0 ]
+//     [ ...                      ]
+//     [ t1   t2   ... tn  ct   0 ]
+//     [ u1   u2   ... un   0  cu ]
+// where ct = 1/2^l and cu = q/2^l. The vector holding the b values, d*ct and cu is much shorter than the rest of the
+// lattice, and LLL finds it for us. Look for a row ending in cu (or -cu) and read d off the column next to it.
+// Implement LLL (the exact version, with rational numbers, so you don't get bitten by floating point), check it on
+// the small example bases, then use it to recover the key. About 20 signatures should be enough for a 128-bit q.
+#[test]
+pub fn challenge_62() {
+    let mut rng = Rng::new(62);
+    let group = WeierstrassGroup::from_spec(&curves::CHALLENGE_59);
+    let signer = BiasedEcdsaSigner::new(group.clone(), 8, &mut rng);
+
+    let signatures: Vec<(Vec<u8>, EcdsaSignature)> = (0..22)
+        .map(|i| {
+            let message = format!("message number {}", i).into_bytes();
+            let signature = signer.sign(&message, &mut rng);
+            (message, signature)
+        })
+        .collect();
+
+    let private_key = biased_nonce_attack(&group, signer.public_key(), &signatures, 8).unwrap();
+
+    let forged = ecdsa::sign(
+        &group,
+        &group.keypair_from_private(private_key),
+        b"a message the signer never saw",
+        &mut rng,
+    );
+    assert!(ecdsa::verify(
+        &group,
+        signer.public_key(),
+        b"a message the signer never saw",
+        &forged
+    ));
+}
+
+// Key-Recovery Attacks on GCM with Repeated Nonces
+// GCM is CTR mode plus a MAC called GHASH. GHASH works in GF(2^128), where the elements are polynomials over GF(2)
+// reduced mod x^128 + x^7 + x^2 + x + 1. Adding is XOR, and multiplying is carry-less multiplication followed by
+// reduction. Watch out for the bit order: GCM puts the coefficient of x^0 in the most significant bit of the first
+// byte.
+// The authentication key is h = E(K, 0^128). GHASH treats the associated data blocks, the ciphertext blocks and a
+// final block of lengths as coefficients c_i and computes
+//     g(h) = c_1*h^n + c_2*h^(n-1) + ... + c_n*h
+// The tag is t = g(h) + s, where s = E(K, nonce || 1).
+// Now suppose the nonce is repeated. Two messages give t1 = g1(h) + s and t2 = g2(h) + s, so h is a root of
+//     f(y) = g1(y) + t1 + g2(y) + t2
+// To find the roots, factor f: make it monic, take a square-free factorization, split that into products of
+// irreducibles of the same degree (distinct-degree factorization), and pull those apart with Cantor-Zassenhaus. The
+// linear factors y + c give the candidate keys c. Use a third message to narrow things down if there's more than one.
+// With h you can recover s from any message, and then forge a tag for any ciphertext under that nonce.
+#[test]
+pub fn challenge_63() {
+    let mut rng = Rng::new(63);
+    let key = rng.bytes(16);
+    let mut nonce = [0; gcm::NONCE_SIZE];
+    rng.fill_bytes(&mut nonce);
+
+    let plaintexts: [&[u8]; 3] = [
+        b"transfer 10 dollars to bob, keep the change",
+        b"lunch at noon?",
+        b"the quick brown fox jumps over the lazy dog, twice",
+    ];
+    let messages: Vec<GcmMessage> = plaintexts
+        .iter()
+        .map(|plaintext| {
+            let (ciphertext, tag) = gcm::gcm_encrypt(&key, &nonce, b"from=alice", plaintext);
+            GcmMessage {
+                associated_data: b"from=alice".to_vec(),
+                ciphertext,
+                tag,
+            }
+        })
+        .collect();
+
+    let h = recover_authentication_key(&messages, &mut rng).unwrap();
+    assert_eq!(h, gcm::authentication_key(&Aes::new(&key)));
+
+    // Flip "10" to "99" in the first message under the nose of its MAC
+    let mut ciphertext = messages[0].ciphertext.clone();
+    for (byte, difference) in ciphertext[9..11].iter_mut().zip(bytes_xor(b"10", b"99")) {
+        *byte ^= difference;
+    }
+    let tag = forge_tag(h, &messages[0], b"from=alice", &ciphertext);
+
+    assert_eq!(
+        gcm::gcm_decrypt(&key, &nonce, b"from=alice", &ciphertext, &tag).unwrap(),
+        b"transfer 99 dollars to bob, keep the change"
+    );
+}
+
+// Key-Recovery Attacks on GCM with a Truncated MAC
+// This time the nonce is never repeated, but the tag is truncated to 32 bits. A random forgery gets through with
+// probability 2^-32, and we can do a lot better than that.
+// Squaring is linear in GF(2^128), so for a 128x128 bit matrix Ms, y^2 = Ms*y. Multiplying by a constant c is linear
+// too: c*y = Mc*y. Only touch the ciphertext blocks that get multiplied by h^(2^i); call the differences d_i. The
+// change in the tag is then
+//     e = sum(d_i * h^(2^i)) = Ad*h   with   Ad = sum(M(d_i) * Ms^i)
+// and Ad depends linearly on the bits of the d_i. With a message of 2^17 blocks there are 17*128 bits to play with,
+// which is enough to force the first 16 rows of Ad to zero. Then the first 16 bits of the tag stay put no matter
+// what h is, and a forgery succeeds with probability 2^-16.
+// When one does, the other rows of Ad within the tag give linear equations in h. Collect them in a matrix K and
+// compute a basis X for its kernel: h = X*h' for some shorter h'. Now zero rows of Ad*X instead; each row has fewer
+// entries, so more rows can be zeroed, and the next forgery is cheaper. Keep going until K has rank 127, and h is the
+// one nonzero vector in its kernel.
+// The full-sized attack needs 2^16 forgery attempts on a 2 MB message before the first success, so the test uses a
+// 16-bit tag and 2^9-block messages; the algorithm is the same.
+#[test]
+pub fn challenge_64() {
+    let mut rng = Rng::new(64);
+    let key = rng.bytes(16);
+    let oracle = TruncatedGcmOracle::with_key(&key, 2);
+
+    let h = truncated_mac_attack(&oracle, 9, &mut rng).unwrap();
+
+    assert_eq!(h, gcm::authentication_key(&Aes::new(&key)));
+}
+
+#[test]
+#[ignore = "2^16 forgery attempts on a 2 MB message before the first success"]
+pub fn challenge_64_full() {
+    let mut rng = Rng::new(64);
+    let key = rng.bytes(16);
+    let oracle = TruncatedGcmOracle::with_key(&key, 4);
+
+    let h = truncated_mac_attack(&oracle, 17, &mut rng).unwrap();
+
+    assert_eq!(h, gcm::authentication_key(&Aes::new(&key)));
+}